@@ -0,0 +1,54 @@
+// Allocating subdivision against the buffer-reusing and streaming APIs on a long curve.
+// Run with `cargo bench`.
+use chaikin_animation::chaikin::Chaikin;
+use chaikin_animation::point::Point;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const CONTROL_POINTS: usize = 10_000;
+const LEVELS: usize = 8;
+
+// A zigzag that keeps every corner worth cutting
+fn zigzag() -> Vec<Point> {
+    (0..CONTROL_POINTS)
+        .map(|i| Point::new(i as f64 * 10.0, if i % 2 == 0 { 0.0 } else { 50.0 }))
+        .collect()
+}
+
+fn subdivision(c: &mut Criterion) {
+    let points = zigzag();
+    let chaikin = Chaikin::new(Vec::new());
+    let mut group = c.benchmark_group("chaikin");
+    group.sample_size(10);
+
+    // A new Vec for every level, as the animation used to do
+    group.bench_function("allocating", |b| {
+        b.iter(|| {
+            let mut level = points.clone();
+            for _ in 0..LEVELS {
+                level = chaikin.apply_chaikin(&level);
+            }
+            black_box(level)
+        })
+    });
+
+    // Two buffers swapped back and forth, kept between runs like a batch job would
+    let (mut buffer, mut scratch) = (Vec::new(), Vec::new());
+    group.bench_function("ping_pong", |b| {
+        b.iter(|| {
+            buffer.clear();
+            buffer.extend_from_slice(&points);
+            chaikin.subdivide_in_place(&mut buffer, &mut scratch, LEVELS);
+            black_box(buffer.len())
+        })
+    });
+
+    // No level is ever held in memory; summing the points stands in for writing them out
+    group.bench_function("stream", |b| {
+        b.iter(|| black_box(chaikin.stream(&points, LEVELS).map(|p| p.position.x).sum::<f64>()))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, subdivision);
+criterion_main!(benches);
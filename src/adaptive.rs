@@ -0,0 +1,122 @@
+// Adaptive Chaikin subdivision. Every corner of the control polygon is refined on its
+// own: Chaikin's cuts only look at a point and its two neighbours, so a corner and its
+// neighbours refine into two smaller corners without needing the rest of the curve.
+// Corners stop being refined once they are flat enough, so the result is a single
+// polyline whose points all lie on the uniform levels, dense only where the curve bends.
+use super::analysis::hausdorff_distance;
+use super::chaikin::Chaikin;
+use super::point::Point;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance {
+    // How far cutting a corner may move the curve before it has to be cut
+    pub deviation: f64,
+    // Largest turn at a corner left uncut, in radians
+    pub angle: f64,
+}
+
+impl Default for Tolerance {
+    // Half a pixel on screen, and corners of up to 10 degrees
+    fn default() -> Self {
+        Self { deviation: 0.5, angle: 10f64.to_radians() }
+    }
+}
+
+// An adaptive curve next to the uniform one it stands in for
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdaptiveReport<const D: usize = 2> {
+    pub curve: Vec<Point<D>>,
+    // Points of the uniform level at the same depth
+    pub uniform_points: usize,
+    // Hausdorff distance between the adaptive and the uniform curve
+    pub max_error: f64,
+}
+
+// Refine corners that turn or bulge more than `tolerance`, at most `max_depth` times.
+// Crease points stay sharp and are never refined, and open curves keep their ends.
+pub fn adaptive_chaikin<const D: usize>(points: &[Point<D>], closed: bool, tolerance: Tolerance, max_depth: usize) -> Vec<Point<D>> {
+    let n = points.len();
+    if n < 3 {
+        return points.to_vec();
+    }
+    // Windows of three points are cut as open polylines, whatever the curve is
+    let open = Chaikin::new(Vec::new());
+    let window = |i: usize| [points[(i + n - 1) % n].clone(), points[i].clone(), points[(i + 1) % n].clone()];
+    let mut curve = Vec::new();
+    if !closed {
+        curve.push(points[0].clone());
+        for i in 1..n - 1 {
+            refine_corner(&open, window(i), tolerance, max_depth, &mut curve);
+        }
+        curve.push(points[n - 1].clone());
+        return curve;
+    }
+    refine_corner(&open, window(0), tolerance, max_depth, &mut curve);
+    // Uniform closed curves start with the last point the first corner refines into
+    let first_corner = curve.len();
+    for i in 1..n {
+        refine_corner(&open, window(i), tolerance, max_depth, &mut curve);
+    }
+    curve.rotate_left(first_corner - 1);
+    curve
+}
+
+// The adaptive curve together with how it measures up against uniform subdivision
+pub fn compare_with_uniform<const D: usize>(points: &[Point<D>], closed: bool, tolerance: Tolerance, depth: usize) -> AdaptiveReport<D> {
+    let curve = adaptive_chaikin(points, closed, tolerance, depth);
+    let mut chaikin = Chaikin::new(Vec::new());
+    chaikin.closed = closed;
+    let uniform: Vec<Point<D>> = chaikin.stream(points, depth).collect();
+    let closed = closed && points.len() >= 3;
+    let max_error = hausdorff_distance(&curve, closed, &uniform, closed);
+    AdaptiveReport { curve, uniform_points: uniform.len(), max_error }
+}
+
+// Emit the corner `window[1]`, or refine it into the corners Chaikin's step makes of it
+fn refine_corner<const D: usize>(
+    chaikin: &Chaikin<D>,
+    window: [Point<D>; 3],
+    tolerance: Tolerance,
+    depth: usize,
+    curve: &mut Vec<Point<D>>,
+) {
+    match split_corner(chaikin, window.clone(), tolerance).filter(|_| depth > 0) {
+        Some((before, after)) => {
+            refine_corner(chaikin, before, tolerance, depth - 1, curve);
+            refine_corner(chaikin, after, tolerance, depth - 1, curve);
+        }
+        None => {
+            let [_, corner, _] = window;
+            curve.push(corner);
+        }
+    }
+}
+
+// The two corners one Chaikin step makes of the corner `window[1]`: the cuts on either
+// side of it, each with its neighbours on the next level. None if the corner is a crease
+// or flat enough to leave as it is.
+fn split_corner<const D: usize>(chaikin: &Chaikin<D>, window: [Point<D>; 3], tolerance: Tolerance) -> Option<([Point<D>; 3], [Point<D>; 3])> {
+    let [prev, corner, next] = window;
+    if corner.crease {
+        return None;
+    }
+    let cuts = chaikin.cut_points(&[prev.clone(), corner.clone(), next.clone()]);
+    let ((before, a), (b, after)) = (cuts[0].clone(), cuts[1].clone());
+    if !needs_cut(&prev, &corner, &next, &a, &b, tolerance) {
+        return None;
+    }
+    Some(([before, a.clone(), b.clone()], [a, b, after]))
+}
+
+// A corner needs cutting if it turns too sharply or if the cut from `a` to `b` would move
+// the curve too far from it
+fn needs_cut<const D: usize>(prev: &Point<D>, corner: &Point<D>, next: &Point<D>, a: &Point<D>, b: &Point<D>, tolerance: Tolerance) -> bool {
+    let (incoming, outgoing) = (corner.position - prev.position, next.position - corner.position);
+    let lengths = incoming.norm() * outgoing.norm();
+    let turn = if lengths > 0.0 { (incoming.dot(&outgoing) / lengths).clamp(-1.0, 1.0).acos() } else { 0.0 };
+    let chord = b.position - a.position;
+    let length_sq = chord.norm_squared();
+    let t = if length_sq > 0.0 { ((corner.position - a.position).dot(&chord) / length_sq).clamp(0.0, 1.0) } else { 0.0 };
+    let deviation = (a.position + chord * t - corner.position).norm();
+    turn > tolerance.angle || deviation > tolerance.deviation
+}
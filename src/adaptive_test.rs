@@ -0,0 +1,93 @@
+use crate::adaptive::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chaikin::Chaikin;
+    use crate::point::Point;
+    use crate::subdivider::Subdivider;
+    use nalgebra::Vector3;
+
+    // A long, nearly straight run with one sharp corner at the end
+    fn hook() -> Vec<Point> {
+        [(0.0, 0.0), (200.0, 2.0), (400.0, 0.0), (600.0, 3.0), (650.0, 200.0)]
+            .iter()
+            .map(|&(x, y)| Point::new(x, y))
+            .collect()
+    }
+
+    fn uniform(points: &[Point], closed: bool, depth: usize) -> Vec<Point> {
+        let mut chaikin = Chaikin::new(Vec::new());
+        chaikin.closed = closed;
+        chaikin.subdivide_times(&points.to_vec(), depth)
+    }
+
+    #[test]
+    fn test_points_come_from_the_uniform_levels() {
+        let points = hook();
+        let curve = adaptive_chaikin(&points, false, Tolerance::default(), 4);
+        let levels: Vec<Vec<Point>> = (0..=4).map(|depth| uniform(&points, false, depth)).collect();
+        assert_eq!(curve.first(), points.first());
+        assert_eq!(curve.last(), points.last());
+        for p in &curve {
+            assert!(levels.iter().any(|level| level.iter().any(|q| (q.position - p.position).norm() < 1e-9)));
+        }
+    }
+
+    #[test]
+    fn test_zero_tolerance_matches_uniform_subdivision() {
+        let tolerance = Tolerance { deviation: 0.0, angle: 0.0 };
+        for closed in [false, true] {
+            let points = hook();
+            let full = uniform(&points, closed, 3);
+            let curve = adaptive_chaikin(&points, closed, tolerance, 3);
+            // Only the collinear cuts next to open ends are left out
+            let report = compare_with_uniform(&points, closed, tolerance, 3);
+            assert!(report.max_error < 1e-9);
+            assert!(curve.len() <= full.len());
+            assert_eq!(report.uniform_points, full.len());
+            if closed {
+                assert_eq!(curve, full);
+            }
+        }
+    }
+
+    #[test]
+    fn test_flat_stretches_use_far_fewer_points() {
+        let report = compare_with_uniform(&hook(), false, Tolerance::default(), 8);
+        assert!(report.curve.len() * 4 < report.uniform_points, "{} vs {}", report.curve.len(), report.uniform_points);
+        assert!(report.max_error < 1.0, "max error {}", report.max_error);
+
+        // Most of the points go to the sharp corner at the end
+        let near_corner = report.curve.iter().filter(|p| p.position.x > 550.0).count();
+        assert!(near_corner * 2 > report.curve.len());
+
+        // A looser tolerance needs fewer points still
+        let loose = Tolerance { deviation: 4.0, angle: 30f64.to_radians() };
+        assert!(adaptive_chaikin(&hook(), false, loose, 8).len() < report.curve.len());
+    }
+
+    #[test]
+    fn test_creases_and_short_inputs() {
+        let mut points = hook();
+        points[3].crease = true;
+        let curve = adaptive_chaikin(&points, false, Tolerance::default(), 6);
+        assert!(curve.iter().any(|p| p.position == points[3].position));
+
+        let two = vec![Point::new(0.0, 0.0), Point::new(5.0, 0.0)];
+        assert_eq!(adaptive_chaikin(&two, true, Tolerance::default(), 5), two);
+    }
+
+    #[test]
+    fn test_works_in_three_dimensions() {
+        let helix: Vec<Point<3>> = (0..12)
+            .map(|i| {
+                let angle = i as f64 * 0.8;
+                Point::from_vector(Vector3::new(100.0 * angle.cos(), 100.0 * angle.sin(), i as f64 * 20.0))
+            })
+            .collect();
+        let report = compare_with_uniform(&helix, false, Tolerance::default(), 7);
+        assert!(report.curve.len() < report.uniform_points);
+        assert!(report.max_error < 1.0);
+    }
+}
@@ -0,0 +1,50 @@
+// Measurements on curves in any number of dimensions. Curves are polylines through their
+// points, with a closing edge back to the start when `closed` is set.
+use nalgebra::SVector;
+use super::point::Point;
+
+// Index pairs of a polyline's edges
+fn edges(len: usize, closed: bool) -> impl Iterator<Item = (usize, usize)> {
+    let closing = (closed && len > 2).then(|| (len - 1, 0));
+    (1..len).map(|i| (i - 1, i)).chain(closing)
+}
+
+pub(crate) fn distance_to_segment<const D: usize>(a: &SVector<f64, D>, b: &SVector<f64, D>, p: &SVector<f64, D>) -> f64 {
+    let ab = b - a;
+    let length_sq = ab.norm_squared();
+    let t = if length_sq > 0.0 { ((p - a).dot(&ab) / length_sq).clamp(0.0, 1.0) } else { 0.0 };
+    (a + ab * t - p).norm()
+}
+
+pub fn curve_length<const D: usize>(points: &[Point<D>], closed: bool) -> f64 {
+    edges(points.len(), closed)
+        .map(|(i, j)| (points[j].position - points[i].position).norm())
+        .sum()
+}
+
+// Distance from `p` to the nearest point on the curve, or f64::MAX for an empty curve
+pub fn distance_to_curve<const D: usize>(points: &[Point<D>], closed: bool, p: &SVector<f64, D>) -> f64 {
+    let nearest_point = points.iter().map(|q| (q.position - p).norm()).fold(f64::MAX, f64::min);
+    edges(points.len(), closed)
+        .map(|(i, j)| distance_to_segment(&points[i].position, &points[j].position, p))
+        .fold(nearest_point, f64::min)
+}
+
+// Average distance from the points of `from` to the curve `to`
+pub fn mean_distance<const D: usize>(from: &[Point<D>], to: &[Point<D>], to_closed: bool) -> f64 {
+    if from.is_empty() || to.is_empty() {
+        return 0.0;
+    }
+    from.iter().map(|p| distance_to_curve(to, to_closed, &p.position)).sum::<f64>() / from.len() as f64
+}
+
+// Largest distance from a point of either curve to the other curve (Hausdorff distance,
+// measured at the curves' points)
+pub fn hausdorff_distance<const D: usize>(a: &[Point<D>], a_closed: bool, b: &[Point<D>], b_closed: bool) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let a_to_b = a.iter().map(|p| distance_to_curve(b, b_closed, &p.position));
+    let b_to_a = b.iter().map(|p| distance_to_curve(a, a_closed, &p.position));
+    a_to_b.chain(b_to_a).fold(0.0, f64::max)
+}
@@ -0,0 +1,53 @@
+use crate::analysis::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+    use nalgebra::{Vector2, Vector3};
+
+    fn square() -> Vec<Point> {
+        vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0), Point::new(10.0, 10.0), Point::new(0.0, 10.0)]
+    }
+
+    fn point3(x: f64, y: f64, z: f64) -> Point<3> {
+        Point::from_vector(Vector3::new(x, y, z))
+    }
+
+    #[test]
+    fn test_curve_length_open_and_closed() {
+        assert_eq!(curve_length(&square(), false), 30.0);
+        assert_eq!(curve_length(&square(), true), 40.0);
+        // Two points never get a closing edge
+        assert_eq!(curve_length(&square()[..2], true), 10.0);
+        assert_eq!(curve_length::<2>(&[], true), 0.0);
+    }
+
+    #[test]
+    fn test_distance_to_curve() {
+        let p = Vector2::new(-3.0, 5.0);
+        assert_eq!(distance_to_curve(&square(), false, &p), 5.0f64.hypot(3.0));
+        assert_eq!(distance_to_curve(&square(), true, &p), 3.0);
+        assert_eq!(distance_to_curve(&square()[..1], false, &p), 34.0f64.sqrt());
+        assert_eq!(distance_to_curve::<2>(&[], false, &p), f64::MAX);
+    }
+
+    #[test]
+    fn test_mean_and_hausdorff_distance() {
+        let shifted: Vec<Point> = square().iter().map(|p| Point::new(p.position.x, p.position.y + 2.0)).collect();
+        assert_eq!(hausdorff_distance(&square(), true, &square(), true), 0.0);
+        assert_eq!(hausdorff_distance(&shifted, true, &square(), true), 2.0);
+        // The shifted points on the vertical edges lie on the square's outline
+        assert_eq!(mean_distance(&shifted, &square(), true), 1.0);
+        assert_eq!(mean_distance(&[], &square(), true), 0.0);
+    }
+
+    #[test]
+    fn test_measures_work_in_three_dimensions() {
+        let helix = vec![point3(0.0, 0.0, 0.0), point3(3.0, 0.0, 4.0), point3(3.0, 12.0, 4.0)];
+        assert_eq!(curve_length(&helix, false), 17.0);
+        assert_eq!(distance_to_curve(&helix, false, &Vector3::new(3.0, 6.0, 0.0)), 4.0);
+        let lifted: Vec<Point<3>> = helix.iter().map(|p| Point::from_vector(p.position + Vector3::new(0.0, 0.0, 1.0))).collect();
+        assert!(hausdorff_distance(&lifted, false, &helix, false) <= 1.0);
+    }
+}
@@ -1,18 +1,51 @@
 use minifb::{Window, WindowOptions};
+use nalgebra::Vector2;
 use std::time::{Duration, Instant};
+use super::frame::{positions, Frame};
 use super::point::Point;
-use super::chaikin::Chaikin;
-use super::input::InputHandler;
+use super::chaikin::{MAX_SPEED, MAX_STEPS, MIN_SPEED, MIN_STEPS};
+use super::compare::{Comparison, MAX_RATIO, MIN_RATIO};
+use super::adaptive::{compare_with_uniform, AdaptiveReport, Tolerance};
+use super::font::BitmapFont;
+use super::input::{FillMode, InputHandler, HELP_ROWS_PER_PAGE, KEY_BINDINGS};
+use super::raster::{blend_color, pack_color, unpack_color, FillRule, Paint, Raster};
+use super::scene::PAINT_COLORS;
+use super::scheme::Scheme;
+use super::selection::{handles, Handle};
+use super::stroke::{stroke_polyline, LineCap, StrokeStyle};
+use super::theme::Theme;
+use super::ui::{Ui, UiInput};
 
 pub struct Canvas {
     window: Window,
     buffer: Vec<u32>,
-    chaikin: Chaikin,
+    width: usize,
+    height: usize,
     last_frame_time: Instant,
     frame_duration: Duration,
     empty_points_message: Option<(String, Instant)>,
+    antialias: bool,
+    curve_stroke: StrokeStyle,
+    polygon_stroke: StrokeStyle,
+    fill_mode: FillMode,
+    fill_rule: FillRule,
+    font: BitmapFont,
+    fps: f64,
+    theme: Theme,
+    // Adaptive curve of the selected curve and what it was made from. Comparing it against
+    // the uniform curve is too slow for every frame.
+    adaptive: Option<(AdaptiveKey, AdaptiveReport)>,
+    // Points of the level being edited and the finished still curve, rebuilt only when
+    // what they were made from changes
+    level_points: Option<(LevelKey, Vec<Point>, Option<Vec<Point>>)>,
 }
 
+// Control points, closed and depth of an adaptive curve
+type AdaptiveKey = (Vec<Point>, bool, usize);
+
+// Control points, closed, scheme, detail, edited level and finished level of a curve
+type LevelKey = (Vec<Point>, bool, Scheme, Vec<Vec<Vector2<f64>>>, usize, Option<usize>);
+
 impl Canvas {
     pub fn new(width: usize, height: usize) -> Self {
         println!("Creating window with dimensions {}x{}", width, height);
@@ -33,17 +66,27 @@ impl Canvas {
         ).expect("Failed to create window");
 
         let buffer = vec![0; width * height];
-        let chaikin = Chaikin::new(Vec::new());
         
         println!("Window created successfully");
         
         Self {
             window,
             buffer,
-            chaikin,
+            width,
+            height,
             last_frame_time: Instant::now(),
             frame_duration: Duration::from_millis(16),
             empty_points_message: None,
+            antialias: false,
+            curve_stroke: StrokeStyle::default(),
+            polygon_stroke: StrokeStyle::default(),
+            fill_mode: FillMode::Off,
+            fill_rule: FillRule::NonZero,
+            font: BitmapFont::builtin(),
+            fps: 0.0,
+            theme: Theme::default(),
+            adaptive: None,
+            level_points: None,
         }
     }
 
@@ -58,39 +101,63 @@ impl Canvas {
         if elapsed < self.frame_duration {
             std::thread::sleep(self.frame_duration - elapsed);
         }
+        let frame_time = (Instant::now() - self.last_frame_time).as_secs_f64();
         self.last_frame_time = Instant::now();
+        if frame_time > 0.0 {
+            // Exponential moving average keeps the readout steady
+            self.fps = if self.fps == 0.0 { 1.0 / frame_time } else { 0.9 * self.fps + 0.1 / frame_time };
+        }
     
         input.handle_input(&mut self.window);
     
-        self.buffer.fill(0);
-    
-        // Convert points to Point structs
-        let points: Vec<Point> = input
-            .points()
-            .iter()
-            .map(|&(x, y)| Point::new(x, y))
-            .collect();
+        if self.theme != *input.theme() {
+            self.theme = input.theme().clone();
+        }
+        self.buffer.fill(pack_color(self.theme.background));
+        self.antialias = input.antialias;
+        self.curve_stroke = input.curve_stroke;
+        self.polygon_stroke = input.polygon_stroke;
+        self.fill_mode = input.fill_mode;
+        self.fill_rule = input.fill_rule;
+
+        if input.grid.visible {
+            self.draw_grid(input.grid.spacing);
+        }
     
         // Check if Enter is pressed with no points
-        if self.window.is_key_down(minifb::Key::Enter) && points.is_empty() {
+        if self.window.is_key_down(minifb::Key::Enter) && !input.scene.has_points() {
             self.empty_points_message = Some((
                 "Please draw some points before pressing Enter".to_string(),
                 Instant::now()
             ));
         }
     
-        // Update Chaikin points if animating
-        if input.is_animating() && !points.is_empty() {
-            self.chaikin.set_points(points.clone());
-            let animated_points = self.chaikin.step();
-            self.draw_animated_curve(&animated_points);
+        // Simplifying the stroke is the slow part of sketching, so it is done once a frame
+        let sketch = input.sketch_preview();
+        if input.comparison.is_on() {
+            self.draw_comparison(input, frame_time);
         } else {
-            self.draw_points(&points);
+            self.draw_scene(input);
+            self.draw_selection(input);
+            self.draw_sketch(input, sketch.as_deref());
+            if input.edit_level > 0 {
+                self.draw_level_points(input);
+            }
+            if input.adaptive {
+                self.draw_adaptive(input);
+            }
+            if let Some(idx) = input.dragging_point() {
+                let (x, y) = input.points()[idx];
+                self.draw_point(x, y, self.theme.drag_point, 8.0);
+            }
         }
     
-        if let Some(idx) = input.dragging_point() {
-            let (x, y) = input.points()[idx];
-            self.draw_point(x as f64, y as f64, [255, 0, 0], 8.0);
+        if input.show_hud {
+            self.draw_hud(input, sketch.as_deref());
+        }
+        self.draw_settings_panel(input);
+        if input.show_help {
+            self.draw_help(input.help_page);
         }
     
         // Store the message in a local variable to avoid borrowing issues
@@ -115,41 +182,129 @@ impl Canvas {
         }
     
         self.window
-            .update_with_buffer(&self.buffer, 800, 600)
+            .update_with_buffer(&self.buffer, self.width, self.height)
             .map_err(|e| format!("Failed to update window: {}", e))
     }
 
+    // Each curve animates on its own; the rest just show their control points
+    fn draw_scene(&mut self, input: &mut InputHandler) {
+        let selected = input.scene.selected;
+        for (i, curve) in input.scene.curves.iter_mut().enumerate() {
+            if curve.animating && !curve.points.is_empty() {
+                let frame = curve.step(input.max_steps, input.animation_speed, input.overlays);
+                self.draw_animated_curve(&frame, i == selected);
+            } else {
+                self.draw_points(&curve.control_points(), i == selected);
+            }
+            for (idx, &(x, y)) in curve.points.iter().enumerate() {
+                if curve.attributes(idx).crease {
+                    self.draw_crease_marker(x, y);
+                }
+            }
+        }
+    }
+
+    // The selected curve in two or four viewports, each with its configuration, level
+    // and how far its finished curve strays from viewport A's
+    fn draw_comparison(&mut self, input: &mut InputHandler, dt: f64) {
+        let comparison = &mut input.comparison;
+        comparison.set_source(&input.scene.selected().control_points());
+        if input.ui.active.is_none() {
+            comparison.update_metrics();
+        }
+        comparison.advance(dt, input.animation_speed);
+        let comparison = &input.comparison;
+        let frames = comparison.frames();
+
+        for (i, frame) in frames.into_iter().enumerate() {
+            let to_viewport = |points: &[Point]| -> Vec<Point> {
+                points
+                    .iter()
+                    .map(|p| {
+                        let (x, y) = comparison.to_viewport(i, (p.position.x, p.position.y));
+                        Point { position: Vector2::new(x, y), ..p.clone() }
+                    })
+                    .collect()
+            };
+            let control = to_viewport(frame.control_polygon);
+            let curve = to_viewport(frame.curve);
+            let scaled = Frame { control_polygon: &control, curve: &curve, ..frame };
+            self.draw_animated_curve(&scaled, true);
+
+            let config = comparison.configs[i];
+            let metrics = comparison.metrics().get(i).copied().unwrap_or_default();
+            let mut lines = vec![
+                format!("{}  {}", Comparison::name(i), config.label()),
+                format!("Step {}/{}  Points {}  Length {:.0}", frame.info.step, config.steps, metrics.points, metrics.length),
+            ];
+            if i == 0 {
+                lines.push("Reference".to_string());
+            } else {
+                lines.push(format!("Deviation max {:.1}  mean {:.1}", metrics.max_deviation, metrics.mean_deviation));
+            }
+            self.draw_viewport_frame(comparison.viewport(i), &lines, i == comparison.active);
+        }
+    }
+
+    // Viewport border with its caption in the bottom left corner. The active viewport
+    // gets a double border.
+    fn draw_viewport_frame(&mut self, (x, y, w, h): (f64, f64, f64, f64), lines: &[String], active: bool) {
+        let line_height = self.font.line_height(1) + 2;
+        let text_width = lines.iter().map(|l| self.font.text_width(l, 1)).max().unwrap_or(0) + 12;
+        let text_height = lines.len() * line_height + 8;
+        // The label sits at the bottom of the viewport, and is left out if it won't fit
+        let top = ((y + h) as usize).checked_sub(text_height + 4).filter(|&top| top >= y as usize);
+        if let Some(top) = top {
+            let background = self.theme.hud_background;
+            self.raster().fill_rect(x as i32 + 4, top as i32, text_width as i32, text_height as i32, background, 0.6);
+            for (i, line) in lines.iter().enumerate() {
+                self.draw_text_string(x as usize + 10, top + 4 + i * line_height, line, self.theme.hud_text);
+            }
+        }
+
+        let (a, b) = ((x, y), (x + w - 1.0, y + h - 1.0));
+        if active {
+            let color = self.theme.selection;
+            self.draw_rect_outline(a, b, color);
+            self.draw_rect_outline((a.0 + 2.0, a.1 + 2.0), (b.0 - 2.0, b.1 - 2.0), color);
+        } else {
+            let color = self.theme.selection_box;
+            self.draw_rect_outline(a, b, color);
+        }
+    }
+
     fn draw_message(&mut self, message: &str) {
         // Calculate dimensions for background
         let width = self.window.get_size().0;
         let height = self.window.get_size().1;
-        let lines: Vec<&str> = message.lines().collect();
+        let lines = self.font.wrap(message, width - 30, 1);
         let line_count = lines.len();
+        let line_height = self.font.line_height(1) + 6;
         
-        // Find the longest line to determine background width
-        let max_len = lines.iter().map(|line| line.len()).max().unwrap_or(0);
-        let bg_width = (max_len * 10).min(width - 20);
-        let bg_height = line_count * 25 + 20;
+        // Size the background to the widest wrapped line
+        let (text_width, _) = self.font.measure(&lines.join("\n"), 1);
+        let bg_width = (text_width + 10).min(width - 20);
+        let bg_height = line_count * line_height + 20;
         
         // Draw semi-transparent background rectangle
         let bg_y = height - bg_height - 10;
         for y in bg_y..bg_y + bg_height {
             for x in 5..5 + bg_width {
                 if x < width && y < height {
-                    let idx = (y * width + x) as usize;
+                    let idx = y * width + x;
                     if idx < self.buffer.len() {
-                        self.buffer[idx] = 0x202020;
+                        self.buffer[idx] = pack_color(self.theme.message_background);
                     }
                 }
             }
         }
         
         // Draw a border around the rectangle
-        let border_color = 0xFFFFFF;
+        let border_color = pack_color(self.theme.text);
         
         // Top border
         for x in 5..5 + bg_width {
-            let idx = (bg_y * width + x) as usize;
+            let idx = bg_y * width + x;
             if idx < self.buffer.len() {
                 self.buffer[idx] = border_color;
             }
@@ -157,7 +312,7 @@ impl Canvas {
         
         // Bottom border
         for x in 5..5 + bg_width {
-            let idx = ((bg_y + bg_height - 1) * width + x) as usize;
+            let idx = (bg_y + bg_height - 1) * width + x;
             if idx < self.buffer.len() {
                 self.buffer[idx] = border_color;
             }
@@ -165,7 +320,7 @@ impl Canvas {
         
         // Left border
         for y in bg_y..bg_y + bg_height {
-            let idx = (y * width + 5) as usize;
+            let idx = y * width + 5;
             if idx < self.buffer.len() {
                 self.buffer[idx] = border_color;
             }
@@ -173,828 +328,533 @@ impl Canvas {
         
         // Right border
         for y in bg_y..bg_y + bg_height {
-            let idx = (y * width + 5 + bg_width - 1) as usize;
+            let idx = y * width + 5 + bg_width - 1;
             if idx < self.buffer.len() {
                 self.buffer[idx] = border_color;
             }
         }
         
         // Draw the text
-        let mut y = bg_y + 15;
+        let mut y = bg_y + 10;
         
-        for line in lines {
-            self.draw_text_string(10, y, line, 0xFFFFFF);
-            y += 25; 
+        for line in &lines {
+            self.draw_text_string(10, y, line, self.theme.text);
+            y += line_height;
         }
     }
 
-    fn draw_text_string(&mut self, x: usize, y: usize, text: &str, color: u32) {
-        let mut pos_x = x;
-        
-        for c in text.chars() {
-            self.draw_large_char(pos_x, y, c, color);
-            pos_x += 10; 
+    fn draw_hud(&mut self, input: &InputHandler, sketch: Option<&[(f64, f64)]>) {
+        let curve = input.scene.selected();
+        let (step, max_steps, level_points) = if curve.animating {
+            (curve.chaikin.current_step, curve.chaikin.max_steps, curve.chaikin.current_points.len())
+        } else {
+            (0, input.max_steps, curve.points.len())
+        };
+        let mode = if curve.closed { "closed" } else { "open" };
+        let color_name = self.theme.curve_name(curve.color).unwrap_or("custom");
+        let mut lines = vec![
+            format!(
+                "Curve {}/{} ({})  {}",
+                input.scene.selected + 1,
+                input.scene.curves.len(),
+                color_name,
+                if input.is_animating() { "playing" } else { "editing" }
+            ),
+            format!("Step {}/{}  Points {}", step, max_steps, level_points),
+            format!("Speed {:.2}x  FPS {:.0}", input.animation_speed, self.fps),
+            format!("Cursor {:.0}, {:.0}", input.mouse_pos.0, input.mouse_pos.1),
+            format!("{}  {}", mode, curve.scheme.name()),
+        ];
+        let adaptive_shown = input.adaptive && !curve.animating && curve.points.len() >= 3;
+        if let (true, Some((_, report))) = (adaptive_shown, &self.adaptive) {
+            lines.push(format!(
+                "Adaptive {} points, uniform {}, error {:.2} px",
+                report.curve.len(),
+                report.uniform_points,
+                report.max_error
+            ));
+        }
+        if input.edit_level > 0 {
+            lines.push(format!("Editing level {} (Shift+Up/Down)", input.edit_level));
+        }
+        if input.sketch_mode {
+            let stroke = match (&input.sketch_stroke, sketch) {
+                (Some(stroke), Some(polygon)) => format!("  {} -> {} points", stroke.len(), polygon.len()),
+                _ => String::new(),
+            };
+            lines.push(format!("Sketch {} {:.1} px{}", input.simplifier.name(), input.sketch_tolerance, stroke));
+        }
+        if let Some(idx) = input.find_nearest_point() {
+            let attributes = curve.attributes(idx);
+            let sharp = if attributes.crease { "  sharp" } else { "" };
+            lines.push(format!("Point {}  weight {:.2}{}", idx + 1, attributes.weight, sharp));
+        }
+        lines.push("F1 help".to_string());
+
+        let line_height = self.font.line_height(1) + 2;
+        let width = lines.iter().map(|l| self.font.text_width(l, 1)).max().unwrap_or(0) + 12;
+        let height = lines.len() * line_height + 8;
+        let background = self.theme.hud_background;
+        self.raster().fill_rect(4, 4, width as i32, height as i32, background, 0.6);
+
+        for (i, line) in lines.iter().enumerate() {
+            self.draw_text_string(10, 8 + i * line_height, line, self.theme.hud_text);
         }
     }
-    
-    fn draw_large_char(&mut self, x: usize, y: usize, c: char, color: u32) {
-        // Simplified larger characters using blocks instead of bitmaps
-        match c {
-            'A'..='Z' | 'a'..='z' | '0'..='9' | '.' | ',' | '!' | '?' | ':' | ';' | '(' | ')' | ' ' => {
-                self.draw_block_char(x, y, c, color);
-            },
-            _ => {
-                self.draw_block_char(x, y, '#', color);
+
+    fn draw_settings_panel(&mut self, input: &mut InputHandler) {
+        let ui_input = UiInput {
+            mouse_pos: input.mouse_pos,
+            mouse_down: input.mouse_down,
+            mouse_pressed: input.mouse_pressed,
+        };
+        let raster = Raster::new(&mut self.buffer, self.width, self.height);
+        let mut ui = Ui::new(raster, &self.font, ui_input, &mut input.ui);
+        ui.set_colors(self.theme.ui);
+
+        let schemes = [
+            (Scheme::Chaikin, Scheme::Chaikin.name()),
+            (Scheme::CubicBSpline, Scheme::CubicBSpline.name()),
+            (Scheme::FourPoint, Scheme::FourPoint.name()),
+        ];
+        let curve_label = format!("Curve {} of {}", input.scene.selected + 1, input.scene.curves.len());
+        let curve_colors = self.theme.curve_colors();
+        let theme_label = format!("Theme: {}", self.theme.name);
+
+        let mut start = false;
+        let mut new_curve = false;
+        let mut clear = false;
+        let mut next_theme = false;
+        // Curve edits are applied after the panel, through the input handler, so they
+        // can be undone
+        let curve = input.scene.selected();
+        let (mut scheme, mut closed, mut color) = (curve.scheme, curve.closed, curve.color);
+        let viewport_label = format!("Viewport {} (click to pick)", Comparison::name(input.comparison.active));
+        ui.side_panel("Settings", 200, |ui| {
+            if input.comparison.is_on() {
+                // Configuration of the active comparison viewport
+                let config = input.comparison.active_config_mut();
+                ui.label(&viewport_label);
+                ui.radio_group("Scheme", &schemes, &mut config.scheme);
+                ui.slider("Cut ratio", &mut config.ratio, MIN_RATIO, MAX_RATIO, 2);
+                ui.slider_usize("Levels", &mut config.steps, MIN_STEPS, MAX_STEPS);
+                ui.checkbox("Closed curve", &mut config.closed);
+            } else {
+                // Settings of the selected curve
+                ui.label(&curve_label);
+                ui.radio_group("Scheme", &schemes, &mut scheme);
+                ui.checkbox("Closed curve", &mut closed);
+                ui.checkbox("Animate this curve", &mut input.scene.selected_mut().animating);
+                ui.swatches("Curve colour", &curve_colors, &mut color);
+                ui.swatches("Paint colour (B)", PAINT_COLORS, &mut input.paint_color);
             }
+            ui.separator();
+            ui.slider_usize("Iterations", &mut input.max_steps, MIN_STEPS, MAX_STEPS);
+            ui.slider("Speed", &mut input.animation_speed, MIN_SPEED, MAX_SPEED, 1);
+            ui.checkbox("Ghost trail (O)", &mut input.overlays.trail);
+            ui.checkbox("Construction (I)", &mut input.overlays.construction);
+            ui.checkbox("Anti-aliasing", &mut input.antialias);
+            ui.checkbox("Grid", &mut input.grid.visible);
+            ui.checkbox("Heads-up display", &mut input.show_hud);
+            ui.checkbox("Freehand sketch (W)", &mut input.sketch_mode);
+            ui.checkbox("Adaptive curve (Z)", &mut input.adaptive);
+            next_theme = ui.button(&theme_label);
+            ui.separator();
+            start = ui.button("Animate all");
+            new_curve = ui.button("New curve");
+            clear = ui.button("Clear canvas");
+        });
+
+        input.set_scheme(scheme);
+        input.set_closed(closed);
+        input.set_curve_color(color);
+        if start {
+            input.animate_all();
+        }
+        if new_curve {
+            input.new_curve();
+        }
+        if clear {
+            input.clear_canvas();
+        }
+        if next_theme {
+            input.cycle_theme();
         }
     }
 
-    fn draw_block_char(&mut self, x_start: usize, y_start: usize, c: char, color: u32) {
-        let width = self.window.get_size().0;
-        
-        // Define character shapes using simple block patterns
-        // Each character is 8x12 pixels
-        
-        // Get the pattern for this character
-        let pattern = match c {
-            'P' => [
-                "  ████  ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " █████  ",
-                " ██     ",
-                " ██     ",
-                "        ",
-            ],
-            'l' => [
-                " ██     ",
-                " ██     ",
-                " ██     ",
-                " ██     ",
-                " ██     ",
-                " ██████ ",
-                "        ",
-            ],
-            'e' => [
-                "        ",
-                "  ████  ",
-                " ██  ██ ",
-                " ██████ ",
-                " ██     ",
-                "  ████  ",
-                "        ",
-            ],
-            'a' => [
-                "        ",
-                "  ████  ",
-                "     ██ ",
-                "  █████ ",
-                " ██  ██ ",
-                "  █████ ",
-                "        ",
-            ],
-            's' => [
-                "        ",
-                "  ████  ",
-                " ██     ",
-                "  ████  ",
-                "     ██ ",
-                " █████  ",
-                "        ",
-            ],
-            'd' => [
-                "     ██ ",
-                "     ██ ",
-                "  █████ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                "  █████ ",
-                "        ",
-            ],
-            'r' => [
-                "        ",
-                " ██ ██  ",
-                " ███    ",
-                " ██     ",
-                " ██     ",
-                " ██     ",
-                "        ",
-            ],
-            'w' => [
-                "        ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██████ ",
-                "  ██ ██ ",
-                "        ",
-            ],
-            'c' => [
-                "        ",
-                "  ████  ",
-                " ██     ",
-                " ██     ",
-                " ██     ",
-                "  ████  ",
-                "        ",
-            ],
-            'o' => [
-                "        ",
-                "  ████  ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                "  ████  ",
-                "        ",
-            ],
-            'n' => [
-                "        ",
-                " ██ ██  ",
-                " ███ ██ ",
-                " ██ ███ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                "        ",
-            ],
-            't' => [
-                "  ██    ",
-                "  ██    ",
-                " █████  ",
-                "  ██    ",
-                "  ██    ",
-                "   ███  ",
-                "        ",
-            ],
-            'i' => [
-                "  ██    ",
-                "        ",
-                " ███    ",
-                "  ██    ",
-                "  ██    ",
-                " ████   ",
-                "        ",
-            ],
-            'u' => [
-                "        ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                "  █████ ",
-                "        ",
-            ],
-            'E' => [
-                " ██████ ",
-                " ██     ",
-                " █████  ",
-                " ██     ",
-                " ██     ",
-                " ██████ ",
-                "        ",
-            ],
-            'f' => [
-                "   ███  ",
-                "  ██    ",
-                " █████  ",
-                "  ██    ",
-                "  ██    ",
-                "  ██    ",
-                "        ",
-            ],
-            'p' => [
-                "        ",
-                " █████  ",
-                " ██  ██ ",
-                " █████  ",
-                " ██     ",
-                " ██     ",
-                "        ",
-            ],
-            'y' => [
-                "        ",
-                " ██  ██ ",
-                " ██  ██ ",
-                "  █████ ",
-                "     ██ ",
-                " █████  ",
-                "        ",
-            ],
-            'b' => [
-                " ██     ",
-                " ██     ",
-                " █████  ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " █████  ",
-                "        ",
-            ],
-            'g' => [
-                "        ",
-                "  █████ ",
-                " ██  ██ ",
-                "  █████ ",
-                "     ██ ",
-                " █████  ",
-                "        ",
-            ],
-            'h' => [
-                " ██     ",
-                " ██     ",
-                " █████  ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                "        ",
-            ],
-            'j' => [
-                "    ██  ",
-                "        ",
-                "   ███  ",
-                "    ██  ",
-                "    ██  ",
-                " ████   ",
-                "        ",
-            ],
-            'k' => [
-                " ██     ",
-                " ██  ██ ",
-                " ██ ██  ",
-                " ████   ",
-                " ██ ██  ",
-                " ██  ██ ",
-                "        ",
-            ],
-            'm' => [
-                "        ",
-                " ██ ██  ",
-                " ██████ ",
-                " ██ ███ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                "        ",
-            ],
-            'q' => [
-                "        ",
-                "  █████ ",
-                " ██  ██ ",
-                "  █████ ",
-                "     ██ ",
-                "     ██ ",
-                "        ",
-            ],
-            'v' => [
-                "        ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                "  ████  ",
-                "   ██   ",
-                "        ",
-            ],
-            'x' => [
-                "        ",
-                " ██  ██ ",
-                "  ████  ",
-                "   ██   ",
-                "  ████  ",
-                " ██  ██ ",
-                "        ",
-            ],
-            'z' => [
-                "        ",
-                " ██████ ",
-                "    ██  ",
-                "   ██   ",
-                "  ██    ",
-                " ██████ ",
-                "        ",
-            ],
-            'A' => [
-                "  ████  ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██████ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                "        ",
-            ],
-            'B' => [
-                " █████  ",
-                " ██  ██ ",
-                " █████  ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " █████  ",
-                "        ",
-            ],
-            'C' => [
-                "  ████  ",
-                " ██  ██ ",
-                " ██     ",
-                " ██     ",
-                " ██  ██ ",
-                "  ████  ",
-                "        ",
-            ],
-            'D' => [
-                " █████  ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " █████  ",
-                "        ",
-            ],
-            'F' => [
-                " ██████ ",
-                " ██     ",
-                " █████  ",
-                " ██     ",
-                " ██     ",
-                " ██     ",
-                "        ",
-            ],
-            'G' => [
-                "  ████  ",
-                " ██  ██ ",
-                " ██     ",
-                " ██ ███ ",
-                " ██  ██ ",
-                "  █████ ",
-                "        ",
-            ],
-            'H' => [
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██████ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                "        ",
-            ],
-            'I' => [
-                " ██████ ",
-                "   ██   ",
-                "   ██   ",
-                "   ██   ",
-                "   ██   ",
-                " ██████ ",
-                "        ",
-            ],
-            'J' => [
-                "     ██ ",
-                "     ██ ",
-                "     ██ ",
-                "     ██ ",
-                " ██  ██ ",
-                "  ████  ",
-                "        ",
-            ],
-            'K' => [
-                " ██  ██ ",
-                " ██ ██  ",
-                " ████   ",
-                " ████   ",
-                " ██ ██  ",
-                " ██  ██ ",
-                "        ",
-            ],
-            'L' => [
-                " ██     ",
-                " ██     ",
-                " ██     ",
-                " ██     ",
-                " ██     ",
-                " ██████ ",
-                "        ",
-            ],
-            'M' => [
-                " ██   ██",
-                " ███ ███",
-                " ███████",
-                " ██ █ ██",
-                " ██   ██",
-                " ██   ██",
-                "        ",
-            ],
-            'N' => [
-                " ██  ██ ",
-                " ███ ██ ",
-                " ██████ ",
-                " ██████ ",
-                " ██ ███ ",
-                " ██  ██ ",
-                "        ",
-            ],
-            'O' => [
-                "  ████  ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                "  ████  ",
-                "        ",
-            ],
-            'Q' => [
-                "  ████  ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██ ██  ",
-                "  ██ ██ ",
-                "        ",
-            ],
-            'R' => [
-                " █████  ",
-                " ██  ██ ",
-                " █████  ",
-                " ████   ",
-                " ██ ██  ",
-                " ██  ██ ",
-                "        ",
-            ],
-            'S' => [
-                "  █████ ",
-                " ██     ",
-                "  ████  ",
-                "     ██ ",
-                " ██  ██ ",
-                "  ████  ",
-                "        ",
-            ],
-            'T' => [
-                "██████  ",
-                "  ██    ",
-                "  ██    ",
-                "  ██    ",
-                "  ██    ",
-                "  ██    ",
-                "        ",
-            ],
-            'U' => [
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                "  ████  ",
-                "        ",
-            ],
-            'V' => [
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                "  ████  ",
-                "   ██   ",
-                "        ",
-            ],
-            'W' => [
-                " ██   ██",
-                " ██   ██",
-                " ██ █ ██",
-                " ███████",
-                " ███ ███",
-                " ██   ██",
-                "        ",
-            ],
-            'X' => [
-                " ██  ██ ",
-                " ██  ██ ",
-                "  ████  ",
-                "  ████  ",
-                " ██  ██ ",
-                " ██  ██ ",
-                "        ",
-            ],
-            'Y' => [
-                " ██  ██ ",
-                " ██  ██ ",
-                "  ████  ",
-                "   ██   ",
-                "   ██   ",
-                "   ██   ",
-                "        ",
-            ],
-            'Z' => [
-                " ██████ ",
-                "    ██  ",
-                "   ██   ",
-                "  ██    ",
-                " ██     ",
-                " ██████ ",
-                "        ",
-            ],
-            '0' => [
-                "  ████  ",
-                " ██  ██ ",
-                " ██ ███ ",
-                " ███ ██ ",
-                " ██  ██ ",
-                "  ████  ",
-                "        ",
-            ],
-            '1' => [
-                "   ██   ",
-                "  ███   ",
-                " ████   ",
-                "   ██   ",
-                "   ██   ",
-                " ██████ ",
-                "        ",
-            ],
-            '2' => [
-                "  ████  ",
-                " ██  ██ ",
-                "    ██  ",
-                "   ██   ",
-                "  ██    ",
-                " ██████ ",
-                "        ",
-            ],
-            '3' => [
-                "  ████  ",
-                " ██  ██ ",
-                "    ██  ",
-                "   ███  ",
-                " ██  ██ ",
-                "  ████  ",
-                "        ",
-            ],
-            '4' => [
-                "    ██  ",
-                "   ███  ",
-                "  ████  ",
-                " ██ ██  ",
-                " ██████ ",
-                "    ██  ",
-                "        ",
-            ],
-            '5' => [
-                " ██████ ",
-                " ██     ",
-                " █████  ",
-                "     ██ ",
-                " ██  ██ ",
-                "  ████  ",
-                "        ",
-            ],
-            '6' => [
-                "  ████  ",
-                " ██     ",
-                " █████  ",
-                " ██  ██ ",
-                " ██  ██ ",
-                "  ████  ",
-                "        ",
-            ],
-            '7' => [
-                " ██████ ",
-                "     ██ ",
-                "    ██  ",
-                "   ██   ",
-                "  ██    ",
-                " ██     ",
-                "        ",
-            ],
-            '8' => [
-                "  ████  ",
-                " ██  ██ ",
-                "  ████  ",
-                "  ████  ",
-                " ██  ██ ",
-                "  ████  ",
-                "        ",
-            ],
-            '9' => [
-                "  ████  ",
-                " ██  ██ ",
-                " ██  ██ ",
-                "  █████ ",
-                "     ██ ",
-                "  ████  ",
-                "        ",
-            ],
-            ' ' => [
-                "        ",
-                "        ",
-                "        ",
-                "        ",
-                "        ",
-                "        ",
-                "        ",
-            ],
-            '.' => [
-                "        ",
-                "        ",
-                "        ",
-                "        ",
-                "        ",
-                "  ██    ",
-                "        ",
-            ],
-            ',' => [
-                "        ",
-                "        ",
-                "        ",
-                "        ",
-                "  ██    ",
-                "  ██    ",
-                " ██     ",
-            ],
-            '!' => [
-                "  ██    ",
-                "  ██    ",
-                "  ██    ",
-                "  ██    ",
-                "        ",
-                "  ██    ",
-                "        ",
-            ],
-            '?' => [
-                "  ████  ",
-                " ██  ██ ",
-                "    ██  ",
-                "   ██   ",
-                "        ",
-                "   ██   ",
-                "        ",
-            ],
-            ':' => [
-                "        ",
-                "  ██    ",
-                "        ",
-                "        ",
-                "  ██    ",
-                "        ",
-                "        ",
-            ],
-            ';' => [
-                "        ",
-                "  ██    ",
-                "        ",
-                "        ",
-                "  ██    ",
-                " ██     ",
-                "        ",
-            ],
-            '(' => [
-                "   ██   ",
-                "  ██    ",
-                " ██     ",
-                " ██     ",
-                "  ██    ",
-                "   ██   ",
-                "        ",
-            ],
-            ')' => [
-                " ██     ",
-                "  ██    ",
-                "   ██   ",
-                "   ██   ",
-                "  ██    ",
-                " ██     ",
-                "        ",
-            ],
-            '\n' => [
-                "        ",
-                "        ",
-                "        ",
-                "        ",
-                "        ",
-                "        ",
-                "        ",
-            ],
-            _ => [
-                " ██████ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██  ██ ",
-                " ██████ ",
-                "        ",
-            ],
-        };
-        
-        // Draw the pattern
-        for (row_idx, &row) in pattern.iter().enumerate() {
-            for (col_idx, c) in row.chars().enumerate() {
-                if c == '█' {
-                    let px = x_start + col_idx;
-                    let py = y_start + row_idx;
-                    
-                    // Draw larger pixels (2x2)
-                    for dy in 0..2 {
-                        for dx in 0..2 {
-                            let x = px + dx;
-                            let y = py + dy;
-                            
-                            if x < self.window.get_size().0 && y < self.window.get_size().1 {
-                                let idx = (y * width + x) as usize;
-                                if idx < self.buffer.len() {
-                                    self.buffer[idx] = color;
-                                }
-                            }
+    // Selected points get a square marker so they stand out by shape as well as colour.
+    // Multi-point selections also get a bounding box with scale and rotate handles.
+    fn draw_selection(&mut self, input: &InputHandler) {
+        let points = input.points();
+        for idx in input.selection.indices() {
+            if let Some(&(x, y)) = points.get(idx) {
+                self.draw_rect_outline((x - 7.0, y - 7.0), (x + 7.0, y + 7.0), self.theme.selection);
+            }
+        }
+
+        if input.selection.len() > 1 {
+            if let Some(bounds) = input.selection.bounds(points) {
+                // The scale handles sit on the corners of the box
+                let handle_list = handles(bounds);
+                let (top_left, bottom_right) = (handle_list[0].1, handle_list[2].1);
+                let (box_color, handle_color) = (self.theme.selection_box, self.theme.selection);
+                self.draw_rect_outline(top_left, bottom_right, box_color);
+                for (handle, (x, y)) in handle_list {
+                    match handle {
+                        Handle::Scale => {
+                            self.raster().fill_rect(x as i32 - 3, y as i32 - 3, 7, 7, handle_color, 1.0);
+                        }
+                        Handle::Rotate => {
+                            self.draw_line(x, top_left.1, x, y, box_color);
+                            self.draw_point(x, y, handle_color, 4.0);
                         }
                     }
                 }
             }
         }
+
+        if let Some((start, end)) = input.rubber_band {
+            let (x, y) = (start.0.min(end.0), start.1.min(end.1));
+            let (w, h) = ((start.0 - end.0).abs(), (start.1 - end.1).abs());
+            let color = self.theme.rubber_band;
+            self.raster().fill_rect(x as i32, y as i32, w as i32, h as i32, color, 0.15);
+            self.draw_rect_outline(start, end, color);
+        }
     }
 
-    fn draw_point(&mut self, x: f64, y: f64, color: [u8; 3], radius: f64) {
-        // Convert to buffer coordinates
-        let x = x as i32;
-        let y = y as i32;
-
-        // Draw circle
-        for dx in -radius as i32..=radius as i32 {
-            for dy in -radius as i32..=radius as i32 {
-                if dx * dx + dy * dy <= (radius * radius) as i32 {
-                    let px = x + dx;
-                    let py = y + dy;
-                    if px >= 0 && px < 800 && py >= 0 && py < 600 {
-                        let idx = (py * 800 + px) as usize;
-                        if idx < self.buffer.len() {
-                            self.buffer[idx] = ((color[0] as u32) << 16) | 
-                                             ((color[1] as u32) << 8) | 
-                                              (color[2] as u32);
-                        }
-                    }
-                }
+    // The points of the level being edited over its faded polygon. A still curve also
+    // shows its finished curve, so the effect of the detail can be seen.
+    fn draw_level_points(&mut self, input: &mut InputHandler) {
+        let (level, max_steps, dragging) = (input.edit_level, input.max_steps, input.dragging_level_point);
+        let curve = input.scene.selected_mut();
+        curve.sync();
+        let closed = curve.closed;
+        // The animation stops one level short of the iteration count, and so does the
+        // finished curve
+        let finished_level = (!curve.animating).then(|| max_steps.saturating_sub(1).max(level));
+        let chaikin = &curve.chaikin;
+        let key = (chaikin.original_points.clone(), closed, curve.scheme, chaikin.details.clone(), level, finished_level);
+        let (key, points, finished) = match self.level_points.take() {
+            Some(cached) if cached.0 == key => cached,
+            _ => {
+                let points = chaikin.level(level);
+                let finished = finished_level.map(|finished| (level..finished).fold(points.clone(), |p, k| chaikin.refine(&p, k)));
+                (key, points, finished)
             }
+        };
+        if let Some(finished) = &finished {
+            self.draw_colored_polyline(finished, closed);
+        }
+        let positions: Vec<(f64, f64)> = points.iter().map(|p| (p.position.x, p.position.y)).collect();
+        let faded = self.faded(self.theme.polygon, 0.5);
+        self.draw_polyline(&positions, faded, &StrokeStyle::default(), closed);
+        for (i, &(x, y)) in positions.iter().enumerate() {
+            let color = if dragging == Some(i) { self.theme.drag_point } else { self.theme.control_point };
+            self.draw_point(x, y, color, 3.0);
         }
+        self.level_points = Some((key, points, finished));
     }
 
-    fn draw_line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, color: [u8; 3]) {
-        // Convert to buffer coordinates
-        let x1 = x1 as i32;
-        let y1 = y1 as i32;
-        let x2 = x2 as i32;
-        let y2 = y2 as i32;
-
-        // Bresenham's line algorithm
-        let dx = (x2 - x1).abs();
-        let dy = (y2 - y1).abs();
-        let sx = if x1 < x2 { 1 } else { -1 };
-        let sy = if y1 < y2 { 1 } else { -1 };
-        let mut err = dx - dy;
-        let mut x = x1;
-        let mut y = y1;
-
-        while x != x2 || y != y2 {
-            if x >= 0 && x < 800 && y >= 0 && y < 600 {
-                let idx = (y * 800 + x) as usize;
-                if idx < self.buffer.len() {
-                    self.buffer[idx] = ((color[0] as u32) << 16) | 
-                                     ((color[1] as u32) << 8) | 
-                                      (color[2] as u32);
-                }
+    // The selected curve refined adaptively to the iteration count, with a dot at every
+    // point so the spacing shows. Animating curves already show their uniform levels.
+    fn draw_adaptive(&mut self, input: &InputHandler) {
+        let curve = input.scene.selected();
+        if curve.animating || curve.points.len() < 3 {
+            return;
+        }
+        let key = (curve.control_points(), curve.closed, input.max_steps);
+        if self.adaptive.as_ref().map(|(k, _)| k) != Some(&key) {
+            let report = compare_with_uniform(&key.0, key.1, Tolerance::default(), key.2);
+            self.adaptive = Some((key, report));
+        }
+        let Some((_, report)) = &self.adaptive else {
+            return;
+        };
+        let points = report.curve.clone();
+        self.draw_colored_polyline(&points, curve.closed);
+        for p in &points {
+            self.draw_point(p.position.x, p.position.y, self.theme.point, 1.5);
+        }
+    }
+
+    // The raw stroke being drawn, faded, under the control polygon it simplifies to
+    fn draw_sketch(&mut self, input: &InputHandler, polygon: Option<&[(f64, f64)]>) {
+        let (Some(stroke), Some(polygon)) = (&input.sketch_stroke, polygon) else {
+            return;
+        };
+        let raw = self.faded(self.theme.polygon, 0.4);
+        self.draw_polyline(stroke, raw, &StrokeStyle::default(), false);
+        let style = self.polygon_stroke;
+        self.draw_polyline(polygon, self.theme.polygon, &style, false);
+        for &(x, y) in polygon {
+            self.draw_point(x, y, self.theme.control_point, 4.0);
+        }
+    }
+
+    // Crease points are outlined with a diamond
+    fn draw_crease_marker(&mut self, x: f64, y: f64) {
+        let r = 7.0;
+        let color = self.theme.crease;
+        let corners = [(x, y - r), (x + r, y), (x, y + r), (x - r, y), (x, y - r)];
+        for pair in corners.windows(2) {
+            self.draw_line(pair[0].0, pair[0].1, pair[1].0, pair[1].1, color);
+        }
+    }
+
+    fn draw_rect_outline(&mut self, a: (f64, f64), b: (f64, f64), color: [u8; 3]) {
+        let (x0, y0) = (a.0.min(b.0).round() as i32, a.1.min(b.1).round() as i32);
+        let (x1, y1) = (a.0.max(b.0).round() as i32, a.1.max(b.1).round() as i32);
+        let mut raster = self.raster();
+        raster.fill_rect(x0, y0, x1 - x0 + 1, 1, color, 1.0);
+        raster.fill_rect(x0, y1, x1 - x0 + 1, 1, color, 1.0);
+        raster.fill_rect(x0, y0, 1, y1 - y0 + 1, color, 1.0);
+        raster.fill_rect(x1, y0, 1, y1 - y0 + 1, color, 1.0);
+    }
+
+    // One page of the key bindings; the box is sized for the longest entries on any page
+    // so it doesn't jump between pages
+    fn draw_help(&mut self, page: usize) {
+        let pages = KEY_BINDINGS.len().div_ceil(HELP_ROWS_PER_PAGE);
+        let rows: Vec<&(&str, &str)> = KEY_BINDINGS.iter().skip(page * HELP_ROWS_PER_PAGE).take(HELP_ROWS_PER_PAGE).collect();
+        let title = format!("Key bindings ({}/{})", page + 1, pages);
+
+        let line_height = self.font.line_height(1) + 3;
+        let key_width = KEY_BINDINGS.iter().map(|(k, _)| self.font.text_width(k, 1)).max().unwrap_or(0) + 16;
+        let action_width = KEY_BINDINGS.iter().map(|(_, a)| self.font.text_width(a, 1)).max().unwrap_or(0);
+        let width = key_width + action_width + 24;
+        let height = (HELP_ROWS_PER_PAGE.min(KEY_BINDINGS.len()) + 2) * line_height + 16;
+        let x = self.width.saturating_sub(width) / 2;
+        let y = self.height.saturating_sub(height) / 2;
+
+        let background = self.theme.hud_background;
+        self.raster().fill_rect(x as i32, y as i32, width as i32, height as i32, background, 0.85);
+        self.draw_text_string(x + 12, y + 8, &title, self.theme.text);
+        for (i, (key, action)) in rows.into_iter().enumerate() {
+            let row_y = y + 8 + (i + 2) * line_height;
+            self.draw_text_string(x + 12, row_y, key, self.theme.help_key);
+            self.draw_text_string(x + 12 + key_width, row_y, action, self.theme.hud_text);
+        }
+    }
+
+    fn draw_text_string(&mut self, x: usize, y: usize, text: &str, color: [u8; 3]) {
+        self.draw_text_scaled(x, y, text, color, 1);
+    }
+
+    fn draw_text_scaled(&mut self, x: usize, y: usize, text: &str, color: [u8; 3], scale: usize) {
+        let color = pack_color(color);
+        let raster = Raster::new(&mut self.buffer, self.width, self.height);
+        self.font.render(x as i32, y as i32, text, scale, |px, py| {
+            if (0..raster.width as i32).contains(&px) && (0..raster.height as i32).contains(&py) {
+                raster.buffer[py as usize * raster.width + px as usize] = color;
+            }
+        });
+    }
+
+    fn draw_grid(&mut self, spacing: f64) {
+        let grid_color = pack_color(self.theme.grid);
+        let axis_color = pack_color(self.theme.grid_axis);
+        let spacing = spacing.max(1.0);
+        let raster = self.raster();
+        let (width, height) = (raster.width, raster.height);
+
+        // Vertical lines
+        let mut x = 0.0;
+        let mut col = 0;
+        while x < width as f64 {
+            let color = if col % 4 == 0 { axis_color } else { grid_color };
+            for y in 0..height {
+                raster.buffer[y * width + x as usize] = color;
             }
+            x += spacing;
+            col += 1;
+        }
+
+        // Horizontal lines
+        let mut y = 0.0;
+        let mut row = 0;
+        while y < height as f64 {
+            let color = if row % 4 == 0 { axis_color } else { grid_color };
+            let start = y as usize * width;
+            raster.buffer[start..start + width].fill(color);
+            y += spacing;
+            row += 1;
+        }
+    }
+
+    fn raster(&mut self) -> Raster<'_> {
+        Raster::new(&mut self.buffer, self.width, self.height)
+    }
+
+    fn draw_point(&mut self, x: f64, y: f64, color: [u8; 3], radius: f64) {
+        if self.antialias {
+            self.raster().draw_disc_aa(x, y, radius, color);
+        } else {
+            self.raster().draw_disc(x, y, radius, color);
+        }
+    }
+
+    fn draw_line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, color: [u8; 3]) {
+        if self.antialias {
+            self.raster().draw_line_aa(x1, y1, x2, y2, color);
+        } else {
+            self.raster().draw_line(x1, y1, x2, y2, color);
+        }
+    }
 
-            let e2 = 2 * err;
-            if e2 > -dy {
-                err -= dy;
-                x += sx;
+    // Draw a polyline with the given stroke, falling back to single pixel lines for
+    // hairlines so the 1 px look stays identical to the plain line path
+    fn draw_polyline(&mut self, points: &[(f64, f64)], color: [u8; 3], style: &StrokeStyle, closed: bool) {
+        let closed = closed && points.len() > 2;
+        if style.is_hairline() {
+            for pair in points.windows(2) {
+                self.draw_line(pair[0].0, pair[0].1, pair[1].0, pair[1].1, color);
+            }
+            if closed {
+                let (first, last) = (points[0], points[points.len() - 1]);
+                self.draw_line(last.0, last.1, first.0, first.1, color);
             }
-            if e2 < dx {
-                err += dx;
-                y += sy;
+            return;
+        }
+
+        let pieces = stroke_polyline(points, style, closed);
+        let antialias = self.antialias;
+        self.raster().fill_polygons(&pieces, color, antialias);
+    }
+
+    // Fill the interior of a closed curve with the current fill mode. The gradient runs
+    // top to bottom across the curve's bounding box.
+    fn fill_curve(&mut self, curve: &[(f64, f64)]) {
+        let min_y = curve.iter().map(|p| p.1).fold(f64::MAX, f64::min);
+        let max_y = curve.iter().map(|p| p.1).fold(f64::MIN, f64::max);
+        let paint = match self.fill_mode {
+            FillMode::Off => return,
+            FillMode::Solid => Paint::Solid(self.theme.fill),
+            FillMode::Gradient => Paint::LinearGradient {
+                start: (0.0, min_y),
+                end: (0.0, max_y),
+                from: self.theme.gradient_top,
+                to: self.theme.gradient_bottom,
+            },
+        };
+
+        let rule = self.fill_rule;
+        let antialias = self.antialias;
+        self.raster().fill_path(&[curve.to_vec()], rule, &paint, antialias);
+    }
+
+    // Unselected curves are drawn with smaller, dimmer control points
+    fn draw_animated_curve(&mut self, frame: &Frame, selected: bool) {
+        let (control_points, curve_points) = (frame.control_polygon, frame.curve);
+        let control = positions(control_points);
+        let curve = positions(curve_points);
+        let closed = frame.info.closed;
+
+        // Fill first, then the teaching overlays, the control polygon and the curve
+        if closed && curve.len() > 2 {
+            self.fill_curve(&curve);
+        }
+        if selected {
+            self.draw_trail(frame.trail, closed);
+            self.draw_construction(frame, closed);
+        }
+        let polygon_stroke = self.polygon_stroke;
+        let polygon_color = if selected { self.theme.polygon } else { self.theme.polygon_inactive };
+        self.draw_polyline(&control, polygon_color, &polygon_stroke, closed);
+        self.draw_colored_polyline(curve_points, closed);
+
+        // Points on top so they stay visible over thick strokes. Control points are
+        // rings, larger than the solid curve points, so they can be told apart whatever
+        // the colours; the selected curve's rings show their paint in the middle.
+        for point in control_points {
+            let (x, y) = (point.position.x, point.position.y);
+            if selected {
+                self.draw_point(x, y, self.theme.control_point, 5.0);
+                self.draw_point(x, y, point.color, 2.5);
+            } else {
+                self.draw_point(x, y, self.theme.control_point_inactive, 3.5);
+                self.draw_point(x, y, self.theme.background, 2.0);
             }
         }
+        for point in curve_points {
+            self.draw_point(point.position.x, point.position.y, point.color, 2.5);
+        }
     }
 
-    fn draw_animated_curve(&mut self, points: &[Point]) {
-        // Draw all points first
-        for point in points {
-            let color = point.color;
-            let size = if color == [255, 0, 0] { 5.0 } else { 2.5 };
-            self.draw_point(point.position.x, point.position.y, color, size);
+    // Earlier subdivision levels as faded hairlines, one colour per level. Older levels
+    // fade more; level 0 is left out as the control polygon is drawn anyway.
+    fn draw_trail(&mut self, trail: &[Vec<Point>], closed: bool) {
+        let style = StrokeStyle::default();
+        for (level, points) in trail.iter().enumerate().skip(1) {
+            let alpha = 0.2 + 0.4 * level as f64 / trail.len() as f64;
+            let color = self.faded(self.theme.level_color(level), alpha);
+            self.draw_polyline(&positions(points), color, &style, closed);
         }
+    }
 
-        // Find and connect points of the same color to form continuous curves
-        let mut green_points = Vec::new();
-        
-        for point in points {
-            match point.color {
-                [0, 255, 0] => green_points.push(point),
-                _ => {} 
+    // The level being cut, faded, with the new edge between each pair of cut points
+    // and a cross on every cut point
+    fn draw_construction(&mut self, frame: &Frame, closed: bool) {
+        if frame.construction.is_empty() {
+            return;
+        }
+        let style = StrokeStyle::default();
+        let edge_color = self.faded(self.theme.construction, 0.35);
+        self.draw_polyline(&positions(frame.previous_level), edge_color, &style, closed);
+
+        let color = self.theme.construction;
+        for (near, far) in &frame.construction {
+            let (a, b) = (near.position, far.position);
+            self.draw_line(a.x, a.y, b.x, b.y, color);
+            for p in [a, b] {
+                self.draw_line(p.x - 3.0, p.y - 3.0, p.x + 3.0, p.y + 3.0, color);
+                self.draw_line(p.x - 3.0, p.y + 3.0, p.x + 3.0, p.y - 3.0, color);
             }
         }
+    }
 
-        // Draw lines between animated curve points
-        for i in 0..green_points.len().saturating_sub(1) {
-            self.draw_line(
-                green_points[i].position.x,
-                green_points[i].position.y,
-                green_points[i + 1].position.x,
-                green_points[i + 1].position.y,
-                [0, 255, 255] 
-            );
+    // `color` blended `alpha` of the way over the background
+    fn faded(&self, color: [u8; 3], alpha: f64) -> [u8; 3] {
+        unpack_color(blend_color(pack_color(self.theme.background), color, alpha))
+    }
+
+    // Stroke a curve whose points carry their own colours. A single-coloured curve is
+    // stroked in one go; otherwise every segment gets the average of its end colours.
+    fn draw_colored_polyline(&mut self, points: &[Point], closed: bool) {
+        let style = self.curve_stroke;
+        let positions = positions(points);
+        let Some(first) = points.first() else {
+            return;
+        };
+        if points.iter().all(|p| p.color == first.color) {
+            self.draw_polyline(&positions, first.color, &style, closed);
+            return;
+        }
+
+        let mut segments: Vec<(usize, usize)> = (1..points.len()).map(|i| (i - 1, i)).collect();
+        if closed && points.len() > 2 {
+            segments.push((points.len() - 1, 0));
+        }
+        // Round caps hide the seams between separately stroked segments
+        let segment_style = StrokeStyle { cap: LineCap::Round, ..style };
+        for (a, b) in segments {
+            let color = points[a].lerp(&points[b], 0.5).color;
+            self.draw_polyline(&[positions[a], positions[b]], color, &segment_style, false);
         }
     }
 
-    fn draw_points(&mut self, points: &[Point]) {
+    fn draw_points(&mut self, points: &[Point], selected: bool) {
+        let color = if selected { self.theme.point } else { self.theme.point_inactive };
+
+        // Special case: draw line between points when exactly two points
+        if points.len() == 2 {
+            let line = [
+                (points[0].position.x, points[0].position.y),
+                (points[1].position.x, points[1].position.y),
+            ];
+            let polygon_stroke = self.polygon_stroke;
+            self.draw_polyline(&line, color, &polygon_stroke, false);
+        }
+
         // Draw points
         for point in points {
             // Special case: single point just shows as a larger circle
@@ -1003,20 +863,13 @@ impl Canvas {
             self.draw_point(
                 point.position.x,
                 point.position.y,
-                [255, 165, 0],
+                color,
                 if points.len() == 1 { 6.0 } else { 4.0 }, 
             );
-        }
-        
-        // Special case: draw line between points when exactly two points
-        if points.len() == 2 {
-            self.draw_line(
-                points[0].position.x,
-                points[0].position.y,
-                points[1].position.x,
-                points[1].position.y,
-                [255, 165, 0]
-            );
+            // Paint shows in the middle of the selected curve's points
+            if selected {
+                self.draw_point(point.position.x, point.position.y, point.color, 2.0);
+            }
         }
     }
 }
@@ -1,19 +1,42 @@
+use nalgebra::SVector;
+use super::frame::{Frame, FrameInfo, Overlays};
 use super::point::Point;
-use nalgebra::Vector2;
+use super::scheme::{cubic_bspline, four_point, Scheme};
+use super::subdivider::Subdivider;
+use std::borrow::Cow;
 use std::time::Instant;
-pub struct Chaikin {
-    pub original_points: Vec<Point>,
-    pub current_points: Vec<Point>,
-    pub next_points: Vec<Point>,
+
+pub const MIN_STEPS: usize = 1;
+pub const MAX_STEPS: usize = 12;
+pub const MIN_SPEED: f64 = 0.1;
+pub const MAX_SPEED: f64 = 8.0;
+
+// Animated subdivision of a curve in `D` dimensions; the canvas uses the 2D default
+pub struct Chaikin<const D: usize = 2> {
+    pub original_points: Vec<Point<D>>,
+    pub current_points: Vec<Point<D>>,
+    pub next_points: Vec<Point<D>>,
     pub animation_progress: f64,
     pub current_step: usize,
     pub max_steps: usize,
     pub  last_update: Instant,
     pub   animation_speed: f64,
+    pub closed: bool,
+    pub scheme: Scheme,
+    pub overlays: Overlays,
+    // Multiresolution detail: offsets added to each level after it is subdivided from the
+    // one before, `details[k]` belonging to level k + 1. Moving the control points keeps
+    // them, so fine edits ride along with coarse ones.
+    pub details: Vec<Vec<SVector<f64, D>>>,
+    // Subdivision levels computed so far for the trail, level 0 being the control points
+    levels: Vec<Vec<Point<D>>>,
+    // The curve on screen, blended between the current and next points. Kept between
+    // steps so blending it doesn't allocate.
+    curve: Vec<Point<D>>,
 }
 
-impl Chaikin {
-    pub fn new(points: Vec<Point>) -> Self {
+impl<const D: usize> Chaikin<D> {
+    pub fn new(points: Vec<Point<D>>) -> Self {
         Self {
             original_points: points.clone(),
             current_points: points.clone(),
@@ -23,12 +46,20 @@ impl Chaikin {
             max_steps: 7,
             last_update: Instant::now(),
             animation_speed: 1.0,
+            closed: false,
+            scheme: Scheme::Chaikin,
+            overlays: Overlays::default(),
+            details: Vec::new(),
+            levels: Vec::new(),
+            curve: Vec::new(),
         }
     }
 
-    pub fn step(&mut self) -> Vec<Point> {
+    // Advance the animation and return what to draw
+    pub fn step(&mut self) -> Frame<'_, D> {
         if self.original_points.len() < 2 {
-            return self.original_points.clone();
+            self.curve.clear();
+            return self.frame();
         }
 
         // Calculate time delta for smooth animation
@@ -45,121 +76,278 @@ impl Chaikin {
             
             // Move to the next iteration
             self.current_step = (self.current_step + 1) % self.max_steps;
-            self.current_points = self.next_points.clone();
-            self.next_points = Vec::new();
+            // Reuse the old level's buffer for the next one instead of allocating
+            std::mem::swap(&mut self.current_points, &mut self.next_points);
+            self.next_points.clear();
         }
         
         // If we need to calculate the next points
         if self.next_points.is_empty() {
-            if self.current_step == 0 {
-                self.current_points = self.original_points.clone();
-                
-                // Calculate first Chaikin iteration as next
-                if self.original_points.len() >= 2 {
-                    self.next_points = self.apply_chaikin(&self.original_points);
-                } else {
-                    self.next_points = self.original_points.clone();
-                }
-            } else if self.current_step == self.max_steps - 1 {
-                // If we're at the last step, next will be original points again
-                self.next_points = self.original_points.clone();
-            } else {
-                // Otherwise, calculate next Chaikin iteration
-                self.next_points = self.apply_chaikin(&self.current_points);
-            }
+            self.plan_next();
         }
         
         // Interpolate between current and next points
-        let result = self.interpolate(self.animation_progress);
+        self.blend(self.animation_progress);
         
-        self.create_visualization(result)
+        if self.overlays.trail {
+            self.compute_levels(self.current_step);
+        }
+        self.frame()
     }
     
-    // Apply one step of Chaikin's algorithm
-    pub fn apply_chaikin(&self, points: &[Point]) -> Vec<Point> {
+    // Work out which level the current one animates towards
+    fn plan_next(&mut self) {
+        let mut next = std::mem::take(&mut self.next_points);
+        if self.current_step == 0 && self.current_points == self.original_points {
+            // Calculate first Chaikin iteration as next
+            self.refine_into(&self.original_points, 0, &mut next);
+        } else if self.current_step == 0 || self.current_step >= self.max_steps.saturating_sub(1) {
+            // If we're at the last step, or the iteration count was lowered below the
+            // level on screen, next will be original points again
+            next.clear();
+            next.extend_from_slice(&self.original_points);
+        } else {
+            // Otherwise, calculate next Chaikin iteration
+            self.refine_into(&self.current_points, self.current_step, &mut next);
+        }
+        self.next_points = next;
+    }
+    
+    // Apply one step of the active subdivision scheme
+    pub fn subdivide(&self, points: &[Point<D>]) -> Vec<Point<D>> {
+        let closed = self.is_closed_curve(points);
+        match self.scheme {
+            Scheme::Chaikin => self.apply_chaikin(points),
+            Scheme::CubicBSpline => cubic_bspline(points, closed),
+            Scheme::FourPoint => four_point(points, closed),
+        }
+    }
+
+    // One step of the active scheme written over `out`, reusing its allocation. Only
+    // Chaikin's scheme fills the buffer in place; the others build a new level and move it in.
+    pub fn subdivide_into(&self, points: &[Point<D>], out: &mut Vec<Point<D>>) {
+        let closed = self.is_closed_curve(points);
+        match self.scheme {
+            Scheme::Chaikin => self.apply_chaikin_into(points, out),
+            Scheme::CubicBSpline => *out = cubic_bspline(points, closed),
+            Scheme::FourPoint => *out = four_point(points, closed),
+        }
+    }
+
+    // Subdivide `points` `levels` times, swapping between `points` and `scratch` so that
+    // after the first few levels nothing is allocated. The result ends up in `points`;
+    // keep both buffers around to refine more curves without allocating again.
+    pub fn subdivide_in_place(&self, points: &mut Vec<Point<D>>, scratch: &mut Vec<Point<D>>, levels: usize) {
+        for _ in 0..levels {
+            self.subdivide_into(points, scratch);
+            std::mem::swap(points, scratch);
+        }
+    }
+    
+    // Subdivide level `level` into the next one and add that level's detail offsets.
+    // Offsets that no longer fit, e.g. after a point was added, are left out.
+    pub fn refine(&self, points: &[Point<D>], level: usize) -> Vec<Point<D>> {
+        let mut refined = Vec::new();
+        self.refine_into(points, level, &mut refined);
+        refined
+    }
+
+    pub fn refine_into(&self, points: &[Point<D>], level: usize, out: &mut Vec<Point<D>>) {
+        self.subdivide_into(points, out);
+        if let Some(offsets) = self.details.get(level).filter(|d| d.len() == out.len()) {
+            for (point, offset) in out.iter_mut().zip(offsets) {
+                point.position += offset;
+            }
+        }
+    }
+
+    // The points of level `level`, detail included; level 0 is the control points
+    pub fn level(&self, level: usize) -> Vec<Point<D>> {
+        (0..level).fold(self.original_points.clone(), |points, k| self.refine(&points, k))
+    }
+
+    // Move point `index` of level `level` to `position` by changing that level's detail,
+    // so the coarser levels stay as they are. Level 0 has no detail; move the control
+    // points themselves instead.
+    pub fn move_level_point(&mut self, level: usize, index: usize, position: SVector<f64, D>) {
+        if level == 0 {
+            return;
+        }
+        let coarser = self.level(level - 1);
+        let refined = self.refine(&coarser, level - 1);
+        let Some(point) = refined.get(index) else {
+            return;
+        };
+        let offset = position - point.position;
+        if self.details.len() < level {
+            self.details.resize(level, Vec::new());
+        }
+        let plain = self.subdivide(&coarser).len();
+        let offsets = &mut self.details[level - 1];
+        if offsets.len() != plain {
+            *offsets = vec![SVector::zeros(); plain];
+        }
+        offsets[index] += offset;
+        self.restart();
+    }
+
+    // Apply one step of Chaikin's algorithm. Each corner is cut by its point's
+    // `cut_ratio`, and crease points are kept in place so their corners stay sharp.
+    pub fn apply_chaikin(&self, points: &[Point<D>]) -> Vec<Point<D>> {
+        let mut result = Vec::new();
+        self.apply_chaikin_into(points, &mut result);
+        result
+    }
+
+    // `apply_chaikin` writing over `out`, which is reserved up front so a buffer that has
+    // held a level this size before is filled without allocating
+    pub fn apply_chaikin_into(&self, points: &[Point<D>], out: &mut Vec<Point<D>>) {
+        out.clear();
         if points.len() < 2 {
-            return points.to_vec();
+            out.extend_from_slice(points);
+            return;
         }
+        out.reserve(points.len() * 2);
         
-        let mut result = Vec::new();
+        if self.is_closed_curve(points) {
+            return self.apply_chaikin_closed(points, out);
+        }
+        
+        let result = out;
         
         // First point stays the same (for open curves)
         result.push(points[0].clone());
         
         // Apply Chaikin's corner cutting
-        for i in 0..points.len() - 1 {
-            let p0 = points[i].position;
-            let p1 = points[i+1].position;
-            
-            result.push(Point {
-                position: Vector2::new(
-                    0.75 * p0.x + 0.25 * p1.x,
-                    0.75 * p0.y + 0.25 * p1.y
-                ),
-                color: [255, 255, 255],
-            });
+        let last = points.len() - 1;
+        for i in 0..last {
+            let (p0, p1) = (&points[i], &points[i + 1]);
             
-            result.push(Point {
-                position: Vector2::new(
-                    0.25 * p0.x + 0.75 * p1.x,
-                    0.25 * p0.y + 0.75 * p1.y
-                ),
-                color: [255, 255, 255],
-            });
+            if !p0.crease {
+                result.push(cut(p0, p1, p0.cut_ratio()));
+            } else if i > 0 {
+                result.push(p0.clone());
+            }
+            if !p1.crease {
+                result.push(cut(p1, p0, p1.cut_ratio()));
+            }
         }
         
         // Last point stays the same (for open curves)
-        result.push(points[points.len() - 1].clone());
-        
-        result
+        result.push(points[last].clone());
+    }
+    
+    // Closed curves cut every corner, including the one between the last and first point
+    fn apply_chaikin_closed(&self, points: &[Point<D>], result: &mut Vec<Point<D>>) {
+        for i in 0..points.len() {
+            let p0 = &points[i];
+            let p1 = &points[(i + 1) % points.len()];
+            
+            if p0.crease {
+                result.push(p0.clone());
+            } else {
+                result.push(cut(p0, p1, p0.cut_ratio()));
+            }
+            if !p1.crease {
+                result.push(cut(p1, p0, p1.cut_ratio()));
+            }
+        }
+    }
+    
+    // The two points Chaikin's scheme cuts from each edge, in order along the curve.
+    // A crease end of an edge is kept itself.
+    pub fn cut_points(&self, points: &[Point<D>]) -> Vec<(Point<D>, Point<D>)> {
+        let edge_count = if self.is_closed_curve(points) { points.len() } else { points.len().saturating_sub(1) };
+        (0..edge_count).map(|i| edge(&points[i], &points[(i + 1) % points.len()])).collect()
+    }
+
+    // Chaikin's curve after `levels` steps, one point at a time, without building any of
+    // the levels in between: each corner is refined on its own, depth first, so memory
+    // stays at a few points per level however long the output is. Always uses Chaikin's
+    // scheme, whatever `scheme` is set to, and ignores the multiresolution detail.
+    pub fn stream<'a>(&self, points: &'a [Point<D>], levels: usize) -> ChaikinStream<'a, D> {
+        let (mut points, mut levels) = (Cow::Borrowed(points), levels);
+        // Two points refine into an open first level, but its four points are enough to
+        // close the curve from then on
+        if self.closed && points.len() == 2 && levels > 0 {
+            points = Cow::Owned(self.apply_chaikin(&points));
+            levels -= 1;
+        }
+        let n = points.len();
+        let closed = self.is_closed_curve(&points);
+        // A single point has nothing to cut
+        let levels = if n < 2 { 0 } else { levels };
+        let mut stream = ChaikinStream { points, closed, levels, next: 0, stack: Vec::new(), head: None, remaining: 0 };
+        stream.remaining = (0..n).map(|i| stream.top(i).leaves(levels)).sum();
+        if closed {
+            // Closed levels start with the last point the first corner refines into
+            let mut node = stream.top(n - 1);
+            for _ in 0..levels {
+                node = match node.children() {
+                    (_, Some(last)) | (last, None) => last,
+                };
+            }
+            stream.head = Some(node.point().clone());
+        }
+        stream
+    }
+
+    // Make sure levels 0 to `level` are cached for the trail
+    fn compute_levels(&mut self, level: usize) {
+        if self.levels.is_empty() {
+            self.levels.push(self.original_points.clone());
+        }
+        while self.levels.len() <= level {
+            let next = self.refine(&self.levels[self.levels.len() - 1], self.levels.len() - 1);
+            self.levels.push(next);
+        }
+    }
+
+    // A closed curve needs at least three points to enclose anything
+    fn is_closed_curve(&self, points: &[Point<D>]) -> bool {
+        self.closed && points.len() >= 3
+    }
+    
+    // Blend the curve on screen `t` of the way from the current points to the next, into
+    // the buffer it was in last step
+    pub fn blend(&mut self, t: f64) {
+        let mut curve = std::mem::take(&mut self.curve);
+        if self.original_points.len() < 2 {
+            curve.clear();
+        } else {
+            self.interpolate_into(t, &mut curve);
+        }
+        self.curve = curve;
     }
     
-    // Interpolate between current and next points based on animation progress
-    pub fn interpolate(&self, t: f64) -> Vec<Point> {
-        // If either set is empty, return the other
+    // Interpolate between current and next points based on animation progress, writing
+    // over `out`. Colours and other per-point attributes are blended along with the positions.
+    pub fn interpolate_into(&self, t: f64, out: &mut Vec<Point<D>>) {
+        out.clear();
+        // If either set is empty, use the other
         if self.current_points.is_empty() {
-            return self.next_points.clone();
+            return out.extend_from_slice(&self.next_points);
         }
         if self.next_points.is_empty() {
-            return self.current_points.clone();
+            return out.extend_from_slice(&self.current_points);
         }
         
         // Handle different point counts
         if self.current_points.len() != self.next_points.len() {
-            return self.interpolate_different_point_counts(t);
+            if self.is_closed_curve(&self.current_points) && self.is_closed_curve(&self.next_points) {
+                return self.interpolate_closed(t, out);
+            }
+            return self.interpolate_different_point_counts(t, out);
         }
         
         // Simple case: same number of points
-        let mut result = Vec::new();
-        for i in 0..self.current_points.len() {
-            let p1 = self.current_points[i].position;
-            let p2 = self.next_points[i].position;
-            
-            result.push(Point {
-                position: Vector2::new(
-                    p1.x + t * (p2.x - p1.x),
-                    p1.y + t * (p2.y - p1.y)
-                ),
-                color: self.current_points[i].color,
-            });
-        }
-        
-        result
+        out.extend(self.current_points.iter().zip(&self.next_points).map(|(p1, p2)| p1.lerp(p2, t)));
     }
     
     // Handle interpolation when point counts differ
-    pub fn interpolate_different_point_counts(&self, t: f64) -> Vec<Point> {
-        let mut result = Vec::new();
-        
+    pub fn interpolate_different_point_counts(&self, t: f64, result: &mut Vec<Point<D>>) {
         // First and last points always stay the same
-        result.push(Point {
-            position: Vector2::new(
-                self.current_points[0].position.x + t * (self.next_points[0].position.x - self.current_points[0].position.x),
-                self.current_points[0].position.y + t * (self.next_points[0].position.y - self.current_points[0].position.y)
-            ),
-            color: [255, 255, 255],
-        });
-        
+        result.push(self.current_points[0].lerp(&self.next_points[0], t));
         
         // Create normalized positions for both point sets
         let curr_len = self.current_points.len();
@@ -186,22 +374,10 @@ impl Chaikin {
                 };
                 
                 // Interpolate between the two nearest points
-                let p1 = self.next_points[next_idx].position;
-                let p2 = self.next_points[next_idx2].position;
-                let target = Vector2::new(
-                    p1.x + local_t * (p2.x - p1.x),
-                    p1.y + local_t * (p2.y - p1.y)
-                );
+                let target = self.next_points[next_idx].lerp(&self.next_points[next_idx2], local_t);
                 
                 // Interpolate from current to target
-                let curr = self.current_points[i].position;
-                result.push(Point {
-                    position: Vector2::new(
-                        curr.x + t * (target.x - curr.x),
-                        curr.y + t * (target.y - curr.y)
-                    ),
-                    color: [255, 255, 255],
-                });
+                result.push(self.current_points[i].lerp(&target, t));
             }
         } else {
             for i in 1..next_len-1 {
@@ -223,66 +399,133 @@ impl Chaikin {
                 };
                 
                 // Interpolate between the two nearest points
-                let p1 = self.current_points[curr_idx].position;
-                let p2 = self.current_points[curr_idx2].position;
-                let source = Vector2::new(
-                    p1.x + local_t * (p2.x - p1.x),
-                    p1.y + local_t * (p2.y - p1.y)
-                );
+                let source = self.current_points[curr_idx].lerp(&self.current_points[curr_idx2], local_t);
                 
                 // Interpolate from source to target
-                let target = self.next_points[i].position;
-                result.push(Point {
-                    position: Vector2::new(
-                        source.x + t * (target.x - source.x),
-                        source.y + t * (target.y - source.y)
-                    ),
-                    color: [255, 255, 255],
-                });
+                result.push(source.lerp(&self.next_points[i], t));
             }
         }
         
         // Last point
-        let last_curr = self.current_points[curr_len - 1].position;
-        let last_next = self.next_points[next_len - 1].position;
-        result.push(Point {
-            position: Vector2::new(
-                last_curr.x + t * (last_next.x - last_curr.x),
-                last_curr.y + t * (last_next.y - last_curr.y)
-            ),
-            color: [255, 255, 255],
-        });
-        
-        result
+        result.push(self.current_points[curr_len - 1].lerp(&self.next_points[next_len - 1], t));
     }
     
-    // Create visualization with original control points highlighted
-    pub fn create_visualization(&self, points: Vec<Point>) -> Vec<Point> {
-        let mut result = Vec::new();
+    // Closed curves have no fixed endpoints, so points are matched by their position
+    // around the loop instead
+    pub fn interpolate_closed(&self, t: f64, result: &mut Vec<Point<D>>) {
+        let (dense, sparse, forward) = if self.current_points.len() >= self.next_points.len() {
+            (&self.current_points, &self.next_points, false)
+        } else {
+            (&self.next_points, &self.current_points, true)
+        };
         
-        // Add original control points in red
-        for point in &self.original_points {
-            result.push(Point::with_color(
-                point.position.x,
-                point.position.y,
-                [255, 0, 0]
-            ));
+        result.reserve(dense.len());
+        for (i, point) in dense.iter().enumerate() {
+            // Position along the loop measured in sparse segments
+            let pos = i as f64 * sparse.len() as f64 / dense.len() as f64;
+            let idx = pos.floor() as usize % sparse.len();
+            let idx2 = (idx + 1) % sparse.len();
+            let on_sparse = sparse[idx].lerp(&sparse[idx2], pos - pos.floor());
+            
+            let (from, to) = if forward {
+                (&on_sparse, point)
+            } else {
+                (point, &on_sparse)
+            };
+            result.push(from.lerp(to, t));
         }
-        
-        // Add animation points in green
-        for point in points {
-            result.push(Point::with_color(
-                point.position.x,
-                point.position.y,
-                [0, 255, 0]
-            ));
+    }
+    
+    // The current state as a frame, showing the curve as last blended
+    pub fn frame(&self) -> Frame<'_, D> {
+        let trail = if self.overlays.trail {
+            &self.levels[..self.levels.len().min(self.current_step + 1)]
+        } else {
+            &[]
+        };
+        let refining = self.next_points.len() > self.current_points.len();
+        let construction = if self.overlays.construction && self.scheme == Scheme::Chaikin && refining {
+            self.cut_points(&self.current_points)
+        } else {
+            Vec::new()
+        };
+        Frame {
+            control_polygon: &self.original_points,
+            previous_level: &self.current_points,
+            next_level: &self.next_points,
+            curve: &self.curve,
+            trail,
+            construction,
+            info: FrameInfo {
+                step: self.current_step,
+                max_steps: self.max_steps,
+                progress: self.animation_progress,
+                closed: self.is_closed_curve(&self.original_points),
+                scheme: self.scheme,
+            },
         }
-        
-        result
     }
 
-    pub fn set_points(&mut self, points: Vec<Point>) {
+    // Opening or closing the curve, changing the scheme or adding or removing control
+    // points changes how the levels line up, so the detail is dropped
+    pub fn set_closed(&mut self, closed: bool) {
+        if self.closed != closed {
+            self.closed = closed;
+            self.details.clear();
+            self.restart();
+        }
+    }
+
+    // Change the number of iterations without restarting. If the animation is already
+    // past the new last step it heads back to the original points from where it is.
+    pub fn set_max_steps(&mut self, max_steps: usize) {
+        let max_steps = max_steps.clamp(MIN_STEPS, MAX_STEPS);
+        if self.max_steps == max_steps {
+            return;
+        }
+        self.max_steps = max_steps;
+        self.current_step = self.current_step.min(max_steps - 1);
+        if !self.next_points.is_empty() {
+            // Retarget from the level currently on screen, keeping the progress
+            self.plan_next();
+        }
+    }
+
+    pub fn set_animation_speed(&mut self, speed: f64) {
+        self.animation_speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+    }
+
+    pub fn set_scheme(&mut self, scheme: Scheme) {
+        if self.scheme != scheme {
+            self.scheme = scheme;
+            self.details.clear();
+            self.restart();
+        }
+    }
+
+    // Start the animation over from the original control points
+    fn restart(&mut self) {
+        self.levels.clear();
+        self.current_points = self.original_points.clone();
+        self.next_points = Vec::new();
+        self.animation_progress = 0.0;
+        self.current_step = 0;
+        self.last_update = Instant::now();
+    }
+
+    // Replace the multiresolution detail, e.g. from a snapshot. The levels drawn so far
+    // were refined with the old detail, so the animation starts over.
+    pub fn set_details(&mut self, details: Vec<Vec<SVector<f64, D>>>) {
+        self.details = details;
+        self.restart();
+    }
+
+    pub fn set_points(&mut self, points: Vec<Point<D>>) {
         if self.original_points != points {
+            if self.original_points.len() != points.len() {
+                self.details.clear();
+            }
+            self.levels.clear();
             self.original_points = points.clone();
             self.current_points = points;
             self.next_points = Vec::new();
@@ -293,4 +536,158 @@ impl Chaikin {
     }
 }
 
+impl<const D: usize> Subdivider for Chaikin<D> {
+    type Shape = Vec<Point<D>>;
+
+    fn subdivide(&self, points: &Vec<Point<D>>) -> Vec<Point<D>> {
+        Chaikin::subdivide(self, points)
+    }
+
+    // Ping-pong between two buffers rather than allocating every level
+    fn subdivide_times(&self, points: &Vec<Point<D>>, count: usize) -> Vec<Point<D>> {
+        let mut points = points.clone();
+        self.subdivide_in_place(&mut points, &mut Vec::new(), count);
+        points
+    }
+}
+
+// Point `t` of the way along the edge from `from` to `to`. The cut belongs to the corner
+// at `from`, so it takes over that point's weight.
+fn cut<const D: usize>(from: &Point<D>, to: &Point<D>, t: f64) -> Point<D> {
+    Point { weight: from.weight, ..from.lerp(to, t) }
+}
+
+// The two points Chaikin's scheme cuts from the edge `p0` to `p1`, a crease end being kept
+fn edge<const D: usize>(p0: &Point<D>, p1: &Point<D>) -> (Point<D>, Point<D>) {
+    let near = if p0.crease { p0.clone() } else { cut(p0, p1, p0.cut_ratio()) };
+    let far = if p1.crease { p1.clone() } else { cut(p1, p0, p1.cut_ratio()) };
+    (near, far)
+}
+
+// A point of some level with the neighbours its refinement depends on
+#[derive(Clone, Debug)]
+enum Node<const D: usize> {
+    // The start of an open curve and the point after it
+    First(Point<D>, Point<D>),
+    // A point between the one before and the one after it
+    Corner(Point<D>, Point<D>, Point<D>),
+    // The point before the end of an open curve and the end
+    Last(Point<D>, Point<D>),
+}
+
+impl<const D: usize> Node<D> {
+    fn point(&self) -> &Point<D> {
+        match self {
+            Node::First(point, _) | Node::Corner(_, point, _) | Node::Last(_, point) => point,
+        }
+    }
+
+    // Points this node turns into after `levels` steps. Cut points are never creases,
+    // so only a crease stays a single point.
+    fn leaves(&self, levels: usize) -> usize {
+        if self.point().crease { 1 } else { 1 << levels }
+    }
+
+    // The one or two nodes a Chaikin step makes of this one, in order along the curve
+    fn children(self) -> (Node<D>, Option<Node<D>>) {
+        match self {
+            Node::First(start, next) => {
+                let (near, far) = edge(&start, &next);
+                if start.crease {
+                    (Node::First(start, far), None)
+                } else {
+                    (Node::First(start.clone(), near.clone()), Some(Node::Corner(start, near, far)))
+                }
+            }
+            Node::Corner(prev, corner, next) => {
+                let (before, b) = edge(&prev, &corner);
+                let (a, after) = edge(&corner, &next);
+                if corner.crease {
+                    (Node::Corner(before, corner, after), None)
+                } else {
+                    (Node::Corner(before, b.clone(), a.clone()), Some(Node::Corner(b, a, after)))
+                }
+            }
+            Node::Last(prev, end) => {
+                let (near, far) = edge(&prev, &end);
+                if end.crease {
+                    (Node::Last(near, end), None)
+                } else {
+                    (Node::Corner(near, far.clone(), end.clone()), Some(Node::Last(far, end)))
+                }
+            }
+        }
+    }
+}
+
+// Iterator returned by `Chaikin::stream`
+pub struct ChaikinStream<'a, const D: usize> {
+    points: Cow<'a, [Point<D>]>,
+    closed: bool,
+    levels: usize,
+    // Next control point to refine
+    next: usize,
+    // Nodes still to refine with the levels left for each, the next one on top
+    stack: Vec<(Node<D>, usize)>,
+    // The point a closed curve starts with, not yet returned
+    head: Option<Point<D>>,
+    remaining: usize,
+}
+
+impl<const D: usize> ChaikinStream<'_, D> {
+    // Control point `i` in the order it is refined: closed curves go round from the
+    // second point, so the first corner's points come last as they do in each level
+    fn top(&self, i: usize) -> Node<D> {
+        let (points, n) = (&self.points, self.points.len());
+        if self.closed {
+            let i = (i + 1) % n;
+            return Node::Corner(points[(i + n - 1) % n].clone(), points[i].clone(), points[(i + 1) % n].clone());
+        }
+        if i == 0 {
+            Node::First(points[0].clone(), points[1.min(n - 1)].clone())
+        } else if i == n - 1 {
+            Node::Last(points[n - 2].clone(), points[n - 1].clone())
+        } else {
+            Node::Corner(points[i - 1].clone(), points[i].clone(), points[i + 1].clone())
+        }
+    }
+}
+
+impl<const D: usize> Iterator for ChaikinStream<'_, D> {
+    type Item = Point<D>;
+
+    fn next(&mut self) -> Option<Point<D>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        if let Some(head) = self.head.take() {
+            return Some(head);
+        }
+        loop {
+            let (node, levels) = match self.stack.pop() {
+                Some(entry) => entry,
+                None => {
+                    self.next += 1;
+                    (self.top(self.next - 1), self.levels)
+                }
+            };
+            if levels == 0 {
+                // The closed curve's head is left over at the very end, but the count
+                // runs out just before it
+                return Some(node.point().clone());
+            }
+            let (first, second) = node.children();
+            if let Some(second) = second {
+                self.stack.push((second, levels - 1));
+            }
+            self.stack.push((first, levels - 1));
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
 
+impl<const D: usize> ExactSizeIterator for ChaikinStream<'_, D> {}
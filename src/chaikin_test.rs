@@ -4,12 +4,20 @@ use crate::chaikin::*;
 mod tests {
     use super::*;
     use super::super::point::Point;
-    use nalgebra::Vector2;
+    use crate::scheme::Scheme;
+    use crate::subdivider::Subdivider;
+    use nalgebra::{Vector2, Vector3};
 
     fn point(x: f64, y: f64) -> Point {
         Point::new(x, y)
     }
 
+    fn interpolate(chaikin: &Chaikin, t: f64) -> Vec<Point> {
+        let mut result = Vec::new();
+        chaikin.interpolate_into(t, &mut result);
+        result
+    }
+
     #[test]
     fn test_new_initializes_correctly() {
         let input = vec![point(0.0, 0.0), point(10.0, 10.0)];
@@ -37,7 +45,7 @@ mod tests {
         chaikin.current_points = vec![point(0.0, 0.0), point(2.0, 2.0)];
         chaikin.next_points = vec![point(2.0, 2.0), point(4.0, 4.0)];
 
-        let result = chaikin.interpolate(0.5);
+        let result = interpolate(&chaikin, 0.5);
         assert_eq!(result.len(), 2);
         assert_eq!(result[0].position, Vector2::new(1.0, 1.0));
         assert_eq!(result[1].position, Vector2::new(3.0, 3.0));
@@ -57,7 +65,8 @@ mod tests {
             point(6.0, 6.0),
         ];
 
-        let result = chaikin.interpolate_different_point_counts(0.5);
+        let mut result = Vec::new();
+        chaikin.interpolate_different_point_counts(0.5, &mut result);
         assert_eq!(result.first().unwrap().position, Vector2::new(0.0, 0.0));
         assert_eq!(result.last().unwrap().position, Vector2::new(6.0, 6.0));
     }
@@ -76,21 +85,64 @@ mod tests {
             point(6.0, 6.0),
         ];
 
-        let result = chaikin.interpolate_different_point_counts(0.5);
+        let mut result = Vec::new();
+        chaikin.interpolate_different_point_counts(0.5, &mut result);
         assert_eq!(result.first().unwrap().position, Vector2::new(0.0, 0.0));
         assert_eq!(result.last().unwrap().position, Vector2::new(6.0, 6.0));
     }
 
     #[test]
-    fn test_create_visualization_adds_colors() {
-        let input = vec![point(1.0, 1.0), point(2.0, 2.0)];
-        let chaikin = Chaikin::new(vec![point(0.0, 0.0)]);
-        let result = chaikin.create_visualization(input.clone());
+    fn test_frame_keeps_control_points_and_curve_apart() {
+        let control = vec![Point::with_color(0.0, 0.0, [255, 0, 0]), point(8.0, 0.0)];
+        let mut chaikin = Chaikin::new(control.clone());
+        let curve = vec![Point::with_color(1.0, 1.0, [0, 0, 255]), point(2.0, 2.0)];
+        chaikin.next_points = curve.clone();
+        chaikin.blend(1.0);
+        let frame = chaikin.frame();
+
+        assert_eq!(frame.control_polygon, control);
+        assert_eq!(frame.curve, curve);
+        assert_eq!(frame.previous_level, control);
+        assert_eq!(frame.next_level, curve);
+        assert_eq!(frame.info.step, 0);
+    }
+
+    #[test]
+    fn test_apply_chaikin_blends_colors_like_positions() {
+        let input = vec![Point::with_color(0.0, 0.0, [0, 0, 0]), Point::with_color(8.0, 0.0, [200, 100, 40])];
+        let chaikin = Chaikin::new(input.clone());
+
+        let output = chaikin.apply_chaikin(&input);
+        let colors: Vec<[u8; 3]> = output.iter().map(|p| p.color).collect();
+        assert_eq!(colors, vec![[0, 0, 0], [50, 25, 10], [150, 75, 30], [200, 100, 40]]);
+    }
 
+    #[test]
+    fn test_interpolation_blends_colors() {
+        let mut chaikin = Chaikin::new(vec![]);
+        chaikin.current_points = vec![Point::with_color(0.0, 0.0, [0, 0, 0]), Point::with_color(4.0, 0.0, [0, 0, 0])];
+        chaikin.next_points = vec![
+            Point::with_color(0.0, 0.0, [100, 0, 0]),
+            Point::with_color(2.0, 0.0, [100, 0, 0]),
+            Point::with_color(4.0, 0.0, [100, 0, 0]),
+        ];
+        let result = interpolate(&chaikin, 0.5);
         assert_eq!(result.len(), 3);
-        assert_eq!(result[0].color, [255, 0, 0]); // original
-        assert_eq!(result[1].color, [0, 255, 0]); // interpolated
-        assert_eq!(result[2].color, [0, 255, 0]); // interpolated
+        assert!(result.iter().all(|p| p.color == [50, 0, 0]));
+
+        // Closed curves blend colours as well
+        chaikin.closed = true;
+        chaikin.current_points = vec![
+            Point::with_color(0.0, 0.0, [0, 0, 0]),
+            Point::with_color(4.0, 0.0, [0, 0, 0]),
+            Point::with_color(4.0, 4.0, [0, 0, 0]),
+        ];
+        chaikin.next_points = chaikin.apply_chaikin(&chaikin.current_points.clone());
+        for p in &mut chaikin.next_points {
+            p.color = [0, 200, 0];
+        }
+        let result = interpolate(&chaikin, 0.25);
+        assert!(result.iter().all(|p| p.color == [0, 50, 0]));
     }
 
     #[test]
@@ -118,4 +170,363 @@ mod tests {
         // Should not reset
         assert_eq!(chaikin.animation_progress, 0.7);
     }
+
+    #[test]
+    fn test_apply_chaikin_closed_cuts_every_corner() {
+        let square = vec![point(0.0, 0.0), point(4.0, 0.0), point(4.0, 4.0), point(0.0, 4.0)];
+        let mut chaikin = Chaikin::new(square.clone());
+        chaikin.closed = true;
+
+        let output = chaikin.apply_chaikin(&square);
+        // Two points per edge, including the closing edge, and no fixed endpoints
+        assert_eq!(output.len(), 8);
+        assert_eq!(output[0].position, Vector2::new(1.0, 0.0));
+        assert_eq!(output[7].position, Vector2::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn test_closed_with_two_points_stays_open() {
+        let input = vec![point(0.0, 0.0), point(10.0, 10.0)];
+        let mut chaikin = Chaikin::new(input.clone());
+        chaikin.closed = true;
+
+        assert_eq!(chaikin.apply_chaikin(&input).len(), 4);
+    }
+
+    #[test]
+    fn test_interpolate_closed_matches_endpoints_of_animation() {
+        let square = vec![point(0.0, 0.0), point(4.0, 0.0), point(4.0, 4.0), point(0.0, 4.0)];
+        let mut chaikin = Chaikin::new(square.clone());
+        chaikin.closed = true;
+        chaikin.current_points = square.clone();
+        chaikin.next_points = chaikin.apply_chaikin(&square);
+
+        let start = interpolate(&chaikin, 0.0);
+        let end = interpolate(&chaikin, 1.0);
+        assert_eq!(start.len(), 8);
+        assert_eq!(end, chaikin.next_points);
+        // At t = 0 every point lies on the control polygon
+        assert_eq!(start[0].position, Vector2::new(0.0, 0.0));
+        assert_eq!(start[1].position, Vector2::new(2.0, 0.0));
+    }
+
+    #[test]
+    fn test_set_closed_restarts_animation() {
+        let mut chaikin = Chaikin::new(vec![point(0.0, 0.0), point(1.0, 0.0), point(1.0, 1.0)]);
+        chaikin.current_step = 3;
+        chaikin.animation_progress = 0.4;
+
+        chaikin.set_closed(true);
+        assert!(chaikin.closed);
+        assert_eq!(chaikin.current_step, 0);
+        assert_eq!(chaikin.animation_progress, 0.0);
+        assert!(chaikin.next_points.is_empty());
+    }
+
+    #[test]
+    fn test_subdivide_uses_active_scheme() {
+        let input = vec![point(0.0, 0.0), point(8.0, 8.0), point(16.0, 0.0)];
+        let mut chaikin = Chaikin::new(input.clone());
+        assert_eq!(chaikin.subdivide(&input), chaikin.apply_chaikin(&input));
+
+        chaikin.set_scheme(Scheme::FourPoint);
+        assert_eq!(chaikin.scheme, Scheme::FourPoint);
+        assert_eq!(chaikin.subdivide(&input).len(), 5);
+    }
+
+    fn advance(chaikin: &mut Chaikin) {
+        // Force the current transition to complete on the next step
+        chaikin.animation_progress = 1.0;
+        chaikin.step();
+    }
+
+    #[test]
+    fn test_set_max_steps_clamps_range() {
+        let mut chaikin = Chaikin::new(vec![point(0.0, 0.0), point(10.0, 0.0)]);
+        chaikin.set_max_steps(0);
+        assert_eq!(chaikin.max_steps, MIN_STEPS);
+        chaikin.set_max_steps(100);
+        assert_eq!(chaikin.max_steps, MAX_STEPS);
+    }
+
+    #[test]
+    fn test_single_iteration_cycles_without_underflow() {
+        let input = vec![point(0.0, 0.0), point(10.0, 10.0), point(20.0, 0.0)];
+        let mut chaikin = Chaikin::new(input.clone());
+        chaikin.set_max_steps(1);
+
+        chaikin.step();
+        assert_eq!(chaikin.next_points.len(), 6);
+        advance(&mut chaikin);
+        // From the first level the curve heads back to the control points
+        assert_eq!(chaikin.current_step, 0);
+        assert_eq!(chaikin.next_points, input);
+        advance(&mut chaikin);
+        assert_eq!(chaikin.current_points, input);
+        assert_eq!(chaikin.next_points.len(), 6);
+    }
+
+    #[test]
+    fn test_lowering_max_steps_mid_animation_keeps_current_level() {
+        let input = vec![point(0.0, 0.0), point(10.0, 10.0), point(20.0, 0.0)];
+        let mut chaikin = Chaikin::new(input.clone());
+        chaikin.step();
+        for _ in 0..4 {
+            advance(&mut chaikin);
+        }
+        assert_eq!(chaikin.current_step, 4);
+        let level = chaikin.current_points.clone();
+        chaikin.animation_progress = 0.5;
+
+        chaikin.set_max_steps(3);
+        assert_eq!(chaikin.current_step, 2);
+        assert_eq!(chaikin.current_points, level);
+        assert_eq!(chaikin.next_points, input);
+        assert_eq!(chaikin.animation_progress, 0.5);
+    }
+
+    #[test]
+    fn test_raising_max_steps_continues_subdividing() {
+        let input = vec![point(0.0, 0.0), point(10.0, 10.0), point(20.0, 0.0)];
+        let mut chaikin = Chaikin::new(input);
+        chaikin.set_max_steps(2);
+        chaikin.step();
+        advance(&mut chaikin);
+        assert_eq!(chaikin.current_step, 1);
+        assert_eq!(chaikin.next_points, chaikin.original_points);
+
+        chaikin.set_max_steps(5);
+        assert_eq!(chaikin.current_step, 1);
+        assert_eq!(chaikin.next_points.len(), 2 * chaikin.current_points.len());
+    }
+
+    #[test]
+    fn test_set_animation_speed_clamps_range() {
+        let mut chaikin: Chaikin = Chaikin::new(vec![]);
+        chaikin.set_animation_speed(2.5);
+        assert_eq!(chaikin.animation_speed, 2.5);
+        chaikin.set_animation_speed(0.0);
+        assert_eq!(chaikin.animation_speed, MIN_SPEED);
+        chaikin.set_animation_speed(1000.0);
+        assert_eq!(chaikin.animation_speed, MAX_SPEED);
+    }
+
+    fn crease(x: f64, y: f64) -> Point {
+        Point { crease: true, ..point(x, y) }
+    }
+
+    #[test]
+    fn test_crease_point_is_kept_as_corner() {
+        let input = vec![point(0.0, 0.0), crease(10.0, 10.0), point(20.0, 0.0)];
+        let chaikin = Chaikin::new(input.clone());
+
+        let output = chaikin.apply_chaikin(&input);
+        let positions: Vec<Vector2<f64>> = output.iter().map(|p| p.position).collect();
+        // The cuts next to the crease are replaced by the crease itself
+        assert_eq!(
+            positions,
+            vec![
+                Vector2::new(0.0, 0.0),
+                Vector2::new(2.5, 2.5),
+                Vector2::new(10.0, 10.0),
+                Vector2::new(17.5, 2.5),
+                Vector2::new(20.0, 0.0),
+            ]
+        );
+        assert!(output[2].crease);
+        assert_eq!(output.iter().filter(|p| p.crease).count(), 1);
+
+        // It survives further subdivision
+        let again = chaikin.apply_chaikin(&output);
+        assert!(again.iter().any(|p| p.crease && p.position == Vector2::new(10.0, 10.0)));
+    }
+
+    #[test]
+    fn test_crease_on_closed_curve() {
+        let input = vec![crease(0.0, 0.0), point(8.0, 0.0), point(8.0, 8.0), point(0.0, 8.0)];
+        let mut chaikin = Chaikin::new(input.clone());
+        chaikin.set_closed(true);
+
+        let output = chaikin.apply_chaikin(&input);
+        assert_eq!(output.len(), 7);
+        assert_eq!(output[0].position, Vector2::new(0.0, 0.0));
+        assert_eq!(output[1].position, Vector2::new(6.0, 0.0));
+        assert_eq!(output[6].position, Vector2::new(0.0, 6.0));
+    }
+
+    #[test]
+    fn test_weight_changes_cut_ratio() {
+        let tight = Point { weight: 2.5, ..point(10.0, 10.0) };
+        let input = vec![point(0.0, 0.0), tight, point(20.0, 0.0)];
+        let chaikin = Chaikin::new(input.clone());
+
+        let output = chaikin.apply_chaikin(&input);
+        assert_eq!(output.len(), 6);
+        // The heavier middle point only loses a tenth of each edge
+        assert!((output[2].position - Vector2::new(9.0, 9.0)).norm() < 1e-9);
+        assert!((output[3].position - Vector2::new(11.0, 9.0)).norm() < 1e-9);
+        // Cuts take over the weight of the corner they belong to
+        assert_eq!(output[2].weight, 2.5);
+        assert_eq!(output[1].weight, 1.0);
+    }
+
+    #[test]
+    fn test_chaikin_smooths_three_dimensional_paths() {
+        let rail: Vec<Point<3>> = [[0.0, 0.0, 0.0], [10.0, 0.0, 10.0], [20.0, 0.0, 0.0]]
+            .iter()
+            .map(|&[x, y, z]| Point::from_vector(Vector3::new(x, y, z)))
+            .collect();
+        let mut chaikin = Chaikin::new(rail.clone());
+        let level = chaikin.apply_chaikin(&rail);
+        assert_eq!(level.len(), 6);
+        assert_eq!(level[1].position, Vector3::new(2.5, 0.0, 2.5));
+        assert_eq!(level[2].position, Vector3::new(7.5, 0.0, 7.5));
+
+        chaikin.closed = true;
+        assert_eq!(chaikin.subdivide(&rail).len(), 6);
+
+        // Animation frames interpolate in 3D as well
+        chaikin.closed = false;
+        let frame = chaikin.step();
+        assert_eq!(frame.control_polygon, rail);
+        assert_eq!(frame.next_level, level);
+        assert!(frame.curve.iter().all(|p| p.position.y == 0.0));
+    }
+
+    #[test]
+    fn test_level_detail_follows_coarse_edits() {
+        let square = vec![point(0.0, 0.0), point(100.0, 0.0), point(100.0, 100.0), point(0.0, 100.0)];
+        let mut chaikin = Chaikin::new(square.clone());
+        chaikin.closed = true;
+        let plain = chaikin.level(2);
+        assert_eq!(plain, chaikin.subdivide(&chaikin.subdivide(&square)));
+
+        // Pull one point of level 2 outwards
+        let target = plain[3].position + Vector2::new(0.0, -20.0);
+        chaikin.move_level_point(2, 3, target);
+        let edited = chaikin.level(2);
+        assert_eq!(edited[3].position, target);
+        // Coarser levels are untouched, and only the dragged point moved at its own level
+        assert_eq!(chaikin.level(1), chaikin.subdivide(&square));
+        assert!(edited.iter().zip(&plain).enumerate().all(|(i, (a, b))| i == 3 || a == b));
+        // Finer levels are smoothed from the edited one
+        assert_eq!(chaikin.level(3), chaikin.subdivide(&edited));
+
+        // Moving every control point carries the detail along as an offset
+        let shifted: Vec<Point> = square.iter().map(|p| point(p.position.x + 50.0, p.position.y + 10.0)).collect();
+        chaikin.set_points(shifted);
+        assert_eq!(chaikin.level(2)[3].position, target + Vector2::new(50.0, 10.0));
+
+        // Moving the same point again adds to its offset instead of starting over
+        chaikin.move_level_point(2, 3, target + Vector2::new(50.0, 0.0));
+        assert_eq!(chaikin.details[1][3], Vector2::new(0.0, -30.0));
+    }
+
+    #[test]
+    fn test_level_detail_is_dropped_when_it_no_longer_fits() {
+        let points = vec![point(0.0, 0.0), point(50.0, 80.0), point(100.0, 0.0)];
+        let mut chaikin = Chaikin::new(points.clone());
+        chaikin.move_level_point(1, 2, Vector2::new(40.0, 90.0));
+        // Level 0 has no detail to change
+        chaikin.move_level_point(0, 1, Vector2::new(0.0, 0.0));
+        assert_eq!(chaikin.details.len(), 1);
+        assert_eq!(chaikin.level(0), points);
+
+        // The animation runs through the edited level
+        let frame = chaikin.step();
+        assert_eq!(frame.next_level[2].position, Vector2::new(40.0, 90.0));
+
+        let mut more = points.clone();
+        more.push(point(150.0, 50.0));
+        chaikin.set_points(more);
+        assert!(chaikin.details.is_empty());
+
+        chaikin.move_level_point(1, 1, Vector2::new(10.0, 10.0));
+        chaikin.set_scheme(Scheme::CubicBSpline);
+        assert!(chaikin.details.is_empty());
+    }
+
+    // Open and closed polygons with creases, weights and colours to stress the cuts
+    fn awkward_polygons() -> Vec<(Vec<Point>, bool)> {
+        let mut points = vec![point(0.0, 0.0), point(40.0, 90.0), point(100.0, 10.0), point(160.0, 80.0), point(200.0, 0.0)];
+        points[1].color = [200, 40, 10];
+        points[2].crease = true;
+        points[3].weight = 3.0;
+        let mut creased_ends = points.clone();
+        creased_ends[0].crease = true;
+        creased_ends[4].crease = true;
+        let mut all = Vec::new();
+        for closed in [false, true] {
+            all.push((points.clone(), closed));
+            all.push((creased_ends.clone(), closed));
+            all.push((points[..2].to_vec(), closed));
+            all.push((points[..3].to_vec(), closed));
+        }
+        all.push((vec![point(5.0, 5.0)], false));
+        all.push((Vec::new(), true));
+        all
+    }
+
+    #[test]
+    fn test_in_place_subdivision_matches_allocating() {
+        for (points, closed) in awkward_polygons() {
+            let mut chaikin = Chaikin::new(Vec::new());
+            chaikin.closed = closed;
+            let expected = chaikin.levels(&points, 4);
+
+            // Reused buffers come out the same as fresh ones, whatever they held before
+            let mut out = vec![point(1.0, 2.0); 50];
+            chaikin.apply_chaikin_into(&points, &mut out);
+            assert_eq!(out, expected[1]);
+
+            let (mut buffer, mut scratch) = (points.clone(), vec![point(3.0, 3.0)]);
+            chaikin.subdivide_in_place(&mut buffer, &mut scratch, 4);
+            assert_eq!(buffer, expected[4]);
+            assert_eq!(chaikin.subdivide_times(&points, 4), expected[4]);
+        }
+    }
+
+    #[test]
+    fn test_in_place_subdivision_follows_the_scheme() {
+        let points = vec![point(0.0, 0.0), point(50.0, 80.0), point(100.0, 0.0), point(150.0, 60.0)];
+        let mut chaikin = Chaikin::new(Vec::new());
+        chaikin.scheme = Scheme::FourPoint;
+        let (mut buffer, mut scratch) = (points.clone(), Vec::new());
+        chaikin.subdivide_in_place(&mut buffer, &mut scratch, 2);
+        assert_eq!(buffer, chaikin.subdivide(&chaikin.subdivide(&points)));
+    }
+
+    #[test]
+    fn test_stream_matches_uniform_levels() {
+        for (points, closed) in awkward_polygons() {
+            let mut chaikin = Chaikin::new(Vec::new());
+            chaikin.closed = closed;
+            for (depth, level) in chaikin.levels(&points, 5).into_iter().enumerate() {
+                let stream = chaikin.stream(&points, depth);
+                assert_eq!(stream.len(), level.len());
+                assert_eq!(stream.collect::<Vec<_>>(), level, "closed {} depth {}", closed, depth);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream_counts_down() {
+        let points = vec![point(0.0, 0.0), point(50.0, 80.0), point(100.0, 0.0)];
+        let chaikin = Chaikin::new(Vec::new());
+        let mut stream = chaikin.stream(&points, 10);
+        // Each point, ends included, refines into 2^10
+        assert_eq!(stream.len(), 3 * 1024);
+        stream.next();
+        assert_eq!(stream.size_hint(), (3071, Some(3071)));
+        assert_eq!(stream.last(), Some(point(100.0, 0.0)));
+    }
+
+    #[test]
+    fn test_step_blends_into_the_same_buffer() {
+        let mut chaikin = Chaikin::new(vec![point(0.0, 0.0), point(50.0, 80.0), point(100.0, 0.0)]);
+        chaikin.animation_speed = 0.0;
+        let first = chaikin.step().curve.as_ptr();
+        let second = chaikin.step().curve.as_ptr();
+        assert_eq!(first, second);
+    }
 }
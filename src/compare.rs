@@ -0,0 +1,281 @@
+// Split-view comparison: the selected curve's control points run through two or four
+// configurations side by side, animated on one shared clock
+use super::chaikin::{Chaikin, MAX_STEPS, MIN_STEPS};
+use super::analysis::{curve_length, hausdorff_distance, mean_distance};
+use super::frame::Frame;
+use super::point::{Point, MAX_WEIGHT, MIN_WEIGHT};
+use super::scheme::Scheme;
+use super::subdivider::Subdivider;
+
+// The canvas the viewports are laid out on
+pub const SCREEN_SIZE: (f64, f64) = (800.0, 600.0);
+// Viewports show the canvas at half size
+pub const VIEW_SCALE: f64 = 0.5;
+pub const MIN_RATIO: f64 = 0.05;
+pub const MAX_RATIO: f64 = 0.5;
+const VIEWPORT_NAMES: [&str; 4] = ["A", "B", "C", "D"];
+// Metrics kept for configurations seen before, e.g. while trying out slider values
+const METRIC_CACHE_SIZE: usize = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Config {
+    pub scheme: Scheme,
+    // Fraction of each edge Chaikin's scheme cuts off at a corner; 1/4 is the classic
+    // scheme. Other schemes ignore it.
+    pub ratio: f64,
+    // Deepest subdivision level
+    pub steps: usize,
+    pub closed: bool,
+}
+
+impl Config {
+    pub fn label(&self) -> String {
+        let mode = if self.closed { "closed" } else { "open" };
+        match self.scheme {
+            Scheme::Chaikin => format!("{} {:.2}  x{}  {}", self.scheme.name(), self.ratio, self.steps, mode),
+            _ => format!("{}  x{}  {}", self.scheme.name(), self.steps, mode),
+        }
+    }
+
+    // The control points with their weights scaled so plain corners are cut at `ratio`
+    fn prepare(&self, points: &[Point]) -> Vec<Point> {
+        let scale = 0.25 / self.ratio.clamp(MIN_RATIO, MAX_RATIO);
+        points
+            .iter()
+            .map(|p| Point { weight: (p.weight * scale).clamp(MIN_WEIGHT, MAX_WEIGHT), ..p.clone() })
+            .collect()
+    }
+}
+
+// How a configuration's finished curve differs from the first viewport's
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Metrics {
+    pub points: usize,
+    pub length: f64,
+    // Largest distance between the two curves, both ways round (Hausdorff distance)
+    pub max_deviation: f64,
+    // Average distance from this curve's points to the reference curve
+    pub mean_deviation: f64,
+}
+
+pub struct Comparison {
+    // Settings of all four viewports; the first `count` are shown
+    pub configs: Vec<Config>,
+    // Number of viewports on screen: 0 (off), 2 or 4
+    pub count: usize,
+    // Viewport whose configuration the settings panel edits
+    pub active: usize,
+    // Shared clock: every viewport moves from level `step` towards the next one
+    pub step: usize,
+    pub progress: f64,
+    source: Vec<Point>,
+    built_for: Vec<Config>,
+    chaikins: Vec<Chaikin>,
+    // Every level of each configuration, from the control points down
+    levels: Vec<Vec<Vec<Point>>>,
+    metrics: Vec<Metrics>,
+    // Metrics worked out for the current points, by reference and compared configuration
+    metric_cache: Vec<(Config, Config, Metrics)>,
+}
+
+impl Default for Comparison {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Comparison {
+    pub fn new() -> Self {
+        let config = |scheme, ratio| Config { scheme, ratio, steps: 5, closed: false };
+        Self {
+            configs: vec![
+                config(Scheme::Chaikin, 0.25),
+                config(Scheme::CubicBSpline, 0.25),
+                config(Scheme::FourPoint, 0.25),
+                config(Scheme::Chaikin, 0.1),
+            ],
+            count: 0,
+            active: 0,
+            step: 0,
+            progress: 0.0,
+            source: Vec::new(),
+            built_for: Vec::new(),
+            chaikins: Vec::new(),
+            levels: Vec::new(),
+            metrics: Vec::new(),
+            metric_cache: Vec::new(),
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.count > 0
+    }
+
+    // Off, two viewports, four viewports, off again
+    pub fn cycle(&mut self) {
+        self.count = match self.count {
+            0 => 2,
+            2 => 4,
+            _ => 0,
+        };
+        self.active = self.active.min(self.count.max(1) - 1);
+        self.step = 0;
+        self.progress = 0.0;
+    }
+
+    pub fn active_config_mut(&mut self) -> &mut Config {
+        &mut self.configs[self.active]
+    }
+
+    // Points to compare. Levels are only recomputed when the points or a shown
+    // configuration changed; metrics wait for `update_metrics`.
+    pub fn set_source(&mut self, points: &[Point]) {
+        let shown = &self.configs[..self.count];
+        if self.source == points && self.built_for == shown {
+            return;
+        }
+        if self.source != points {
+            self.metric_cache.clear();
+        }
+        self.source = points.to_vec();
+        self.built_for = shown.to_vec();
+        self.chaikins.clear();
+        self.levels.clear();
+        for config in shown {
+            let config = Config { steps: config.steps.clamp(MIN_STEPS, MAX_STEPS), ..*config };
+            let mut chaikin = Chaikin::new(config.prepare(points));
+            chaikin.closed = config.closed;
+            chaikin.scheme = config.scheme;
+            chaikin.max_steps = config.steps;
+            let count = if points.len() >= 2 { config.steps } else { 0 };
+            let levels = chaikin.levels(&chaikin.original_points, count);
+            self.chaikins.push(chaikin);
+            self.levels.push(levels);
+        }
+        self.step = self.step.min(self.last_step());
+        self.place();
+    }
+
+    // The shared clock runs to the deepest level of any viewport, then back to 0
+    fn last_step(&self) -> usize {
+        self.built_for.iter().map(|c| c.steps).max().unwrap_or(0)
+    }
+
+    // Move the shared clock on by `dt` seconds
+    pub fn advance(&mut self, dt: f64, speed: f64) {
+        self.progress += dt * speed;
+        if self.progress >= 1.0 {
+            self.progress = 0.0;
+            self.step = if self.step >= self.last_step() { 0 } else { self.step + 1 };
+            self.place();
+        } else {
+            self.blend();
+        }
+    }
+
+    // Put each viewport's animation between the levels the shared clock is at. A
+    // configuration with fewer levels waits at its deepest level until the others catch up.
+    fn place(&mut self) {
+        let (step, last) = (self.step, self.last_step());
+        for (chaikin, levels) in self.chaikins.iter_mut().zip(&self.levels) {
+            let deepest = levels.len() - 1;
+            let from = step.min(deepest);
+            let to = if step >= last { 0 } else { (step + 1).min(deepest) };
+            chaikin.current_points.clone_from(&levels[from]);
+            chaikin.next_points.clone_from(&levels[to]);
+            chaikin.current_step = from;
+        }
+        self.blend();
+    }
+
+    fn blend(&mut self) {
+        for chaikin in &mut self.chaikins {
+            chaikin.blend(self.progress);
+        }
+    }
+
+    // One frame per shown viewport
+    pub fn frames(&self) -> Vec<Frame<'_>> {
+        self.chaikins
+            .iter()
+            .map(|chaikin| {
+                let mut frame = chaikin.frame();
+                frame.info.progress = self.progress;
+                frame
+            })
+            .collect()
+    }
+
+    pub fn metrics(&self) -> &[Metrics] {
+        &self.metrics
+    }
+
+    // Work out the metrics of the shown configurations. Each costs a Hausdorff distance
+    // between two finished curves, so the canvas holds off while a slider is dragged, and
+    // configurations seen before for the same points are looked up instead.
+    pub fn update_metrics(&mut self) {
+        self.metrics.clear();
+        for i in 0..self.levels.len() {
+            let (reference, config) = (self.built_for[0], self.built_for[i]);
+            let cached = self.metric_cache.iter().find(|(r, c, _)| *r == reference && *c == config);
+            let metrics = match cached {
+                Some(&(_, _, metrics)) => metrics,
+                None => {
+                    let metrics = self.measure(i);
+                    if self.metric_cache.len() >= METRIC_CACHE_SIZE {
+                        self.metric_cache.remove(0);
+                    }
+                    self.metric_cache.push((reference, config, metrics));
+                    metrics
+                }
+            };
+            self.metrics.push(metrics);
+        }
+    }
+
+    // How viewport `index`'s finished curve differs from viewport A's
+    fn measure(&self, index: usize) -> Metrics {
+        if self.source.len() < 2 {
+            return Metrics { points: self.source.len(), ..Metrics::default() };
+        }
+        let finished = |i: usize| &self.levels[i][self.levels[i].len() - 1];
+        let (reference, reference_closed) = (finished(0), self.built_for[0].closed);
+        let (curve, closed) = (finished(index), self.built_for[index].closed);
+        Metrics {
+            points: curve.len(),
+            length: curve_length(curve, closed),
+            max_deviation: hausdorff_distance(curve, closed, reference, reference_closed),
+            mean_deviation: mean_distance(curve, reference, reference_closed),
+        }
+    }
+
+    // Screen rectangle (x, y, width, height) of viewport `index`: side by side halves
+    // for two viewports, quadrants for four
+    pub fn viewport(&self, index: usize) -> (f64, f64, f64, f64) {
+        let (w, h) = SCREEN_SIZE;
+        if self.count <= 2 {
+            (index as f64 * w / 2.0, 0.0, w / 2.0, h)
+        } else {
+            ((index % 2) as f64 * w / 2.0, (index / 2) as f64 * h / 2.0, w / 2.0, h / 2.0)
+        }
+    }
+
+    pub fn viewport_at(&self, pos: (f64, f64)) -> Option<usize> {
+        (0..self.count).find(|&i| {
+            let (x, y, w, h) = self.viewport(i);
+            pos.0 >= x && pos.0 < x + w && pos.1 >= y && pos.1 < y + h
+        })
+    }
+
+    // Map a canvas position into viewport `index`, keeping the scaled canvas centred
+    pub fn to_viewport(&self, index: usize, pos: (f64, f64)) -> (f64, f64) {
+        let (x, y, w, h) = self.viewport(index);
+        let offset_x = x + (w - SCREEN_SIZE.0 * VIEW_SCALE) / 2.0;
+        let offset_y = y + (h - SCREEN_SIZE.1 * VIEW_SCALE) / 2.0;
+        (offset_x + pos.0 * VIEW_SCALE, offset_y + pos.1 * VIEW_SCALE)
+    }
+
+    pub fn name(index: usize) -> &'static str {
+        VIEWPORT_NAMES[index % VIEWPORT_NAMES.len()]
+    }
+}
@@ -0,0 +1,157 @@
+use crate::compare::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::curve_length;
+    use crate::frame::positions;
+    use crate::point::Point;
+    use crate::scheme::Scheme;
+
+    fn zigzag() -> Vec<Point> {
+        vec![Point::new(0.0, 0.0), Point::new(100.0, 100.0), Point::new(200.0, 0.0), Point::new(300.0, 100.0)]
+    }
+
+    fn comparison(count: usize) -> Comparison {
+        let mut comparison = Comparison::new();
+        while comparison.count != count {
+            comparison.cycle();
+        }
+        comparison
+    }
+
+    #[test]
+    fn test_cycle_goes_through_two_four_and_off() {
+        let mut comparison = Comparison::new();
+        assert!(!comparison.is_on());
+        comparison.cycle();
+        assert_eq!(comparison.count, 2);
+        comparison.active = 3;
+        comparison.cycle();
+        assert_eq!(comparison.count, 4);
+        comparison.cycle();
+        assert!(!comparison.is_on());
+        assert_eq!(comparison.active, 0);
+    }
+
+    #[test]
+    fn test_viewports_split_the_screen() {
+        let two = comparison(2);
+        assert_eq!(two.viewport(1), (400.0, 0.0, 400.0, 600.0));
+        assert_eq!(two.viewport_at((500.0, 550.0)), Some(1));
+        // The half-size canvas is centred vertically in a tall viewport
+        assert_eq!(two.to_viewport(0, (0.0, 0.0)), (0.0, 150.0));
+
+        let four = comparison(4);
+        assert_eq!(four.viewport(3), (400.0, 300.0, 400.0, 300.0));
+        assert_eq!(four.viewport_at((10.0, 310.0)), Some(2));
+        assert_eq!(four.to_viewport(3, (800.0, 600.0)), (800.0, 600.0));
+        assert_eq!(four.viewport_at((900.0, 10.0)), None);
+    }
+
+    #[test]
+    fn test_frames_follow_each_configuration() {
+        let mut comparison = comparison(4);
+        comparison.configs[1].closed = true;
+        comparison.configs[2].steps = 2;
+        comparison.set_source(&zigzag());
+        let frames = comparison.frames();
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[0].info.scheme, Scheme::Chaikin);
+        assert_eq!(frames[2].info.scheme, Scheme::FourPoint);
+        assert!(frames[1].info.closed && !frames[0].info.closed);
+        let control = positions(&zigzag());
+        assert!(frames.iter().all(|f| positions(f.control_polygon) == control));
+    }
+
+    #[test]
+    fn test_shared_clock_waits_at_deepest_level_and_wraps() {
+        let mut comparison = comparison(2);
+        comparison.configs[0].steps = 1;
+        comparison.configs[1].steps = 3;
+        comparison.set_source(&zigzag());
+
+        for _ in 0..2 {
+            comparison.advance(1.0, 1.0);
+        }
+        assert_eq!(comparison.step, 2);
+        let frames = comparison.frames();
+        // Viewport A stays at its only level while B keeps refining
+        assert_eq!(frames[0].info.step, 1);
+        assert_eq!(frames[0].previous_level, frames[0].next_level);
+        assert!(frames[1].next_level.len() > frames[1].previous_level.len());
+
+        comparison.advance(1.0, 1.0);
+        assert_eq!(comparison.frames()[1].next_level, zigzag());
+        comparison.advance(1.0, 1.0);
+        assert_eq!(comparison.step, 0);
+    }
+
+    #[test]
+    fn test_ratio_changes_how_deep_corners_are_cut() {
+        let mut comparison = comparison(2);
+        comparison.configs[1] = Config { ratio: 0.1, ..comparison.configs[0] };
+        comparison.configs[0].steps = 1;
+        comparison.configs[1].steps = 1;
+        comparison.set_source(&zigzag());
+        comparison.advance(0.999, 1.0);
+        let frames = comparison.frames();
+        let first_cut = |i: usize| frames[i].next_level[1].position.x;
+        assert!((first_cut(0) - 25.0).abs() < 1e-9);
+        assert!((first_cut(1) - 10.0).abs() < 1e-9);
+        assert!(Config { ratio: 0.1, ..comparison.configs[0] }.label().contains("0.10"));
+        assert!(!Config { scheme: Scheme::FourPoint, ..comparison.configs[0] }.label().contains("0.25"));
+    }
+
+    #[test]
+    fn test_metrics_compare_against_first_viewport() {
+        let mut comparison = comparison(4);
+        comparison.configs[1] = comparison.configs[0];
+        comparison.set_source(&zigzag());
+        comparison.update_metrics();
+        let metrics = comparison.metrics();
+        assert_eq!(metrics.len(), 4);
+        assert_eq!(metrics[0].max_deviation, 0.0);
+        assert_eq!(metrics[1], metrics[0]);
+        // The interpolating scheme passes through the control points, so it strays
+        // further from Chaikin's curve than it does on average
+        assert!(metrics[2].max_deviation > metrics[2].mean_deviation);
+        assert!(metrics[2].mean_deviation > 0.0);
+        // Corner cutting shortens the zigzag
+        assert!(metrics[0].length > 0.0 && metrics[0].length < curve_length(&zigzag(), false));
+    }
+
+    #[test]
+    fn test_too_few_points_give_empty_curves() {
+        let mut comparison = comparison(2);
+        comparison.set_source(&[Point::new(1.0, 1.0)]);
+        comparison.update_metrics();
+        let frames = comparison.frames();
+        assert!(frames.iter().all(|f| f.curve.is_empty()));
+        assert_eq!(comparison.metrics()[1].max_deviation, 0.0);
+    }
+
+    #[test]
+    fn test_metrics_wait_for_update_and_are_cached() {
+        let mut comparison = comparison(2);
+        comparison.configs[1] = comparison.configs[0];
+        comparison.set_source(&zigzag());
+        assert!(comparison.metrics().is_empty());
+        comparison.update_metrics();
+        let same = comparison.metrics()[1];
+        assert_eq!(same.max_deviation, 0.0);
+
+        // A slider being dragged changes the curves but not the metrics until it settles
+        comparison.configs[1].ratio = 0.1;
+        comparison.set_source(&zigzag());
+        assert_eq!(comparison.metrics()[1], same);
+        comparison.update_metrics();
+        assert!(comparison.metrics()[1].max_deviation > 0.0);
+
+        // Going back picks up the earlier result
+        comparison.configs[1].ratio = 0.25;
+        comparison.set_source(&zigzag());
+        comparison.update_metrics();
+        assert_eq!(comparison.metrics()[1], same);
+    }
+}
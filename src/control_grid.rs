@@ -0,0 +1,141 @@
+// Grids of control points, such as height fields, refined by tensor-product Chaikin: each
+// step cuts the corners along every row, then along every column. Heights are measured
+// along y, the up axis in OBJ files and in previews.
+use nalgebra::Vector3;
+use super::chaikin::Chaikin;
+use super::mesh::Mesh;
+use super::point::Point;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ControlGrid {
+    pub columns: usize,
+    pub rows: usize,
+    // Row by row, `columns` points each
+    pub points: Vec<Point<3>>,
+    // Rows wrap around from their last point back to the first
+    pub periodic_u: bool,
+    // Columns wrap around
+    pub periodic_v: bool,
+}
+
+impl ControlGrid {
+    // A grid over the xz-plane, one unit between points, lifted to the given heights.
+    // Every row needs the same number of heights.
+    pub fn height_field(heights: &[Vec<f64>]) -> Result<Self, String> {
+        let columns = heights.first().map_or(0, Vec::len);
+        if let Some(row) = heights.iter().position(|line| line.len() != columns) {
+            return Err(format!("Row {} has {} heights, expected {}", row + 1, heights[row].len(), columns));
+        }
+        let points = heights
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.iter()
+                    .enumerate()
+                    .map(move |(column, &h)| Point::from_vector(Vector3::new(column as f64, h, row as f64)))
+            })
+            .collect();
+        Ok(Self { columns, rows: heights.len(), points, periodic_u: false, periodic_v: false })
+    }
+
+    // Parse a height field: one row of heights per line, separated by spaces or commas.
+    // `#` starts a comment at the beginning of a line.
+    pub fn parse(data: &str) -> Result<Self, String> {
+        let mut heights: Vec<Vec<f64>> = Vec::new();
+        for (line_no, line) in data.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let row = line
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|field| !field.is_empty())
+                .map(|field| field.parse())
+                .collect::<Result<Vec<f64>, _>>()
+                .map_err(|_| format!("Invalid height on line {}", line_no + 1))?;
+            if let Some(first) = heights.first() {
+                if row.len() != first.len() {
+                    return Err(format!(
+                        "Line {} has {} heights, expected {}",
+                        line_no + 1,
+                        row.len(),
+                        first.len()
+                    ));
+                }
+            }
+            heights.push(row);
+        }
+        if heights.is_empty() {
+            return Err("Height field has no rows".to_string());
+        }
+        Self::height_field(&heights)
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let data = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        Self::parse(&data)
+    }
+
+    // One step of tensor-product Chaikin. Open directions keep their end rows and
+    // columns; every direction with at least two points doubles its point count.
+    pub fn subdivide(&self) -> Self {
+        let rows: Vec<Vec<Point<3>>> = (0..self.rows)
+            .map(|row| refine_line(&self.points[row * self.columns..(row + 1) * self.columns], self.periodic_u))
+            .collect();
+        let columns = rows.first().map_or(0, Vec::len);
+        let refined_columns: Vec<Vec<Point<3>>> = (0..columns)
+            .map(|column| refine_line(&rows.iter().map(|row| row[column].clone()).collect::<Vec<_>>(), self.periodic_v))
+            .collect();
+        let row_count = refined_columns.first().map_or(self.rows, Vec::len);
+        let points = (0..row_count)
+            .flat_map(|row| refined_columns.iter().map(move |column| column[row].clone()))
+            .collect();
+        Self { columns, rows: row_count, points, ..*self }
+    }
+
+    // The grid as a quad mesh facing up, closing the seams of periodic directions
+    pub fn to_mesh(&self) -> Mesh {
+        let spans = |count: usize, periodic: bool| if periodic && count >= 3 { count } else { count.saturating_sub(1) };
+        let index = |column: usize, row: usize| (row % self.rows) * self.columns + column % self.columns;
+        let mut faces = Vec::new();
+        for row in 0..spans(self.rows, self.periodic_v) {
+            for column in 0..spans(self.columns, self.periodic_u) {
+                faces.push(vec![
+                    index(column, row),
+                    index(column, row + 1),
+                    index(column + 1, row + 1),
+                    index(column + 1, row),
+                ]);
+            }
+        }
+        Mesh { vertices: self.points.clone(), faces, creases: Vec::new() }
+    }
+
+    // Heights as a greyscale image with one pixel per point, row by row: the lowest
+    // point is black and the highest white
+    pub fn height_map(&self) -> Vec<u8> {
+        let heights = self.points.iter().map(|p| p.position.y);
+        let (low, high) = heights.clone().fold((f64::MAX, f64::MIN), |(lo, hi), h| (lo.min(h), hi.max(h)));
+        let range = high - low;
+        heights
+            .map(|h| if range > 0.0 { ((h - low) / range * 255.0).round() as u8 } else { 0 })
+            .collect()
+    }
+}
+
+// One Chaikin step along a row or column. Cut points come in pairs per edge, with crease
+// ends kept as they are, so every line of the grid keeps the same length.
+fn refine_line(points: &[Point<3>], periodic: bool) -> Vec<Point<3>> {
+    if points.len() < 2 {
+        return points.to_vec();
+    }
+    let mut chaikin = Chaikin::new(Vec::new());
+    chaikin.closed = periodic;
+    let cuts = chaikin.cut_points(points).into_iter().flat_map(|(near, far)| [near, far]);
+    if periodic && points.len() >= 3 {
+        cuts.collect()
+    } else {
+        let ends = (points[0].clone(), points[points.len() - 1].clone());
+        std::iter::once(ends.0).chain(cuts).chain(std::iter::once(ends.1)).collect()
+    }
+}
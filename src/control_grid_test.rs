@@ -0,0 +1,109 @@
+use crate::control_grid::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chaikin::Chaikin;
+
+    // 4 columns by 3 rows, rising along each row and falling down the columns
+    fn slope() -> ControlGrid {
+        ControlGrid::parse("0 1 4 9\n1, 2, 5, 10\n\n# last row\n2 3 6 11\n").unwrap()
+    }
+
+    // Swap rows and columns, along with their boundaries
+    fn transpose(grid: &ControlGrid) -> ControlGrid {
+        let points = (0..grid.columns)
+            .flat_map(|column| (0..grid.rows).map(move |row| grid.points[row * grid.columns + column].clone()))
+            .collect();
+        ControlGrid {
+            columns: grid.rows,
+            rows: grid.columns,
+            points,
+            periodic_u: grid.periodic_v,
+            periodic_v: grid.periodic_u,
+        }
+    }
+
+    #[test]
+    fn test_parse_height_field() {
+        let grid = slope();
+        assert_eq!((grid.columns, grid.rows), (4, 3));
+        // Columns run along x, rows along z, heights along y
+        assert_eq!(grid.points[4 + 3].position, nalgebra::Vector3::new(3.0, 10.0, 1.0));
+
+        assert_eq!(ControlGrid::parse("1 2\n3\n").unwrap_err(), "Line 2 has 1 heights, expected 2");
+        assert_eq!(ControlGrid::parse("1 x\n").unwrap_err(), "Invalid height on line 1");
+        assert!(ControlGrid::parse("# nothing\n").is_err());
+        let ragged = ControlGrid::height_field(&[vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0]]);
+        assert_eq!(ragged.unwrap_err(), "Row 3 has 1 heights, expected 2");
+    }
+
+    #[test]
+    fn test_subdivide_doubles_both_directions() {
+        let grid = slope().subdivide();
+        assert_eq!((grid.columns, grid.rows), (8, 6));
+        assert_eq!(grid.points.len(), 48);
+        // Open boundaries keep the corner points
+        assert_eq!(grid.points[0], slope().points[0]);
+        assert_eq!(grid.points[47], slope().points[11]);
+
+        let mut periodic = slope();
+        periodic.periodic_u = true;
+        periodic.periodic_v = true;
+        let grid = periodic.subdivide();
+        assert_eq!((grid.columns, grid.rows), (8, 6));
+        assert_ne!(grid.points[0], slope().points[0]);
+    }
+
+    #[test]
+    fn test_rows_follow_curve_chaikin() {
+        // Every row has the same profile, so every refined row is its Chaikin curve
+        let profile = [0.0, 3.0, -1.0, 2.0, 5.0];
+        let grid = ControlGrid::height_field(&[profile.to_vec(), profile.to_vec(), profile.to_vec()]).unwrap().subdivide();
+        let curve = Chaikin::new(Vec::new()).apply_chaikin(&ControlGrid::height_field(&[profile.to_vec()]).unwrap().points);
+        assert_eq!(grid.columns, curve.len());
+        for (p, q) in grid.points.iter().zip(&curve).take(grid.columns) {
+            assert!((p.position.x - q.position.x).abs() < 1e-12 && (p.position.y - q.position.y).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_directions_are_interchangeable() {
+        let mut grid = slope();
+        grid.periodic_u = true;
+        let a = transpose(&grid.subdivide());
+        let b = transpose(&grid).subdivide();
+        assert_eq!((a.columns, a.rows), (b.columns, b.rows));
+        for (p, q) in a.points.iter().zip(&b.points) {
+            assert!((p.position - q.position).norm() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_to_mesh_closes_periodic_seams() {
+        let mesh = slope().to_mesh();
+        assert_eq!(mesh.vertices.len(), 12);
+        assert_eq!(mesh.faces.len(), 3 * 2);
+        // Quads face up (+y) for a flat grid
+        let flat = ControlGrid::height_field(&[vec![0.0; 3], vec![0.0; 3]]).unwrap().to_mesh();
+        let [a, b, c] = [0, 1, 2].map(|i| flat.vertices[flat.faces[0][i]].position);
+        assert!((b - a).cross(&(c - a)).y > 0.0);
+
+        let mut cylinder = slope();
+        cylinder.periodic_u = true;
+        let mesh = cylinder.to_mesh();
+        assert_eq!(mesh.faces.len(), 4 * 2);
+        // The last quad of a row wraps back to the first column
+        assert_eq!(mesh.faces[3], vec![3, 7, 4, 0]);
+    }
+
+    #[test]
+    fn test_height_map_spans_black_to_white() {
+        let pixels = slope().height_map();
+        assert_eq!(pixels.len(), 12);
+        assert_eq!(pixels[0], 0);
+        assert_eq!(pixels[11], 255);
+        assert_eq!(pixels[1], (255.0f64 / 11.0).round() as u8);
+        assert!(ControlGrid::height_field(&[vec![2.0; 3]]).unwrap().height_map().iter().all(|&p| p == 0));
+    }
+}
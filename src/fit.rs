@@ -0,0 +1,389 @@
+// Reverse subdivision: find the compact control polygon whose Chaikin curve best matches a
+// dense polyline such as a GPS track or a mouse stroke. Chaikin's scheme is linear, so
+// the refined curve is a fixed matrix times the control points; the fit alternates
+// between matching each sample to its nearest spot on the curve and solving the least
+// squares problem for the control points. Each refined point depends on only three
+// control points, so the matrix is kept as sparse rows and the normal equations are banded.
+use nalgebra::{SMatrix, SVector};
+use super::chaikin::Chaikin;
+use super::point::Point;
+use super::subdivider::Subdivider;
+
+// Levels of refinement standing in for the limit curve
+const FIT_LEVELS: usize = 5;
+// Rounds of matching samples to the curve and solving again
+const FIT_ITERATIONS: usize = 8;
+// How much distance along the curve counts compared to distance across it
+const TANGENT_WEIGHT: f64 = 0.001;
+// Most control points a fit uses, however long the track
+const MAX_CONTROL_POINTS: usize = 1024;
+// Edges either side of the expected spot that each sample searches for its nearest point
+const SEARCH_WINDOW: usize = 8;
+
+// A refined point as a weighted sum of control points, by control point index
+type Row = Vec<(usize, f64)>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fit<const D: usize = 2> {
+    pub control_points: Vec<Point<D>>,
+    // Largest gap between the samples and the fitted curve, measured both ways
+    pub max_error: f64,
+    // Root mean square distance from the samples to the curve
+    pub rms_error: f64,
+}
+
+// Fewest control points a fit can have
+fn min_count(closed: bool) -> usize {
+    if closed { 3 } else { 2 }
+}
+
+// Fit `count` control points, or as many as there are samples if that is fewer, up to
+// MAX_CONTROL_POINTS. Open Chaikin curves keep their end points, so open fits pin them to
+// the first and last sample.
+pub fn fit_control_points<const D: usize>(samples: &[Point<D>], closed: bool, count: usize) -> Fit<D> {
+    let count = count.max(min_count(closed)).min(most_points(samples));
+    if samples.len() <= min_count(closed) {
+        let positions: Vec<SVector<f64, D>> = samples.iter().map(|p| p.position).collect();
+        return finish(samples, closed, positions);
+    }
+
+    let basis = basis(count, closed);
+    // Start from the samples resampled evenly by arc length, which already lies close to
+    // the samples, so matching them to its curve gives a good first guess
+    let mut control = resample(samples, closed, count);
+    let positions: Vec<SVector<f64, D>> = samples.iter().map(|p| p.position).collect();
+    for _ in 0..FIT_ITERATIONS {
+        let curve = apply(&basis, &control);
+        let params: Vec<f64> = track(&curve, closed, &positions).into_iter().map(|(u, _)| u).collect();
+        match solve(&basis, count, &curve, &params, samples, closed) {
+            Some(solved) => control = solved,
+            None => break,
+        }
+    }
+    finish(samples, closed, control)
+}
+
+// Measure how far the samples are from the curve of `control` and colour its points
+fn finish<const D: usize>(samples: &[Point<D>], closed: bool, control: Vec<SVector<f64, D>>) -> Fit<D> {
+    let mut chaikin = Chaikin::new(Vec::new());
+    chaikin.closed = closed;
+    let polygon: Vec<Point<D>> = control.iter().map(|&c| Point::from_vector(c)).collect();
+    let curve: Vec<SVector<f64, D>> = chaikin.subdivide_times(&polygon, FIT_LEVELS).iter().map(|p| p.position).collect();
+    let positions: Vec<SVector<f64, D>> = samples.iter().map(|p| p.position).collect();
+    let errors: Vec<f64> = track(&curve, closed, &positions).into_iter().map(|(_, e)| e).collect();
+    // The curve must not stray from the samples either, or a few far-flung control points
+    // could pass near sparse samples while swinging wide between them
+    let stray = track(&positions, closed, &curve).into_iter().map(|(_, e)| e).fold(0.0, f64::max);
+    let max_error = errors.iter().copied().fold(stray, f64::max);
+    let rms_error = if errors.is_empty() { 0.0 } else { (errors.iter().map(|e| e * e).sum::<f64>() / errors.len() as f64).sqrt() };
+
+    // Control points take the colour of the sample nearest to where they sit along the track
+    let nearest = track(&positions, closed, &control);
+    let control_points = control
+        .into_iter()
+        .zip(nearest)
+        .map(|(position, (u, _))| {
+            let sample = &samples[u.round() as usize % samples.len()];
+            Point { color: sample.color, ..Point::from_vector(position) }
+        })
+        .collect();
+    Fit { control_points, max_error, rms_error }
+}
+
+// The smallest fit whose samples all lie within `tolerance` of its curve. If no fit with
+// fewer points than samples, or than MAX_CONTROL_POINTS, gets that close, the closest one
+// found is returned.
+pub fn fit_to_tolerance<const D: usize>(samples: &[Point<D>], closed: bool, tolerance: f64) -> Fit<D> {
+    let min = min_count(closed);
+    if samples.len() <= min {
+        return fit_control_points(samples, closed, samples.len());
+    }
+    // Double the count until the fit is close enough, then narrow down between the last
+    // count that was too coarse and the first that was fine
+    let mut low = min;
+    let mut count = min;
+    let mut best = fit_control_points(samples, closed, count);
+    let most = most_points(samples);
+    while best.max_error > tolerance && count < most {
+        low = count;
+        count = (count * 2).min(most);
+        best = fit_control_points(samples, closed, count);
+    }
+    if best.max_error > tolerance {
+        return best;
+    }
+    let mut high = count;
+    while high - low > 1 {
+        let mid = (low + high) / 2;
+        let fit = fit_control_points(samples, closed, mid);
+        if fit.max_error <= tolerance {
+            high = mid;
+            best = fit;
+        } else {
+            low = mid;
+        }
+    }
+    best
+}
+
+fn most_points<const D: usize>(samples: &[Point<D>]) -> usize {
+    samples.len().min(MAX_CONTROL_POINTS)
+}
+
+// Row `i` gives refined point `i` in terms of the `count` control points
+fn basis(count: usize, closed: bool) -> Vec<Row> {
+    let ratio = Point::<1>::default().cut_ratio();
+    let mut rows: Vec<Row> = (0..count).map(|j| vec![(j, 1.0)]).collect();
+    for _ in 0..FIT_LEVELS {
+        rows = refine_rows(&rows, closed, ratio);
+    }
+    rows
+}
+
+// Chaikin's step on rows instead of points, cutting every corner at `ratio`
+fn refine_rows(rows: &[Row], closed: bool, ratio: f64) -> Vec<Row> {
+    let n = rows.len();
+    let closed = closed && n >= 3;
+    let edges = if closed { n } else { n - 1 };
+    let mut refined = Vec::with_capacity(2 * n);
+    if !closed {
+        refined.push(rows[0].clone());
+    }
+    for i in 0..edges {
+        let (a, b) = (&rows[i], &rows[(i + 1) % n]);
+        refined.push(blend(a, b, ratio));
+        refined.push(blend(b, a, ratio));
+    }
+    if !closed {
+        refined.push(rows[n - 1].clone());
+    }
+    refined
+}
+
+// `t` of the way from row `a` to row `b`
+fn blend(a: &Row, b: &Row, t: f64) -> Row {
+    let mut row: Row = a.iter().map(|&(j, w)| (j, w * (1.0 - t))).collect();
+    for &(j, w) in b {
+        match row.iter_mut().find(|(k, _)| *k == j) {
+            Some((_, v)) => *v += w * t,
+            None => row.push((j, w * t)),
+        }
+    }
+    row
+}
+
+fn apply<const D: usize>(basis: &[Row], control: &[SVector<f64, D>]) -> Vec<SVector<f64, D>> {
+    basis.iter().map(|row| row.iter().map(|&(j, w)| control[j] * w).sum()).collect()
+}
+
+// Least-squares control points for samples at the given fractional indices of `curve`,
+// the current refined curve. Distance along the curve's tangent counts for only a little,
+// so samples are free to slide along it: without that the fit only creeps towards the
+// best curve. None if the equations could not be solved.
+fn solve<const D: usize>(
+    basis: &[Row],
+    count: usize,
+    curve: &[SVector<f64, D>],
+    params: &[f64],
+    samples: &[Point<D>],
+    closed: bool,
+) -> Option<Vec<SVector<f64, D>>> {
+    let rows = basis.len();
+    // Pinned ends move to the right-hand side, leaving only the inner points free
+    let (first, last) = (samples[0].position, samples[samples.len() - 1].position);
+    let free = if closed { count } else { count - 2 };
+    if free == 0 {
+        return Some(vec![first, last]);
+    }
+    // Where each control point's unknowns go. Closed curves take them from both ends in
+    // turn, 0, n-1, 1, n-2, ..., so that neighbours round the loop stay close together
+    // and the equations stay banded.
+    let slot = |j: usize| -> Option<usize> {
+        if closed {
+            Some(if 2 * j < count { 2 * j } else { 2 * (count - 1 - j) + 1 })
+        } else if j == 0 || j == count - 1 {
+            None
+        } else {
+            Some(j - 1)
+        }
+    };
+
+    // Each sample's row of the refined curve, with its target and metric
+    let terms: Vec<(Row, SVector<f64, D>, SMatrix<f64, D, D>)> = samples
+        .iter()
+        .zip(params)
+        .map(|(sample, &u)| {
+            let k = (u.floor() as usize).min(rows - 1);
+            let t = u - k as f64;
+            let next = if closed { (k + 1) % rows } else { (k + 1).min(rows - 1) };
+            let row = blend(&basis[k], &basis[next], t);
+            let mut target = sample.position;
+            for &(j, w) in &row {
+                if slot(j).is_none() {
+                    target -= if j == 0 { first } else { last } * w;
+                }
+            }
+            let tangent = (curve[next] - curve[k]).try_normalize(1e-12).unwrap_or_else(SVector::zeros);
+            let weight = SMatrix::<f64, D, D>::identity() - tangent * tangent.transpose() * (1.0 - TANGENT_WEIGHT);
+            (row, weight * target, weight)
+        })
+        .collect();
+
+    let spread = terms
+        .iter()
+        .flat_map(|(row, _, _)| {
+            let slots: Vec<usize> = row.iter().filter_map(|&(j, _)| slot(j)).collect();
+            let (low, high) = (slots.iter().min().copied(), slots.iter().max().copied());
+            low.zip(high).map(|(low, high)| high - low)
+        })
+        .max()
+        .unwrap_or(0);
+    let size = free * D;
+    let width = spread * D + D - 1;
+    // Lower band of the normal matrix: `normal[i][i - c]` holds entry (i, c)
+    let mut normal = vec![vec![0.0; width + 1]; size];
+    let mut rhs = vec![0.0; size];
+    for (row, target, weight) in &terms {
+        for &(j, wj) in row {
+            let Some(sj) = slot(j) else { continue };
+            for d in 0..D {
+                rhs[sj * D + d] += wj * target[d];
+            }
+            for &(k, wk) in row {
+                let Some(sk) = slot(k) else { continue };
+                for d in 0..D {
+                    for e in 0..D {
+                        let (i, c) = (sj * D + d, sk * D + e);
+                        if c <= i {
+                            normal[i][i - c] += wj * wk * weight[(d, e)];
+                        }
+                    }
+                }
+            }
+        }
+    }
+    // A touch of damping keeps the system solvable when samples bunch up
+    for row in &mut normal {
+        row[0] += 1e-9;
+    }
+    let solution = solve_banded(normal, width, rhs)?;
+    let position = |j: usize| slot(j).map(|s| SVector::from_fn(|d, _| solution[s * D + d]));
+    Some(
+        (0..count)
+            .map(|j| position(j).unwrap_or(if j == 0 { first } else { last }))
+            .collect(),
+    )
+}
+
+// Solve a symmetric positive definite system given by its lower band of `width`
+// diagonals below the main one, by banded Cholesky factorisation
+fn solve_banded(mut band: Vec<Vec<f64>>, width: usize, mut rhs: Vec<f64>) -> Option<Vec<f64>> {
+    let n = rhs.len();
+    // Factor in place: afterwards `band[i][i - c]` holds L(i, c)
+    for i in 0..n {
+        for c in i.saturating_sub(width)..=i {
+            let mut sum = band[i][i - c];
+            for k in i.saturating_sub(width).max(c.saturating_sub(width))..c {
+                sum -= band[i][i - k] * band[c][c - k];
+            }
+            if c == i {
+                if sum <= 0.0 || !sum.is_finite() {
+                    return None;
+                }
+                band[i][0] = sum.sqrt();
+            } else {
+                band[i][i - c] = sum / band[c][0];
+            }
+        }
+    }
+    // L y = b, then L^T x = y
+    for i in 0..n {
+        for k in i.saturating_sub(width)..i {
+            rhs[i] -= band[i][i - k] * rhs[k];
+        }
+        rhs[i] /= band[i][0];
+    }
+    for i in (0..n).rev() {
+        for k in i + 1..n.min(i + width + 1) {
+            rhs[i] -= band[k][k - i] * rhs[k];
+        }
+        rhs[i] /= band[i][0];
+    }
+    Some(rhs)
+}
+
+// `count` points spread evenly by arc length along the samples
+fn resample<const D: usize>(samples: &[Point<D>], closed: bool, count: usize) -> Vec<SVector<f64, D>> {
+    let lengths = arc_lengths(samples, closed);
+    let total = lengths[lengths.len() - 1];
+    let spans = if closed { count } else { count - 1 };
+    let mut edge = 0;
+    (0..count)
+        .map(|i| {
+            let target = total * i as f64 / spans as f64;
+            while edge + 2 < lengths.len() && lengths[edge + 1] < target {
+                edge += 1;
+            }
+            let length = lengths[edge + 1] - lengths[edge];
+            let t = if length > 0.0 { ((target - lengths[edge]) / length).clamp(0.0, 1.0) } else { 0.0 };
+            let (a, b) = (samples[edge].position, samples[(edge + 1) % samples.len()].position);
+            a + (b - a) * t
+        })
+        .collect()
+}
+
+// Distance along the polyline to each point, plus the closing edge's end for closed curves
+fn arc_lengths<const D: usize>(points: &[Point<D>], closed: bool) -> Vec<f64> {
+    let mut lengths = vec![0.0];
+    let mut total = 0.0;
+    let ends = if closed { points.len() } else { points.len() - 1 };
+    for i in 0..ends {
+        total += (points[(i + 1) % points.len()].position - points[i].position).norm();
+        lengths.push(total);
+    }
+    lengths
+}
+
+// Fractional index of and distance to the nearest point on `polyline` for each of
+// `points`, which run along it in order. Each point only searches the edges around where
+// the one before it landed, moved on by the typical step between points; the first one
+// searches the whole polyline.
+fn track<const D: usize>(polyline: &[SVector<f64, D>], closed: bool, points: &[SVector<f64, D>]) -> Vec<(f64, f64)> {
+    let edges = if closed && polyline.len() > 2 { polyline.len() } else { polyline.len().saturating_sub(1) };
+    if edges == 0 {
+        return points.iter().map(|p| (0.0, (polyline[0] - p).norm())).collect();
+    }
+    let step = edges.div_ceil(points.len().max(1));
+    let reach = SEARCH_WINDOW + step;
+    let mut last: Option<usize> = None;
+    points
+        .iter()
+        .map(|p| {
+            let (start, count) = match last {
+                Some(k) if 2 * reach + 1 < edges => (k as isize + step as isize - reach as isize, 2 * reach + 1),
+                _ => (0, edges),
+            };
+            let mut best = (f64::MAX, 0, 0.0);
+            for offset in 0..count {
+                let k = start + offset as isize;
+                let k = if closed {
+                    k.rem_euclid(edges as isize) as usize
+                } else if k < 0 || k >= edges as isize {
+                    continue;
+                } else {
+                    k as usize
+                };
+                let (a, b) = (&polyline[k], &polyline[(k + 1) % polyline.len()]);
+                let ab = b - a;
+                let length_sq = ab.norm_squared();
+                let t = if length_sq > 0.0 { ((p - a).dot(&ab) / length_sq).clamp(0.0, 1.0) } else { 0.0 };
+                let distance = (a + ab * t - p).norm_squared();
+                if distance < best.0 {
+                    best = (distance, k, t);
+                }
+            }
+            last = Some(best.1);
+            (best.1 as f64 + best.2, best.0.sqrt())
+        })
+        .collect()
+}
@@ -0,0 +1,102 @@
+use crate::fit::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chaikin::Chaikin;
+    use crate::point::Point;
+    use crate::subdivider::Subdivider;
+    use nalgebra::Vector3;
+
+    fn polygon() -> Vec<Point> {
+        [(0.0, 0.0), (100.0, 20.0), (150.0, 120.0), (60.0, 180.0), (-40.0, 150.0), (-20.0, 60.0)]
+            .iter()
+            .map(|&(x, y)| Point::new(x, y))
+            .collect()
+    }
+
+    // A dense polyline on the Chaikin curve of `control`
+    fn dense<const D: usize>(control: &[Point<D>], closed: bool) -> Vec<Point<D>> {
+        let mut chaikin = Chaikin::new(Vec::new());
+        chaikin.closed = closed;
+        chaikin.subdivide_times(&control.to_vec(), 6)
+    }
+
+    fn circle(count: usize, radius: f64) -> Vec<Point> {
+        (0..count)
+            .map(|i| {
+                let angle = i as f64 / count as f64 * std::f64::consts::TAU;
+                Point::new(radius * angle.cos(), radius * angle.sin())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_recovers_the_control_polygon_of_its_own_curve() {
+        for closed in [false, true] {
+            let samples = dense(&polygon(), closed);
+            let fit = fit_control_points(&samples, closed, polygon().len());
+            assert_eq!(fit.control_points.len(), 6);
+            assert!(fit.max_error < 0.1, "closed {}: max error {}", closed, fit.max_error);
+            assert!(fit.rms_error <= fit.max_error);
+            for (p, q) in fit.control_points.iter().zip(polygon()) {
+                assert!((p.position - q.position).norm() < 0.5, "closed {}: {:?} vs {:?}", closed, p.position, q.position);
+            }
+        }
+    }
+
+    #[test]
+    fn test_more_control_points_fit_closer() {
+        let samples = circle(120, 100.0);
+        let coarse = fit_control_points(&samples, true, 4);
+        let fine = fit_control_points(&samples, true, 12);
+        assert!(fine.max_error < coarse.max_error);
+        assert!(coarse.max_error > 1.0);
+    }
+
+    #[test]
+    fn test_fit_to_tolerance_finds_a_small_polygon() {
+        let samples = circle(200, 100.0);
+        let fit = fit_to_tolerance(&samples, true, 0.5);
+        assert!(fit.max_error <= 0.5);
+        assert!(fit.control_points.len() < 40, "{} points", fit.control_points.len());
+        // One point fewer would no longer be close enough
+        let fewer = fit_control_points(&samples, true, fit.control_points.len() - 1);
+        assert!(fewer.max_error > 0.5);
+    }
+
+    #[test]
+    fn test_small_inputs_are_kept() {
+        let samples = vec![Point::new(0.0, 0.0), Point::new(10.0, 0.0)];
+        let fit = fit_control_points(&samples, false, 5);
+        assert_eq!(fit.control_points, samples);
+        assert_eq!(fit.max_error, 0.0);
+        assert!(fit_to_tolerance(&samples[..1], false, 1.0).control_points.len() == 1);
+    }
+
+    #[test]
+    fn test_fits_three_dimensional_tracks() {
+        let control: Vec<Point<3>> = [(0.0, 0.0, 0.0), (50.0, 0.0, 20.0), (50.0, 50.0, 40.0), (0.0, 50.0, 60.0)]
+            .iter()
+            .map(|&(x, y, z)| Point::from_vector(Vector3::new(x, y, z)))
+            .collect();
+        let fit = fit_control_points(&dense(&control, false), false, 4);
+        assert!(fit.max_error < 0.1);
+        assert!((fit.control_points[3].position - control[3].position).norm() < 0.5);
+    }
+
+    #[test]
+    fn test_long_tracks_fit_within_the_point_cap() {
+        // A long wiggly track, with far more samples than the cap
+        let samples: Vec<Point> = (0..2000)
+            .map(|i| {
+                let x = i as f64 * 0.75;
+                Point::new(x, 40.0 * (x / 15.0).sin())
+            })
+            .collect();
+        let fit = fit_control_points(&samples, false, samples.len());
+        assert_eq!(fit.control_points.len(), 1024);
+        assert_eq!(fit.control_points[0], samples[0]);
+        assert!(fit.max_error < 0.1, "max error {}", fit.max_error);
+    }
+}
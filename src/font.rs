@@ -0,0 +1,245 @@
+// Bitmap fonts loaded from BDF (Glyph Bitmap Distribution Format) data
+
+use std::collections::HashMap;
+
+// Public domain misc-fixed 8x13 font, printable ASCII and Latin-1
+const BUILTIN_BDF: &str = include_str!("../assets/fonts/8x13.bdf");
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Glyph {
+    pub advance: usize,
+    pub width: usize,
+    pub height: usize,
+    pub x_offset: i32,
+    pub y_offset: i32,
+    // Row-major, `width * height` entries
+    pub bitmap: Vec<bool>,
+}
+
+impl Glyph {
+    pub fn is_set(&self, col: usize, row: usize) -> bool {
+        col < self.width && row < self.height && self.bitmap[row * self.width + col]
+    }
+}
+
+pub struct BitmapFont {
+    glyphs: HashMap<char, Glyph>,
+    pub ascent: i32,
+    pub descent: i32,
+    default_char: Option<char>,
+}
+
+impl BitmapFont {
+    pub fn builtin() -> Self {
+        Self::parse_bdf(BUILTIN_BDF).expect("Built-in font is valid BDF")
+    }
+
+    pub fn parse_bdf(data: &str) -> Result<Self, String> {
+        let mut glyphs = HashMap::new();
+        let mut ascent = None;
+        let mut descent = None;
+        let mut bounding_box = None;
+        let mut default_encoding = None;
+
+        let mut lines = data.lines().enumerate();
+        while let Some((line_no, line)) = lines.next() {
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                Some("FONTBOUNDINGBOX") => {
+                    let values = parse_numbers(fields, 4, line_no)?;
+                    bounding_box = Some((values[1], values[3]));
+                }
+                Some("FONT_ASCENT") => ascent = Some(parse_numbers(fields, 1, line_no)?[0]),
+                Some("FONT_DESCENT") => descent = Some(parse_numbers(fields, 1, line_no)?[0]),
+                Some("DEFAULT_CHAR") => default_encoding = Some(parse_numbers(fields, 1, line_no)?[0]),
+                Some("STARTCHAR") => {
+                    if let Some((encoding, glyph)) = parse_glyph(&mut lines)? {
+                        if let Some(c) = u32::try_from(encoding).ok().and_then(char::from_u32) {
+                            glyphs.insert(c, glyph);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if glyphs.is_empty() {
+            return Err("BDF font contains no glyphs".to_string());
+        }
+
+        // Fall back to the bounding box when the ascent/descent properties are missing
+        let (ascent, descent) = match (ascent, descent, bounding_box) {
+            (Some(a), Some(d), _) => (a, d),
+            (_, _, Some((height, y_offset))) => (height + y_offset, -y_offset),
+            _ => return Err("BDF font has no FONT_ASCENT/FONT_DESCENT or FONTBOUNDINGBOX".to_string()),
+        };
+
+        let default_char = default_encoding
+            .and_then(|e| u32::try_from(e).ok())
+            .and_then(char::from_u32)
+            .filter(|c| glyphs.contains_key(c))
+            .or_else(|| Some('?').filter(|c| glyphs.contains_key(c)));
+
+        Ok(Self { glyphs, ascent, descent, default_char })
+    }
+
+    // Glyph for `c`, or the font's default glyph for characters it does not cover
+    pub fn glyph(&self, c: char) -> Option<&Glyph> {
+        self.glyphs
+            .get(&c)
+            .or_else(|| self.default_char.and_then(|d| self.glyphs.get(&d)))
+    }
+
+    pub fn line_height(&self, scale: usize) -> usize {
+        (self.ascent + self.descent).max(0) as usize * scale
+    }
+
+    // Width of a single line of text in pixels
+    pub fn text_width(&self, text: &str, scale: usize) -> usize {
+        text.chars()
+            .map(|c| self.glyph(c).map_or(0, |g| g.advance))
+            .sum::<usize>()
+            * scale
+    }
+
+    // Width of the widest line and total height of a possibly multi-line text
+    pub fn measure(&self, text: &str, scale: usize) -> (usize, usize) {
+        let width = text.lines().map(|line| self.text_width(line, scale)).max().unwrap_or(0);
+        let line_count = text.lines().count().max(1);
+        (width, line_count * self.line_height(scale))
+    }
+
+    // Break text into lines no wider than `max_width`. Existing line breaks are kept,
+    // lines are broken between words where possible and inside words otherwise.
+    pub fn wrap(&self, text: &str, max_width: usize, scale: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+
+        for paragraph in text.split('\n') {
+            let mut current = String::new();
+            for word in paragraph.split(' ') {
+                let candidate = if current.is_empty() {
+                    word.to_string()
+                } else {
+                    format!("{} {}", current, word)
+                };
+                if self.text_width(&candidate, scale) <= max_width {
+                    current = candidate;
+                    continue;
+                }
+
+                if !current.is_empty() {
+                    lines.push(std::mem::take(&mut current));
+                }
+                // Split words that are too long for a line on their own
+                for c in word.chars() {
+                    current.push(c);
+                    if self.text_width(&current, scale) > max_width && current.chars().count() > 1 {
+                        current.pop();
+                        lines.push(std::mem::replace(&mut current, c.to_string()));
+                    }
+                }
+            }
+            lines.push(current);
+        }
+
+        lines
+    }
+
+    // Call `plot` for every lit pixel of a line of text whose top-left corner is at (x, y)
+    pub fn render<F: FnMut(i32, i32)>(&self, x: i32, y: i32, text: &str, scale: usize, mut plot: F) {
+        let scale_i = scale as i32;
+        let baseline = y + self.ascent * scale_i;
+        let mut pen_x = x;
+
+        for c in text.chars() {
+            let Some(glyph) = self.glyph(c) else {
+                continue;
+            };
+            let top = baseline - (glyph.y_offset + glyph.height as i32) * scale_i;
+            let left = pen_x + glyph.x_offset * scale_i;
+
+            for row in 0..glyph.height {
+                for col in 0..glyph.width {
+                    if glyph.is_set(col, row) {
+                        let px = left + col as i32 * scale_i;
+                        let py = top + row as i32 * scale_i;
+                        for dy in 0..scale_i {
+                            for dx in 0..scale_i {
+                                plot(px + dx, py + dy);
+                            }
+                        }
+                    }
+                }
+            }
+            pen_x += (glyph.advance * scale) as i32;
+        }
+    }
+}
+
+fn parse_numbers<'a>(fields: impl Iterator<Item = &'a str>, count: usize, line_no: usize) -> Result<Vec<i32>, String> {
+    let values: Vec<i32> = fields
+        .take(count)
+        .map(|f| f.parse::<i32>())
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Invalid number on line {}: {}", line_no + 1, e))?;
+    if values.len() < count {
+        return Err(format!("Expected {} values on line {}", count, line_no + 1));
+    }
+    Ok(values)
+}
+
+// Parse one STARTCHAR..ENDCHAR block. Returns None for glyphs without an encoding.
+fn parse_glyph<'a>(lines: &mut impl Iterator<Item = (usize, &'a str)>) -> Result<Option<(i32, Glyph)>, String> {
+    let mut encoding = -1;
+    let mut advance = None;
+    let mut bbx = None;
+
+    while let Some((line_no, line)) = lines.next() {
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some("ENCODING") => encoding = parse_numbers(fields, 1, line_no)?[0],
+            Some("DWIDTH") => advance = Some(parse_numbers(fields, 1, line_no)?[0]),
+            Some("BBX") => bbx = Some(parse_numbers(fields, 4, line_no)?),
+            Some("BITMAP") => {
+                let bbx = bbx.ok_or_else(|| format!("Glyph without BBX before line {}", line_no + 1))?;
+                let (width, height) = (bbx[0].max(0) as usize, bbx[1].max(0) as usize);
+                let mut bitmap = Vec::with_capacity(width * height);
+
+                for _ in 0..height {
+                    let (row_no, row) = lines
+                        .next()
+                        .ok_or_else(|| "Unexpected end of BDF data in BITMAP".to_string())?;
+                    let row = row.trim();
+                    let bits = u64::from_str_radix(row, 16)
+                        .map_err(|e| format!("Invalid bitmap row on line {}: {}", row_no + 1, e))?;
+                    let row_bits = row.len() * 4;
+                    for col in 0..width {
+                        bitmap.push(col < row_bits && (bits >> (row_bits - 1 - col)) & 1 == 1);
+                    }
+                }
+
+                for (_, line) in lines.by_ref() {
+                    if line.trim() == "ENDCHAR" {
+                        break;
+                    }
+                }
+
+                if encoding < 0 {
+                    return Ok(None);
+                }
+                let glyph = Glyph {
+                    advance: advance.unwrap_or(bbx[0]).max(0) as usize,
+                    width,
+                    height,
+                    x_offset: bbx[2],
+                    y_offset: bbx[3],
+                    bitmap,
+                };
+                return Ok(Some((encoding, glyph)));
+            }
+            _ => {}
+        }
+    }
+
+    Err("Unexpected end of BDF data in glyph".to_string())
+}
@@ -0,0 +1,114 @@
+use crate::font::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Two-glyph font: 'A' is a 2x2 block, 'B' a single pixel sitting below the baseline
+    const TINY_BDF: &str = "STARTFONT 2.1
+FONTBOUNDINGBOX 3 4 0 -1
+STARTPROPERTIES 2
+FONT_ASCENT 3
+FONT_DESCENT 1
+ENDPROPERTIES
+CHARS 2
+STARTCHAR A
+ENCODING 65
+DWIDTH 3 0
+BBX 2 2 0 0
+BITMAP
+C0
+C0
+ENDCHAR
+STARTCHAR B
+ENCODING 66
+DWIDTH 3 0
+BBX 1 1 1 -1
+BITMAP
+80
+ENDCHAR
+ENDFONT
+";
+
+    fn rendered(font: &BitmapFont, text: &str, scale: usize) -> Vec<(i32, i32)> {
+        let mut pixels = Vec::new();
+        font.render(0, 0, text, scale, |x, y| pixels.push((x, y)));
+        pixels.sort();
+        pixels
+    }
+
+    #[test]
+    fn test_parse_bdf_reads_metrics_and_glyphs() {
+        let font = BitmapFont::parse_bdf(TINY_BDF).unwrap();
+        assert_eq!(font.ascent, 3);
+        assert_eq!(font.descent, 1);
+        assert_eq!(font.line_height(1), 4);
+
+        let a = font.glyph('A').unwrap();
+        assert_eq!((a.width, a.height, a.advance), (2, 2, 3));
+        assert!(a.is_set(0, 0) && a.is_set(1, 1));
+        assert!(!a.is_set(2, 0));
+    }
+
+    #[test]
+    fn test_parse_bdf_rejects_invalid_data() {
+        assert!(BitmapFont::parse_bdf("").is_err());
+        assert!(BitmapFont::parse_bdf("STARTFONT 2.1\nSTARTCHAR x\nENCODING 65\nBBX 1 1 0 0\nBITMAP\nZZ\nENDCHAR\n").is_err());
+    }
+
+    #[test]
+    fn test_render_places_glyphs_relative_to_baseline() {
+        let font = BitmapFont::parse_bdf(TINY_BDF).unwrap();
+        // 'A' sits on the baseline at y = 3, so it covers rows 1 and 2
+        assert_eq!(rendered(&font, "A", 1), vec![(0, 1), (0, 2), (1, 1), (1, 2)]);
+        // 'B' is one pixel below the baseline, offset by one column and one advance
+        assert_eq!(rendered(&font, "AB", 1).last(), Some(&(4, 3)));
+    }
+
+    #[test]
+    fn test_render_scales_glyphs() {
+        let font = BitmapFont::parse_bdf(TINY_BDF).unwrap();
+        let pixels = rendered(&font, "A", 2);
+        assert_eq!(pixels.len(), 16);
+        assert_eq!(pixels.first(), Some(&(0, 2)));
+        assert_eq!(pixels.last(), Some(&(3, 5)));
+    }
+
+    #[test]
+    fn test_measure_and_text_width() {
+        let font = BitmapFont::parse_bdf(TINY_BDF).unwrap();
+        assert_eq!(font.text_width("AAB", 1), 9);
+        assert_eq!(font.text_width("AAB", 2), 18);
+        assert_eq!(font.measure("A\nAAB", 1), (9, 8));
+    }
+
+    #[test]
+    fn test_builtin_font_covers_ascii_and_latin1() {
+        let font = BitmapFont::builtin();
+        for c in (0x20u8..=0x7E).chain(0xA0..=0xFF) {
+            assert!(font.glyph(c as char).is_some(), "missing glyph for {:?}", c as char);
+        }
+        assert_eq!(font.text_width("Chaikin", 1), 7 * 8);
+        assert_eq!(font.line_height(1), 13);
+
+        // Characters outside the font fall back to the default glyph
+        assert_eq!(font.glyph('\u{4E2D}'), font.glyph('\0'));
+    }
+
+    #[test]
+    fn test_wrap_breaks_between_words() {
+        let font = BitmapFont::builtin();
+        let lines = font.wrap("Press Enter to start the animation", 12 * 8, 1);
+        assert_eq!(lines, vec!["Press Enter", "to start the", "animation"]);
+        for line in &lines {
+            assert!(font.text_width(line, 1) <= 12 * 8);
+        }
+    }
+
+    #[test]
+    fn test_wrap_keeps_newlines_and_splits_long_words() {
+        let font = BitmapFont::builtin();
+        assert_eq!(font.wrap("ab\ncd", 100, 1), vec!["ab", "cd"]);
+        assert_eq!(font.wrap("abcdefgh", 3 * 8, 1), vec!["abc", "def", "gh"]);
+    }
+}
@@ -0,0 +1,51 @@
+// One frame of a subdivision animation, split into the layers renderers, exporters and
+// tests care about, so nothing has to be told apart by colour or position in a list
+use super::point::Point;
+use super::scheme::Scheme;
+
+// Extra teaching layers a frame can carry. They cost time to build, so frames only
+// include the ones asked for.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Overlays {
+    // Every level from the control polygon up to the previous level
+    pub trail: bool,
+    // Chaikin's cut points on each edge of the previous level
+    pub construction: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameInfo {
+    // Level the animation is moving away from; 0 is the control polygon
+    pub step: usize,
+    pub max_steps: usize,
+    // How far the curve has moved from the previous level to the next, 0 to 1
+    pub progress: f64,
+    pub closed: bool,
+    pub scheme: Scheme,
+}
+
+// Layers that are already stored elsewhere, such as the levels and the blended curve, are
+// borrowed rather than copied, so stepping an animation doesn't allocate unless the
+// construction overlay is on
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame<'a, const D: usize = 2> {
+    // The control points as placed by the user
+    pub control_polygon: &'a [Point<D>],
+    // Subdivision level the animation starts from and the one it heads for
+    pub previous_level: &'a [Point<D>],
+    pub next_level: &'a [Point<D>],
+    // The curve on screen, between the previous and next level. Empty when there are
+    // too few control points to subdivide.
+    pub curve: &'a [Point<D>],
+    // Levels 0 to `info.step`, coarsest first, when the trail overlay is on
+    pub trail: &'a [Vec<Point<D>>],
+    // The two cut points taken from each edge of the previous level, when the
+    // construction overlay is on and Chaikin's scheme is refining it
+    pub construction: Vec<(Point<D>, Point<D>)>,
+    pub info: FrameInfo,
+}
+
+// Screen positions of a layer's points
+pub fn positions(points: &[Point]) -> Vec<(f64, f64)> {
+    points.iter().map(|p| (p.position.x, p.position.y)).collect()
+}
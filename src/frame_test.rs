@@ -0,0 +1,105 @@
+use crate::frame::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chaikin::Chaikin;
+    use crate::point::Point;
+    use crate::scheme::Scheme;
+
+    fn triangle() -> Vec<Point> {
+        vec![Point::new(0.0, 0.0), Point::new(10.0, 10.0), Point::new(20.0, 0.0)]
+    }
+
+    #[test]
+    fn test_positions_follow_point_order() {
+        let points = triangle();
+        assert_eq!(positions(&points), vec![(0.0, 0.0), (10.0, 10.0), (20.0, 0.0)]);
+        assert!(positions(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_step_reports_each_layer() {
+        let mut chaikin = Chaikin::new(triangle());
+        chaikin.closed = true;
+        chaikin.scheme = Scheme::CubicBSpline;
+        let (refined, max_steps) = (chaikin.subdivide(&triangle()), chaikin.max_steps);
+        let frame = chaikin.step();
+
+        assert_eq!(frame.control_polygon, triangle());
+        assert_eq!(frame.previous_level, triangle());
+        assert_eq!(frame.next_level, refined);
+        // Closed curves move every point from the coarse level towards the fine one
+        assert_eq!(frame.curve.len(), frame.next_level.len());
+        assert_eq!(frame.info.step, 0);
+        assert_eq!(frame.info.max_steps, max_steps);
+        assert!(frame.info.closed);
+        assert_eq!(frame.info.scheme, Scheme::CubicBSpline);
+        assert!((0.0..1.0).contains(&frame.info.progress));
+    }
+
+    #[test]
+    fn test_step_on_finished_transition_moves_to_next_level() {
+        let mut chaikin = Chaikin::new(triangle());
+        chaikin.step();
+        chaikin.animation_progress = 1.0;
+        let frame = chaikin.step();
+        assert_eq!(frame.info.step, 1);
+        assert_eq!(frame.previous_level.len(), 6);
+        assert_eq!(frame.next_level.len(), 12);
+    }
+
+    #[test]
+    fn test_too_few_points_give_no_curve() {
+        let mut chaikin = Chaikin::new(vec![Point::new(5.0, 5.0)]);
+        let frame = chaikin.step();
+        assert_eq!(frame.control_polygon.len(), 1);
+        assert!(frame.curve.is_empty());
+        assert!(!frame.info.closed);
+    }
+
+    #[test]
+    fn test_overlays_are_only_built_when_asked_for() {
+        let mut chaikin = Chaikin::new(triangle());
+        let frame = chaikin.step();
+        assert!(frame.trail.is_empty() && frame.construction.is_empty());
+
+        chaikin.overlays = Overlays { trail: true, construction: true };
+        let frame = chaikin.step();
+        assert_eq!(frame.trail, vec![triangle()]);
+        assert_eq!(frame.construction.len(), 2);
+    }
+
+    #[test]
+    fn test_trail_holds_every_level_up_to_the_current_one() {
+        let mut chaikin = Chaikin::new(triangle());
+        chaikin.overlays.trail = true;
+        chaikin.step();
+        for _ in 0..3 {
+            chaikin.animation_progress = 1.0;
+            chaikin.step();
+        }
+        let frame = chaikin.step();
+        let sizes: Vec<usize> = frame.trail.iter().map(|level| level.len()).collect();
+        assert_eq!(sizes, vec![3, 6, 12, 24]);
+        assert_eq!(frame.trail.last().map(Vec::as_slice), Some(frame.previous_level));
+
+        // New control points start the trail over
+        chaikin.set_points(vec![Point::new(0.0, 0.0), Point::new(5.0, 0.0)]);
+        assert_eq!(chaikin.step().trail.len(), 1);
+    }
+
+    #[test]
+    fn test_construction_cut_points_match_next_level() {
+        let mut chaikin = Chaikin::new(triangle());
+        chaikin.overlays.construction = true;
+        let frame = chaikin.step();
+        let cuts: Vec<Point> = frame.construction.iter().flat_map(|(a, b)| [a.clone(), b.clone()]).collect();
+        // Open curves keep their end points; everything in between is a cut point
+        assert_eq!(cuts, frame.next_level[1..frame.next_level.len() - 1]);
+
+        // Other schemes don't cut corners
+        chaikin.set_scheme(Scheme::CubicBSpline);
+        assert!(chaikin.step().construction.is_empty());
+    }
+}
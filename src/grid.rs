@@ -0,0 +1,117 @@
+pub struct Grid {
+    pub spacing: f64,
+    pub visible: bool,
+    pub snap_to_grid: bool,
+    pub snap_to_points: bool,
+    pub angle_step: f64,
+    pub point_snap_radius: f64,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Self::new(25.0)
+    }
+}
+
+impl Grid {
+    pub fn new(spacing: f64) -> Self {
+        Self {
+            spacing,
+            visible: false,
+            snap_to_grid: false,
+            snap_to_points: false,
+            angle_step: 15.0,
+            point_snap_radius: 10.0,
+        }
+    }
+
+    // Round a position to the nearest grid intersection
+    pub fn snap_to_grid_point(&self, x: f64, y: f64) -> (f64, f64) {
+        if self.spacing <= 0.0 {
+            return (x, y);
+        }
+        (
+            (x / self.spacing).round() * self.spacing,
+            (y / self.spacing).round() * self.spacing,
+        )
+    }
+
+    // Constrain the direction from `anchor` to the position to a multiple of `angle_step`
+    // degrees, keeping the distance from the anchor
+    pub fn snap_angle(&self, anchor: (f64, f64), x: f64, y: f64) -> (f64, f64) {
+        let dx = x - anchor.0;
+        let dy = y - anchor.1;
+        let length = (dx * dx + dy * dy).sqrt();
+        if length == 0.0 || self.angle_step <= 0.0 {
+            return (x, y);
+        }
+
+        let step = self.angle_step.to_radians();
+        let angle = (dy.atan2(dx) / step).round() * step;
+        (anchor.0 + length * angle.cos(), anchor.1 + length * angle.sin())
+    }
+
+    // Find the closest existing point within `point_snap_radius`, ignoring `exclude`
+    pub fn snap_to_existing(
+        &self,
+        points: &[(f64, f64)],
+        x: f64,
+        y: f64,
+        exclude: Option<usize>,
+    ) -> Option<(f64, f64)> {
+        let mut closest = None;
+        let mut closest_dist = self.point_snap_radius;
+
+        for (i, &(px, py)) in points.iter().enumerate() {
+            if Some(i) == exclude {
+                continue;
+            }
+            let dist = ((px - x).powi(2) + (py - y).powi(2)).sqrt();
+            if dist <= closest_dist {
+                closest = Some((px, py));
+                closest_dist = dist;
+            }
+        }
+
+        closest
+    }
+
+    // Apply every enabled snapping rule to a position. Existing points win over angle
+    // snapping, which wins over the grid, so a point can always be closed onto another one.
+    pub fn snap(
+        &self,
+        points: &[(f64, f64)],
+        x: f64,
+        y: f64,
+        exclude: Option<usize>,
+        angle_anchor: Option<(f64, f64)>,
+    ) -> (f64, f64) {
+        if self.snap_to_points {
+            if let Some(pos) = self.snap_to_existing(points, x, y, exclude) {
+                return pos;
+            }
+        }
+
+        if let Some(anchor) = angle_anchor {
+            return self.snap_angle(anchor, x, y);
+        }
+
+        if self.snap_to_grid {
+            return self.snap_to_grid_point(x, y);
+        }
+
+        (x, y)
+    }
+
+    pub fn cycle_angle_step(&mut self) {
+        self.angle_step = if self.angle_step == 15.0 { 45.0 } else { 15.0 };
+    }
+
+    pub fn increase_spacing(&mut self) {
+        self.spacing = (self.spacing + 5.0).min(200.0);
+    }
+
+    pub fn decrease_spacing(&mut self) {
+        self.spacing = (self.spacing - 5.0).max(5.0);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::grid::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: (f64, f64), expected: (f64, f64)) {
+        assert!((actual.0 - expected.0).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        assert!((actual.1 - expected.1).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+    }
+
+    #[test]
+    fn test_new_grid_has_snapping_disabled() {
+        let grid = Grid::new(20.0);
+        assert_eq!(grid.spacing, 20.0);
+        assert!(!grid.visible);
+        assert!(!grid.snap_to_grid);
+        assert!(!grid.snap_to_points);
+    }
+
+    #[test]
+    fn test_snap_to_grid_point_rounds_to_nearest_intersection() {
+        let grid = Grid::new(20.0);
+        assert_close(grid.snap_to_grid_point(29.0, 31.0), (20.0, 40.0));
+        assert_close(grid.snap_to_grid_point(-9.0, 11.0), (0.0, 20.0));
+    }
+
+    #[test]
+    fn test_snap_angle_keeps_distance_and_rounds_direction() {
+        let mut grid = Grid::new(20.0);
+        grid.angle_step = 45.0;
+
+        // Slightly above the horizontal snaps onto it
+        let snapped = grid.snap_angle((0.0, 0.0), 10.0, 1.0);
+        let length = (101.0f64).sqrt();
+        assert_close(snapped, (length, 0.0));
+
+        // Close to the diagonal snaps onto the diagonal
+        let snapped = grid.snap_angle((10.0, 10.0), 20.0, 21.0);
+        assert!((snapped.0 - snapped.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_snap_to_existing_ignores_excluded_and_far_points() {
+        let grid = Grid::new(20.0);
+        let points = vec![(0.0, 0.0), (100.0, 100.0)];
+
+        assert_eq!(grid.snap_to_existing(&points, 3.0, 4.0, None), Some((0.0, 0.0)));
+        assert_eq!(grid.snap_to_existing(&points, 3.0, 4.0, Some(0)), None);
+        assert_eq!(grid.snap_to_existing(&points, 50.0, 50.0, None), None);
+    }
+
+    #[test]
+    fn test_snap_priority() {
+        let mut grid = Grid::new(20.0);
+        grid.snap_to_grid = true;
+        grid.snap_to_points = true;
+        let points = vec![(33.0, 33.0)];
+
+        // Existing point wins over the grid
+        assert_eq!(grid.snap(&points, 35.0, 35.0, None, None), (33.0, 33.0));
+        // Grid is used away from existing points
+        assert_close(grid.snap(&points, 78.0, 61.0, None, None), (80.0, 60.0));
+        // Angle snapping wins over the grid while an anchor is given
+        let snapped = grid.snap(&points, 100.0, 2.0, None, Some((0.0, 0.0)));
+        assert!(snapped.1.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_snap_without_rules_returns_input() {
+        let grid = Grid::new(20.0);
+        assert_eq!(grid.snap(&[], 12.3, 45.6, None, None), (12.3, 45.6));
+    }
+
+    #[test]
+    fn test_spacing_and_angle_step_adjustments() {
+        let mut grid = Grid::new(10.0);
+        grid.decrease_spacing();
+        grid.decrease_spacing();
+        assert_eq!(grid.spacing, 5.0);
+        grid.increase_spacing();
+        assert_eq!(grid.spacing, 10.0);
+
+        grid.cycle_angle_step();
+        assert_eq!(grid.angle_step, 45.0);
+        grid.cycle_angle_step();
+        assert_eq!(grid.angle_step, 15.0);
+    }
+}
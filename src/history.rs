@@ -0,0 +1,35 @@
+// Undo / redo stacks of whole states. Callers push the state from before an edit.
+
+pub struct History<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+    limit: usize,
+}
+
+impl<T> History<T> {
+    pub fn new(limit: usize) -> Self {
+        Self { undo: Vec::new(), redo: Vec::new(), limit: limit.max(1) }
+    }
+
+    // Record the state from before an edit. A new edit discards anything undone.
+    pub fn push(&mut self, state: T) {
+        if self.undo.len() == self.limit {
+            self.undo.remove(0);
+        }
+        self.undo.push(state);
+        self.redo.clear();
+    }
+
+    // Step back, returning the state to restore. `current` is kept for redo.
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let state = self.undo.pop()?;
+        self.redo.push(current);
+        Some(state)
+    }
+
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let state = self.redo.pop()?;
+        self.undo.push(current);
+        Some(state)
+    }
+}
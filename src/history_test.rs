@@ -0,0 +1,41 @@
+use crate::history::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undo_and_redo_walk_through_states() {
+        let mut history = History::new(10);
+        history.push(1);
+        history.push(2);
+
+        assert_eq!(history.undo(3), Some(2));
+        assert_eq!(history.undo(2), Some(1));
+        assert_eq!(history.undo(1), None);
+
+        assert_eq!(history.redo(1), Some(2));
+        assert_eq!(history.redo(2), Some(3));
+        assert_eq!(history.redo(3), None);
+    }
+
+    #[test]
+    fn test_new_edit_clears_redo() {
+        let mut history = History::new(10);
+        history.push(1);
+        assert_eq!(history.undo(2), Some(1));
+        history.push(1);
+        assert_eq!(history.redo(5), None);
+    }
+
+    #[test]
+    fn test_limit_drops_oldest_state() {
+        let mut history = History::new(2);
+        history.push(1);
+        history.push(2);
+        history.push(3);
+        assert_eq!(history.undo(4), Some(3));
+        assert_eq!(history.undo(3), Some(2));
+        assert_eq!(history.undo(2), None);
+    }
+}
@@ -1,27 +1,209 @@
 use minifb::{Key, MouseButton, MouseMode};
+use super::chaikin::{MAX_SPEED, MAX_STEPS, MIN_SPEED, MIN_STEPS};
+use super::compare::Comparison;
+use super::fit::fit_to_tolerance;
+use super::frame::Overlays;
+use super::grid::Grid;
+use super::history::History;
+use super::raster::FillRule;
+use super::scene::{Scene, SceneSnapshot, PAINT_COLORS};
+use super::scheme::Scheme;
+use super::selection::{self, Axis, Selection, TransformDrag};
+use super::sketch::{Simplifier, MAX_TOLERANCE, MIN_TOLERANCE};
+use super::stroke::StrokeStyle;
+use super::theme::Theme;
+use super::ui::{self, UiState};
+
+// Key and mouse bindings shown in the help overlay
+pub const KEY_BINDINGS: &[(&str, &str)] = &[
+    ("Left click", "Add or drag a point / select a curve"),
+    ("Shift+click", "Add / remove a point from the selection"),
+    ("Right drag", "Select points in a box (Shift: add)"),
+    ("Enter", "Animate all curves"),
+    ("Shift+Enter", "Start / stop the selected curve"),
+    ("N", "Start a new curve"),
+    ("Space", "Clear the canvas"),
+    ("Esc", "Quit"),
+    ("C", "Toggle open / closed (selected curve)"),
+    ("M", "Cycle scheme (selected curve)"),
+    ("Up / Down", "More / fewer iterations (1-12)"),
+    ("Shift+Up / Down", "Edit a finer / coarser subdivision level"),
+    ("Right / Left", "Faster / slower animation"),
+    ("D", "Compare schemes: 2 / 4 viewports / off"),
+    ("O", "Toggle ghost trail of previous levels"),
+    ("I", "Toggle Chaikin construction cut points"),
+    ("F", "Cycle fill: off, solid, gradient"),
+    ("E", "Toggle non-zero / even-odd fill rule"),
+    ("= / -", "Curve stroke width (Shift: polygon)"),
+    ("J / K", "Curve joins / caps (Shift: polygon)"),
+    ("L", "Toggle anti-aliasing"),
+    ("G", "Toggle grid"),
+    ("[ / ]", "Grid spacing"),
+    ("S", "Toggle snap to grid"),
+    ("P", "Toggle snap to points"),
+    ("Shift", "Snap angle to previous point"),
+    ("A", "Angle step 15 / 45 degrees"),
+    ("Ctrl+A", "Select all points"),
+    ("Ctrl+Arrows", "Move selection (Shift: 10 px)"),
+    ("R", "Rotate selection 15° (Shift: back)"),
+    (", / .", "Shrink / grow selection"),
+    ("X / Y", "Mirror selection horizontally / vertically"),
+    ("Delete", "Delete selected points"),
+    ("V", "Toggle sharp corner at the hovered point"),
+    ("T", "Tighten the hovered point (Shift: loosen)"),
+    ("B", "Paint selected / hovered points (Shift: clear)"),
+    ("U", "Fit a compact control polygon to the curve"),
+    ("W", "Freehand sketch mode (Shift: simplifier)"),
+    ("Q", "Sketch tolerance up (Shift: down)"),
+    ("Z", "Adaptive subdivision of the still curve"),
+    ("Ctrl+Z / Ctrl+Y", "Undo / redo"),
+    ("Tab", "Open / close the settings panel"),
+    ("H", "Toggle heads-up display"),
+    ("F2", "Cycle colour theme"),
+    ("F1", "Help: next page / close"),
+];
+
+// How close a click has to be to a curve's control polygon to select it
+const CURVE_PICK_DISTANCE: f64 = 6.0;
+// How close a click has to be to a selection handle to grab it
+const HANDLE_PICK_RADIUS: f64 = 7.0;
+const UNDO_LIMIT: usize = 100;
+// How far the curve of a fitted control polygon may stray from the points it replaces
+const FIT_TOLERANCE: f64 = 1.5;
+// Spacing of the points recorded along a freehand stroke
+const SKETCH_SPACING: f64 = 2.0;
+// Finest subdivision level whose points can be dragged
+pub const MAX_EDIT_LEVEL: usize = 4;
+// How close the cursor has to be to a point to grab it
+const POINT_PICK_DISTANCE: f64 = 20.0;
+// Key bindings shown per page of the help overlay
+pub const HELP_ROWS_PER_PAGE: usize = 24;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FillMode {
+    Off,
+    Solid,
+    Gradient,
+}
+
+impl FillMode {
+    pub fn next(self) -> Self {
+        match self {
+            FillMode::Off => FillMode::Solid,
+            FillMode::Solid => FillMode::Gradient,
+            FillMode::Gradient => FillMode::Off,
+        }
+    }
+}
 
 pub struct InputHandler {
-    pub points: Vec<(f64, f64)>,
+    pub scene: Scene,
     pub mouse_down: bool,
     pub mouse_pos: (f64, f64),
-    pub is_animating: bool,
     pub should_close: bool,
     pub dragging_point: Option<usize>,
     pub message: Option<String>,
     pub key_cooldown: u32,
+    pub grid: Grid,
+    pub shift_down: bool,
+    pub antialias: bool,
+    pub curve_stroke: StrokeStyle,
+    pub polygon_stroke: StrokeStyle,
+    pub fill_mode: FillMode,
+    pub fill_rule: FillRule,
+    pub show_hud: bool,
+    pub show_help: bool,
+    pub help_page: usize,
+    pub max_steps: usize,
+    pub animation_speed: f64,
+    pub mouse_pressed: bool,
+    pub ui: UiState,
+    // Set while a press that started on the UI is held, so it never reaches the canvas
+    pub mouse_on_ui: bool,
+    pub ctrl_down: bool,
+    pub right_mouse_down: bool,
+    // Selected points of the selected curve
+    pub selection: Selection,
+    // Corners of the selection box while the right button is dragged
+    pub rubber_band: Option<((f64, f64), (f64, f64))>,
+    pub transform_drag: Option<TransformDrag>,
+    pub history: History<SceneSnapshot>,
+    // Colour applied by the paint key
+    pub paint_color: [u8; 3],
+    // Teaching layers drawn under animating curves
+    pub overlays: Overlays,
+    // Split view comparing configurations on the selected curve
+    pub comparison: Comparison,
+    // Built-in themes plus any loaded from a file, and the one in use
+    pub themes: Vec<Theme>,
+    pub theme: usize,
+    // Dragging on empty canvas draws a freehand stroke instead of adding a point
+    pub sketch_mode: bool,
+    pub simplifier: Simplifier,
+    // How far the simplified polygon may stray from the stroke, in pixels
+    pub sketch_tolerance: f64,
+    // Raw stroke being drawn
+    pub sketch_stroke: Option<Vec<(f64, f64)>>,
+    // Subdivision level being edited; above 0, drags move that level's points and the
+    // change is kept as detail on top of the control points
+    pub edit_level: usize,
+    pub dragging_level_point: Option<usize>,
+    // Show the selected still curve refined adaptively instead of uniformly
+    pub adaptive: bool,
+    // Scene before the current mouse drag, recorded in the history once the drag ends
+    pending_edit: Option<SceneSnapshot>,
+}
+
+impl Default for InputHandler {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl InputHandler {
     pub fn new() -> Self {
         Self {
-            points: Vec::new(),
+            scene: Scene::new(),
             mouse_down: false,
             mouse_pos: (0.0, 0.0),
-            is_animating: false,
             should_close: false,
             dragging_point: None,
             message: None,
             key_cooldown: 0,
+            grid: Grid::default(),
+            shift_down: false,
+            antialias: true,
+            curve_stroke: StrokeStyle::default(),
+            polygon_stroke: StrokeStyle::default(),
+            fill_mode: FillMode::Off,
+            fill_rule: FillRule::NonZero,
+            show_hud: true,
+            show_help: false,
+            help_page: 0,
+            max_steps: 7,
+            animation_speed: 1.0,
+            mouse_pressed: false,
+            ui: UiState::default(),
+            mouse_on_ui: false,
+            ctrl_down: false,
+            right_mouse_down: false,
+            selection: Selection::new(),
+            rubber_band: None,
+            transform_drag: None,
+            history: History::new(UNDO_LIMIT),
+            paint_color: PAINT_COLORS[0],
+            overlays: Overlays::default(),
+            comparison: Comparison::new(),
+            themes: Theme::builtin(),
+            theme: 0,
+            sketch_mode: false,
+            simplifier: Simplifier::DouglasPeucker,
+            sketch_tolerance: 3.0,
+            sketch_stroke: None,
+            edit_level: 0,
+            dragging_level_point: None,
+            adaptive: false,
+            pending_edit: None,
         }
     }
 
@@ -32,6 +214,16 @@ impl InputHandler {
 
         let was_mouse_down = self.mouse_down;
         self.mouse_down = window.get_mouse_down(MouseButton::Left);
+        self.mouse_pressed = self.mouse_down && !was_mouse_down;
+        if self.mouse_pressed {
+            self.mouse_on_ui = ui::covers(&self.ui, self.mouse_pos);
+        } else if !self.mouse_down {
+            self.mouse_on_ui = false;
+        }
+        self.shift_down = window.is_key_down(Key::LeftShift) || window.is_key_down(Key::RightShift);
+        self.ctrl_down = window.is_key_down(Key::LeftCtrl) || window.is_key_down(Key::RightCtrl);
+        let was_right_down = self.right_mouse_down;
+        self.right_mouse_down = window.get_mouse_down(MouseButton::Right);
 
         // Decrement cooldown timer
         if self.key_cooldown > 0 {
@@ -39,37 +231,217 @@ impl InputHandler {
         }
 
         // Handle key presses
+        let nudge = if self.shift_down { 10.0 } else { 1.0 };
         if window.is_key_down(Key::Escape) {
             self.should_close = true;
+        } else if self.ctrl_down && window.is_key_down(Key::Z) && self.key_cooldown == 0 {
+            self.undo();
+            self.key_cooldown = 15;
+        } else if self.ctrl_down && window.is_key_down(Key::Y) && self.key_cooldown == 0 {
+            self.redo();
+            self.key_cooldown = 15;
+        } else if self.ctrl_down && window.is_key_down(Key::A) && self.key_cooldown == 0 {
+            self.selection.select_all(self.points().len());
+            self.key_cooldown = 20;
+        } else if self.ctrl_down && window.is_key_down(Key::Up) && self.key_cooldown == 0 {
+            self.nudge_selection(0.0, -nudge);
+            self.key_cooldown = 5;
+        } else if self.ctrl_down && window.is_key_down(Key::Down) && self.key_cooldown == 0 {
+            self.nudge_selection(0.0, nudge);
+            self.key_cooldown = 5;
+        } else if self.ctrl_down && window.is_key_down(Key::Left) && self.key_cooldown == 0 {
+            self.nudge_selection(-nudge, 0.0);
+            self.key_cooldown = 5;
+        } else if self.ctrl_down && window.is_key_down(Key::Right) && self.key_cooldown == 0 {
+            self.nudge_selection(nudge, 0.0);
+            self.key_cooldown = 5;
+        } else if window.is_key_down(Key::R) && self.key_cooldown == 0 {
+            let angle = if self.shift_down { -15.0f64 } else { 15.0 };
+            self.rotate_selection(angle.to_radians());
+            self.key_cooldown = 10;
+        } else if window.is_key_down(Key::Period) && self.key_cooldown == 0 {
+            self.scale_selection(1.1);
+            self.key_cooldown = 10;
+        } else if window.is_key_down(Key::Comma) && self.key_cooldown == 0 {
+            self.scale_selection(1.0 / 1.1);
+            self.key_cooldown = 10;
+        } else if window.is_key_down(Key::X) && self.key_cooldown == 0 {
+            self.mirror_selection(Axis::Horizontal);
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::Y) && self.key_cooldown == 0 {
+            self.mirror_selection(Axis::Vertical);
+            self.key_cooldown = 20;
+        } else if (window.is_key_down(Key::Delete) || window.is_key_down(Key::Backspace)) && self.key_cooldown == 0 {
+            self.delete_selection();
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::V) && self.key_cooldown == 0 {
+            self.toggle_crease();
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::T) && self.key_cooldown == 0 {
+            let tighten = !self.shift_down;
+            self.change_weight(tighten);
+            self.key_cooldown = 10;
+        } else if window.is_key_down(Key::B) && self.key_cooldown == 0 {
+            let color = if self.shift_down { None } else { Some(self.paint_color) };
+            self.paint_points(color);
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::U) && self.key_cooldown == 0 {
+            self.fit_curve();
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::W) && self.key_cooldown == 0 {
+            if self.shift_down {
+                self.simplifier = self.simplifier.next();
+            } else {
+                self.sketch_mode = !self.sketch_mode;
+            }
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::Z) && self.key_cooldown == 0 {
+            self.adaptive = !self.adaptive;
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::Q) && self.key_cooldown == 0 {
+            let factor = if self.shift_down { 0.8 } else { 1.25 };
+            self.change_sketch_tolerance(factor);
+            self.key_cooldown = 10;
         } else if window.is_key_down(Key::Enter) && self.key_cooldown == 0 {
-            if self.points.is_empty() {
-                self.message = Some("Please draw control points first!\nPress Enter to continue".to_string());
-            } else if !self.is_animating {
-                self.is_animating = true;
+            if self.shift_down {
+                if !self.scene.toggle_selected_animation() {
+                    self.no_points_message();
+                }
+            } else {
+                self.animate_all();
             }
             self.key_cooldown = 20; 
         } else if window.is_key_down(Key::Space) && self.key_cooldown == 0 {
-            self.points.clear();
-            self.is_animating = false;
+            self.clear_canvas();
             self.key_cooldown = 20; 
+        } else if window.is_key_down(Key::N) && self.key_cooldown == 0 {
+            self.new_curve();
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::G) && self.key_cooldown == 0 {
+            self.grid.visible = !self.grid.visible;
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::S) && self.key_cooldown == 0 {
+            self.grid.snap_to_grid = !self.grid.snap_to_grid;
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::P) && self.key_cooldown == 0 {
+            self.grid.snap_to_points = !self.grid.snap_to_points;
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::A) && self.key_cooldown == 0 {
+            self.grid.cycle_angle_step();
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::L) && self.key_cooldown == 0 {
+            self.antialias = !self.antialias;
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::C) && self.key_cooldown == 0 {
+            self.set_closed(!self.scene.selected().closed);
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::D) && self.key_cooldown == 0 {
+            self.comparison.cycle();
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::O) && self.key_cooldown == 0 {
+            self.overlays.trail = !self.overlays.trail;
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::I) && self.key_cooldown == 0 {
+            self.overlays.construction = !self.overlays.construction;
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::F) && self.key_cooldown == 0 {
+            self.fill_mode = self.fill_mode.next();
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::E) && self.key_cooldown == 0 {
+            self.fill_rule = match self.fill_rule {
+                FillRule::NonZero => FillRule::EvenOdd,
+                FillRule::EvenOdd => FillRule::NonZero,
+            };
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::M) && self.key_cooldown == 0 {
+            self.set_scheme(self.scene.selected().scheme.next());
+            self.key_cooldown = 20;
+        } else if self.shift_down && window.is_key_down(Key::Up) && self.key_cooldown == 0 {
+            self.change_edit_level(1);
+            self.key_cooldown = 15;
+        } else if self.shift_down && window.is_key_down(Key::Down) && self.key_cooldown == 0 {
+            self.change_edit_level(-1);
+            self.key_cooldown = 15;
+        } else if window.is_key_down(Key::Up) && self.key_cooldown == 0 {
+            self.change_iterations(1);
+            self.key_cooldown = 10;
+        } else if window.is_key_down(Key::Down) && self.key_cooldown == 0 {
+            self.change_iterations(-1);
+            self.key_cooldown = 10;
+        } else if window.is_key_down(Key::Right) && self.key_cooldown == 0 {
+            self.change_speed(1.25);
+            self.key_cooldown = 10;
+        } else if window.is_key_down(Key::Left) && self.key_cooldown == 0 {
+            self.change_speed(0.8);
+            self.key_cooldown = 10;
+        } else if window.is_key_down(Key::Tab) && self.key_cooldown == 0 {
+            self.ui.panel_open = !self.ui.panel_open;
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::H) && self.key_cooldown == 0 {
+            self.show_hud = !self.show_hud;
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::F1) && self.key_cooldown == 0 {
+            self.cycle_help();
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::F2) && self.key_cooldown == 0 {
+            self.cycle_theme();
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::Equal) && self.key_cooldown == 0 {
+            self.stroke_mut().increase_width();
+            self.key_cooldown = 10;
+        } else if window.is_key_down(Key::Minus) && self.key_cooldown == 0 {
+            self.stroke_mut().decrease_width();
+            self.key_cooldown = 10;
+        } else if window.is_key_down(Key::J) && self.key_cooldown == 0 {
+            self.stroke_mut().cycle_join();
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::K) && self.key_cooldown == 0 {
+            self.stroke_mut().cycle_cap();
+            self.key_cooldown = 20;
+        } else if window.is_key_down(Key::RightBracket) && self.key_cooldown == 0 {
+            self.grid.increase_spacing();
+            self.key_cooldown = 10;
+        } else if window.is_key_down(Key::LeftBracket) && self.key_cooldown == 0 {
+            self.grid.decrease_spacing();
+            self.key_cooldown = 10;
         }
 
         // Handle point dragging
-        if self.mouse_down {
-            if let Some(idx) = self.dragging_point {
-                self.points[idx] = self.mouse_pos;
-            } else if was_mouse_down {
-                if let Some(idx) = self.find_nearest_point() {
-                    self.dragging_point = Some(idx);
-                    self.points[idx] = self.mouse_pos;
-                }
-            } else if !self.is_animating {
-                // Add a new point
-                self.add_point(self.mouse_pos.0, self.mouse_pos.1);
+        if self.mouse_on_ui {
+            // Clicks on the settings panel are handled by the widgets
+        } else if self.mouse_down {
+            if let Some(drag) = &self.transform_drag {
+                let points = drag.apply(&self.selection, self.mouse_pos);
+                self.scene.selected_mut().points = points;
+            } else if let Some(idx) = self.dragging_point {
+                self.drag_point(idx);
+            } else if let Some(idx) = self.dragging_level_point {
+                let (level, pos) = (self.edit_level, self.mouse_pos);
+                self.scene.selected_mut().move_level_point(level, idx, pos);
+            } else if self.sketch_stroke.is_some() {
+                self.extend_stroke(self.mouse_pos);
+            } else if self.mouse_pressed {
+                self.press_on_canvas();
             }
         } else {
             // Mouse released
+            self.finish_stroke();
             self.dragging_point = None;
+            self.dragging_level_point = None;
+            self.transform_drag = None;
+            self.finish_edit();
+        }
+
+        // Rubber band selection with the right button
+        if self.right_mouse_down {
+            if let Some((_, end)) = &mut self.rubber_band {
+                *end = self.mouse_pos;
+            } else if !was_right_down {
+                self.rubber_band = Some((self.mouse_pos, self.mouse_pos));
+            }
+        } else if let Some((start, end)) = self.rubber_band.take() {
+            let additive = self.shift_down;
+            self.selection.select_in_rect(&self.scene.selected().points, start, end, additive);
         }
 
         if window.is_key_down(Key::Enter) && self.message.is_some() && self.key_cooldown == 0 {
@@ -78,36 +450,407 @@ impl InputHandler {
         }
     }
 
-    pub fn find_nearest_point(&self) -> Option<usize> {
-        let mut closest_idx = None;
-        let mut closest_dist = f64::MAX;
-        let (mx, my) = self.mouse_pos;
-        let threshold = 20.0;
-
-        for (i, &(x, y)) in self.points.iter().enumerate() {
-            let dx = x - mx;
-            let dy = y - my;
-            let dist = (dx * dx + dy * dy).sqrt();
-            
-            if dist < closest_dist && dist < threshold {
-                closest_idx = Some(i);
-                closest_dist = dist;
+    // A press grabs a selection handle or a point of the selected curve, selects another
+    // curve under the cursor, or adds a point to the selected curve when it isn't animating
+    // (in sketch mode it starts a freehand stroke instead). When a finer level is being
+    // edited it grabs one of that level's points instead of a control point.
+    // In the comparison view it picks the viewport the settings panel edits.
+    fn press_on_canvas(&mut self) {
+        let pos = self.mouse_pos;
+        if self.comparison.is_on() {
+            if let Some(index) = self.comparison.viewport_at(pos) {
+                self.comparison.active = index;
+            }
+            return;
+        }
+        if self.edit_level > 0 {
+            let level = self.edit_level;
+            let points: Vec<(f64, f64)> =
+                self.scene.selected_mut().level_points(level).iter().map(|p| (p.position.x, p.position.y)).collect();
+            if let Some(idx) = nearest_point(&points, pos) {
+                self.pending_edit = Some(self.scene.snapshot());
+                self.dragging_level_point = Some(idx);
+            } else if let Some(idx) = self.scene.curve_at(pos.0, pos.1, CURVE_PICK_DISTANCE) {
+                self.scene.select(idx);
+                self.selection.clear();
+            }
+            return;
+        }
+        if self.selection.len() > 1 {
+            let bounds = self.selection.bounds(self.points());
+            let handle = bounds.and_then(|b| selection::handle_at(b, pos, HANDLE_PICK_RADIUS));
+            let pivot = self.selection.pivot(self.points());
+            if let (Some(handle), Some(pivot)) = (handle, pivot) {
+                self.pending_edit = Some(self.scene.snapshot());
+                self.transform_drag = Some(TransformDrag::new(handle, pivot, pos, self.points()));
+                return;
+            }
+        }
+
+        if let Some(idx) = self.find_nearest_point() {
+            if self.shift_down {
+                self.selection.toggle(idx);
+                if !self.selection.contains(idx) {
+                    return;
+                }
+            } else if !self.selection.contains(idx) {
+                self.selection.select_only(idx);
             }
+            self.pending_edit = Some(self.scene.snapshot());
+            self.dragging_point = Some(idx);
+            return;
         }
 
-        closest_idx
+        match self.scene.curve_at(pos.0, pos.1, CURVE_PICK_DISTANCE) {
+            Some(idx) if idx != self.scene.selected => {
+                self.scene.select(idx);
+                self.selection.clear();
+            }
+            _ if !self.scene.selected().animating && self.sketch_mode => self.begin_stroke(pos),
+            _ if !self.scene.selected().animating => {
+                self.pending_edit = Some(self.scene.snapshot());
+                let (x, y) = self.snapped_position(None);
+                self.add_point(x, y);
+                self.selection.clear();
+                self.dragging_point = Some(self.points().len() - 1);
+            }
+            _ => self.selection.clear(),
+        }
     }
 
+    // Move the dragged point to the cursor, taking the rest of the selection along
+    fn drag_point(&mut self, idx: usize) {
+        let (x, y) = self.snapped_position(Some(idx));
+        let (old_x, old_y) = self.points()[idx];
+        let points = &mut self.scene.selected_mut().points;
+        if self.selection.contains(idx) {
+            self.selection.translate(points, x - old_x, y - old_y);
+        } else {
+            points[idx] = (x, y);
+        }
+    }
+
+    pub fn begin_stroke(&mut self, pos: (f64, f64)) {
+        self.pending_edit = Some(self.scene.snapshot());
+        self.selection.clear();
+        self.sketch_stroke = Some(vec![pos]);
+    }
+
+    // Record the cursor once it has moved far enough from the last recorded point
+    pub fn extend_stroke(&mut self, pos: (f64, f64)) {
+        if let Some(stroke) = &mut self.sketch_stroke {
+            let &(x, y) = stroke.last().unwrap();
+            if (pos.0 - x).hypot(pos.1 - y) >= SKETCH_SPACING {
+                stroke.push(pos);
+            }
+        }
+    }
+
+    // Simplified control polygon of the stroke being drawn
+    pub fn sketch_preview(&self) -> Option<Vec<(f64, f64)>> {
+        let stroke = self.sketch_stroke.as_ref()?;
+        Some(self.simplifier.simplify(stroke, self.sketch_tolerance))
+    }
+
+    // Turn the finished stroke into the control points of the selected curve, or of a new
+    // curve if the selected one already has points. Strokes too short to draw a line are
+    // dropped.
+    pub fn finish_stroke(&mut self) {
+        let Some(points) = self.sketch_preview() else {
+            return;
+        };
+        self.sketch_stroke = None;
+        if points.len() < 2 {
+            return;
+        }
+        self.scene.new_curve();
+        let curve = self.scene.selected_mut();
+        curve.points = points;
+        curve.attributes.clear();
+        self.finish_edit();
+    }
+
+    // Record the scene before an edit so it can be undone
+    fn record_edit(&mut self) {
+        self.history.push(self.scene.snapshot());
+    }
+
+    // Record a finished mouse drag, unless it didn't change anything
+    fn finish_edit(&mut self) {
+        if let Some(before) = self.pending_edit.take() {
+            if before != self.scene.snapshot() {
+                self.history.push(before);
+            }
+        }
+    }
+
+    pub fn undo(&mut self) {
+        if let Some(state) = self.history.undo(self.scene.snapshot()) {
+            self.restore(state);
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Some(state) = self.history.redo(self.scene.snapshot()) {
+            self.restore(state);
+        }
+    }
+
+    fn restore(&mut self, state: SceneSnapshot) {
+        self.scene.restore(state);
+        self.selection.retain_valid(self.points().len());
+        self.dragging_point = None;
+        self.dragging_level_point = None;
+        self.transform_drag = None;
+    }
+
+    // Apply a transform to the selected points as one undoable edit. The closure gets the
+    // selection's pivot.
+    fn edit_selection<F: FnOnce(&Selection, &mut Vec<(f64, f64)>, (f64, f64))>(&mut self, edit: F) {
+        let Some(pivot) = self.selection.pivot(self.points()) else {
+            return;
+        };
+        self.record_edit();
+        edit(&self.selection, &mut self.scene.selected_mut().points, pivot);
+    }
+
+    pub fn nudge_selection(&mut self, dx: f64, dy: f64) {
+        self.edit_selection(|selection, points, _| selection.translate(points, dx, dy));
+    }
+
+    pub fn rotate_selection(&mut self, angle: f64) {
+        self.edit_selection(|selection, points, pivot| selection.rotate(points, angle, pivot));
+    }
+
+    pub fn scale_selection(&mut self, factor: f64) {
+        self.edit_selection(|selection, points, pivot| selection.scale(points, factor, pivot));
+    }
+
+    pub fn mirror_selection(&mut self, axis: Axis) {
+        self.edit_selection(|selection, points, pivot| selection.mirror(points, axis, pivot));
+    }
+
+    // Start every curve that has points
+    pub fn animate_all(&mut self) {
+        if !self.scene.animate_all() {
+            self.no_points_message();
+        }
+    }
+
+    fn no_points_message(&mut self) {
+        self.message = Some("Please draw control points first!\nPress Enter to continue".to_string());
+    }
+
+    // Remove every curve. The selection indexed the old points, so it goes too.
+    pub fn clear_canvas(&mut self) {
+        self.record_edit();
+        self.scene.clear();
+        self.selection.clear();
+    }
+
+    pub fn new_curve(&mut self) {
+        self.record_edit();
+        self.scene.new_curve();
+        self.selection.clear();
+    }
+
+    pub fn set_closed(&mut self, closed: bool) {
+        if self.scene.selected().closed != closed {
+            self.record_edit();
+            self.scene.selected_mut().closed = closed;
+        }
+    }
+
+    pub fn set_scheme(&mut self, scheme: Scheme) {
+        if self.scene.selected().scheme != scheme {
+            self.record_edit();
+            self.scene.selected_mut().scheme = scheme;
+        }
+    }
+
+    pub fn set_curve_color(&mut self, color: [u8; 3]) {
+        if self.scene.selected().color != color {
+            self.record_edit();
+            self.scene.selected_mut().color = color;
+        }
+    }
+
+    // Make the point under the cursor a sharp corner, or smooth it again
+    pub fn toggle_crease(&mut self) {
+        let Some(idx) = self.find_nearest_point() else {
+            return;
+        };
+        self.record_edit();
+        let attributes = self.scene.selected_mut().attributes_mut(idx);
+        attributes.crease = !attributes.crease;
+    }
+
+    // Treat the selected curve's points as a dense polyline and replace them with the
+    // fewest control points whose Chaikin curve stays within FIT_TOLERANCE of it
+    pub fn fit_curve(&mut self) {
+        let curve = self.scene.selected();
+        if curve.points.len() < 4 {
+            return;
+        }
+        let before = curve.points.len();
+        let fit = fit_to_tolerance(&curve.control_points(), curve.closed, FIT_TOLERANCE);
+        if fit.control_points.len() >= before {
+            self.message = Some("The curve already has no points to spare\nPress Enter to continue".to_string());
+            return;
+        }
+        self.record_edit();
+        self.selection.clear();
+        let curve = self.scene.selected_mut();
+        curve.points = fit.control_points.iter().map(|p| (p.position.x, p.position.y)).collect();
+        curve.attributes.clear();
+        self.message = Some(format!(
+            "Fitted {} control points to {} (max error {:.2} px, RMS {:.2} px)\nPress Enter to continue",
+            fit.control_points.len(),
+            before,
+            fit.max_error,
+            fit.rms_error
+        ));
+    }
+
+    // Raise or lower the tension weight of the point under the cursor
+    pub fn change_weight(&mut self, tighten: bool) {
+        let Some(idx) = self.find_nearest_point() else {
+            return;
+        };
+        self.record_edit();
+        let attributes = self.scene.selected_mut().attributes_mut(idx);
+        if tighten {
+            attributes.increase_weight();
+        } else {
+            attributes.decrease_weight();
+        }
+    }
+
+    // Paint the selected points, or the hovered one when nothing is selected. None
+    // removes the paint so the points follow the curve's colour again.
+    pub fn paint_points(&mut self, color: Option<[u8; 3]>) {
+        let targets = if self.selection.is_empty() {
+            self.find_nearest_point().into_iter().collect()
+        } else {
+            self.selection.indices()
+        };
+        if targets.is_empty() {
+            return;
+        }
+        self.record_edit();
+        let curve = self.scene.selected_mut();
+        for idx in targets {
+            curve.attributes_mut(idx).color = color;
+        }
+    }
+
+    pub fn delete_selection(&mut self) {
+        if self.selection.is_empty() {
+            return;
+        }
+        self.record_edit();
+        let curve = self.scene.selected_mut();
+        self.selection.remove_selected(&mut curve.attributes);
+        self.selection.delete(&mut curve.points);
+    }
+
+    pub fn find_nearest_point(&self) -> Option<usize> {
+        nearest_point(self.points(), self.mouse_pos)
+    }
+
+    // Mouse position after grid, angle and point snapping. `moving` is the index of the
+    // point being dragged, or None when a new point is about to be appended.
+    pub fn snapped_position(&self, moving: Option<usize>) -> (f64, f64) {
+        let (x, y) = self.mouse_pos;
+        let points = self.points();
+        let previous = match moving {
+            Some(idx) => idx.checked_sub(1),
+            None => points.len().checked_sub(1),
+        };
+        let angle_anchor = if self.shift_down {
+            previous.map(|i| points[i])
+        } else {
+            None
+        };
+
+        // Points of every curve are snap targets, so curves can be joined up
+        self.grid.snap(&self.scene.all_points(), x, y, moving, angle_anchor)
+    }
+
+    pub fn change_sketch_tolerance(&mut self, factor: f64) {
+        self.sketch_tolerance = (self.sketch_tolerance * factor).clamp(MIN_TOLERANCE, MAX_TOLERANCE);
+    }
+
+    pub fn change_edit_level(&mut self, delta: i32) {
+        self.edit_level = (self.edit_level as i32 + delta).clamp(0, MAX_EDIT_LEVEL as i32) as usize;
+        self.dragging_level_point = None;
+    }
+
+    pub fn change_iterations(&mut self, delta: i32) {
+        let steps = (self.max_steps as i32 + delta).clamp(MIN_STEPS as i32, MAX_STEPS as i32);
+        self.max_steps = steps as usize;
+    }
+
+    pub fn change_speed(&mut self, factor: f64) {
+        self.animation_speed = (self.animation_speed * factor).clamp(MIN_SPEED, MAX_SPEED);
+    }
+
+    // F1 opens the help, steps through its pages and closes it after the last one
+    pub fn cycle_help(&mut self) {
+        let pages = KEY_BINDINGS.len().div_ceil(HELP_ROWS_PER_PAGE);
+        if !self.show_help {
+            self.show_help = true;
+            self.help_page = 0;
+        } else if self.help_page + 1 < pages {
+            self.help_page += 1;
+        } else {
+            self.show_help = false;
+        }
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.themes[self.theme]
+    }
+
+    // Switch to the theme at `index`, recolouring curves that use the old theme's colours
+    pub fn set_theme(&mut self, index: usize) {
+        if index < self.themes.len() {
+            self.theme = index;
+            self.scene.set_palette(self.themes[index].curve_colors());
+        }
+    }
+
+    pub fn cycle_theme(&mut self) {
+        self.set_theme((self.theme + 1) % self.themes.len());
+    }
+
+    // Add a theme, e.g. one loaded from a file, and switch to it
+    pub fn add_theme(&mut self, theme: Theme) {
+        self.themes.push(theme);
+        self.set_theme(self.themes.len() - 1);
+    }
+
+    // Stroke edited by the width/join/cap keys: the control polygon while Shift is
+    // held, the subdivided curve otherwise
+    pub fn stroke_mut(&mut self) -> &mut StrokeStyle {
+        if self.shift_down {
+            &mut self.polygon_stroke
+        } else {
+            &mut self.curve_stroke
+        }
+    }
+
+    // Points are added to and read from the selected curve
     pub fn add_point(&mut self, x: f64, y: f64) {
-        self.points.push((x, y));
+        self.scene.selected_mut().points.push((x, y));
     }
 
     pub fn points(&self) -> &[(f64, f64)] {
-        &self.points
+        &self.scene.selected().points
     }
 
     pub fn is_animating(&self) -> bool {
-        self.is_animating
+        self.scene.is_animating()
     }
 
     pub fn should_close(&self) -> bool {
@@ -127,3 +870,21 @@ impl InputHandler {
     }
 }
 
+// Index of the point closest to `pos`, if any is within POINT_PICK_DISTANCE
+fn nearest_point(points: &[(f64, f64)], (mx, my): (f64, f64)) -> Option<usize> {
+    let mut closest_idx = None;
+    let mut closest_dist = f64::MAX;
+
+    for (i, &(x, y)) in points.iter().enumerate() {
+        let dx = x - mx;
+        let dy = y - my;
+        let dist = (dx * dx + dy * dy).sqrt();
+
+        if dist < closest_dist && dist < POINT_PICK_DISTANCE {
+            closest_idx = Some(i);
+            closest_dist = dist;
+        }
+    }
+
+    closest_idx
+}
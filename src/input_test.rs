@@ -1,4 +1,7 @@
-use crate::input::InputHandler;
+use crate::input::{InputHandler, HELP_ROWS_PER_PAGE, KEY_BINDINGS};
+use crate::selection::Axis;
+use crate::chaikin::Chaikin;
+use nalgebra::Vector2;
 
 #[cfg(test)]
 mod tests {
@@ -49,4 +52,285 @@ mod tests {
         let idx = handler.find_nearest_point();
         assert_eq!(idx, None);
     }
+
+    #[test]
+    fn test_snapped_position_uses_grid_and_angle_anchor() {
+        let mut handler = InputHandler::new();
+        handler.grid.snap_to_grid = true;
+        handler.mouse_pos = (23.0, 48.0);
+        assert_eq!(handler.snapped_position(None), (25.0, 50.0));
+
+        // With Shift held the new point is constrained relative to the last one
+        handler.add_point(0.0, 0.0);
+        handler.shift_down = true;
+        handler.mouse_pos = (100.0, 3.0);
+        let (_, y) = handler.snapped_position(None);
+        assert!(y.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_points_belong_to_selected_curve() {
+        let mut handler = InputHandler::new();
+        handler.add_point(10.0, 10.0);
+        handler.scene.new_curve();
+        handler.add_point(50.0, 50.0);
+        assert_eq!(handler.points(), &[(50.0, 50.0)]);
+
+        // Only the selected curve's points can be grabbed
+        handler.mouse_pos = (10.0, 10.0);
+        assert_eq!(handler.find_nearest_point(), None);
+        handler.scene.select(0);
+        assert_eq!(handler.find_nearest_point(), Some(0));
+    }
+
+    #[test]
+    fn test_selection_transforms_can_be_undone() {
+        let mut handler = InputHandler::new();
+        handler.add_point(0.0, 0.0);
+        handler.add_point(10.0, 0.0);
+        handler.add_point(10.0, 10.0);
+
+        // Without a selection nothing happens and nothing is recorded
+        handler.rotate_selection(1.0);
+        handler.undo();
+        assert_eq!(handler.points().len(), 3);
+
+        handler.selection.select_only(1);
+        handler.selection.toggle(2);
+        handler.nudge_selection(5.0, 0.0);
+        handler.mirror_selection(Axis::Vertical);
+        assert_eq!(handler.points(), &[(0.0, 0.0), (15.0, 10.0), (15.0, 0.0)]);
+
+        handler.undo();
+        assert_eq!(handler.points(), &[(0.0, 0.0), (15.0, 0.0), (15.0, 10.0)]);
+        handler.undo();
+        assert_eq!(handler.points(), &[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)]);
+        handler.redo();
+        assert_eq!(handler.points()[1], (15.0, 0.0));
+    }
+
+    #[test]
+    fn test_deleting_selection_can_be_undone() {
+        let mut handler = InputHandler::new();
+        handler.add_point(0.0, 0.0);
+        handler.add_point(10.0, 0.0);
+        handler.selection.select_all(2);
+        handler.delete_selection();
+        assert!(handler.points().is_empty());
+        assert!(handler.selection.is_empty());
+
+        handler.undo();
+        assert_eq!(handler.points().len(), 2);
+    }
+
+    #[test]
+    fn test_clear_and_new_curve_can_be_undone_and_drop_selection() {
+        let mut handler = InputHandler::new();
+        handler.add_point(0.0, 0.0);
+        handler.add_point(10.0, 0.0);
+        handler.selection.select_all(2);
+        handler.clear_canvas();
+        assert!(handler.points().is_empty());
+        assert!(handler.selection.is_empty());
+        handler.undo();
+        assert_eq!(handler.points().len(), 2);
+
+        handler.selection.select_all(2);
+        handler.new_curve();
+        assert_eq!(handler.scene.curves.len(), 2);
+        assert!(handler.selection.is_empty());
+        handler.undo();
+        assert_eq!(handler.scene.curves.len(), 1);
+    }
+
+    #[test]
+    fn test_curve_settings_can_be_undone() {
+        let mut handler = InputHandler::new();
+        handler.add_point(0.0, 0.0);
+        let (scheme, color) = (handler.scene.selected().scheme, handler.scene.selected().color);
+        handler.set_closed(true);
+        handler.set_scheme(scheme.next());
+        handler.set_curve_color([1, 2, 3]);
+        // Setting a value the curve already has records nothing
+        handler.set_curve_color([1, 2, 3]);
+
+        handler.undo();
+        assert_eq!(handler.scene.selected().color, color);
+        handler.undo();
+        assert_eq!(handler.scene.selected().scheme, scheme);
+        handler.undo();
+        assert!(!handler.scene.selected().closed);
+        assert_eq!(handler.points().len(), 1);
+    }
+
+    #[test]
+    fn test_toggle_crease_on_hovered_point() {
+        let mut handler = InputHandler::new();
+        handler.add_point(0.0, 0.0);
+        handler.add_point(50.0, 0.0);
+        handler.add_point(100.0, 0.0);
+
+        handler.mouse_pos = (300.0, 300.0);
+        handler.toggle_crease();
+        assert!(handler.scene.selected().control_points().iter().all(|p| !p.crease));
+
+        handler.mouse_pos = (52.0, 1.0);
+        handler.toggle_crease();
+        handler.change_weight(true);
+        let points = handler.scene.selected().control_points();
+        assert!(points[1].crease && !points[0].crease);
+        assert!(points[1].weight > 1.0);
+
+        // Attributes follow their points when others are deleted
+        handler.selection.select_only(0);
+        handler.delete_selection();
+        assert!(handler.scene.selected().control_points()[0].crease);
+
+        handler.undo();
+        handler.undo();
+        handler.undo();
+        assert!(!handler.scene.selected().control_points()[1].crease);
+    }
+
+    #[test]
+    fn test_paint_points_colors_selection_or_hovered_point() {
+        let mut handler = InputHandler::new();
+        handler.add_point(0.0, 0.0);
+        handler.add_point(50.0, 0.0);
+        handler.add_point(100.0, 0.0);
+        let curve_color = handler.scene.selected().color;
+
+        handler.mouse_pos = (1.0, 1.0);
+        handler.paint_points(Some([255, 0, 0]));
+        handler.selection.select_only(1);
+        handler.selection.toggle(2);
+        handler.paint_points(Some([0, 0, 255]));
+        let colors: Vec<[u8; 3]> = handler.scene.selected().control_points().iter().map(|p| p.color).collect();
+        assert_eq!(colors, vec![[255, 0, 0], [0, 0, 255], [0, 0, 255]]);
+
+        handler.paint_points(None);
+        assert_eq!(handler.scene.selected().control_points()[2].color, curve_color);
+        handler.undo();
+        assert_eq!(handler.scene.selected().control_points()[2].color, [0, 0, 255]);
+    }
+
+    #[test]
+    fn test_change_iterations_and_speed_stay_in_range() {
+        let mut handler = InputHandler::new();
+        for _ in 0..20 {
+            handler.change_iterations(1);
+        }
+        assert_eq!(handler.max_steps, 12);
+        for _ in 0..20 {
+            handler.change_iterations(-1);
+        }
+        assert_eq!(handler.max_steps, 1);
+
+        handler.change_speed(2.0);
+        assert_eq!(handler.animation_speed, 2.0);
+        for _ in 0..20 {
+            handler.change_speed(0.5);
+        }
+        assert_eq!(handler.animation_speed, 0.1);
+    }
+
+    #[test]
+    fn test_help_pages_through_bindings_then_closes() {
+        let mut handler = InputHandler::new();
+        let pages = KEY_BINDINGS.len().div_ceil(HELP_ROWS_PER_PAGE);
+        assert!(pages > 1);
+        for page in 0..pages {
+            handler.cycle_help();
+            assert!(handler.show_help);
+            assert_eq!(handler.help_page, page);
+        }
+        handler.cycle_help();
+        assert!(!handler.show_help);
+        handler.cycle_help();
+        assert_eq!(handler.help_page, 0);
+    }
+
+    #[test]
+    fn test_fit_curve_replaces_dense_points_and_can_be_undone() {
+        let mut handler = InputHandler::new();
+        for i in 0..60 {
+            let angle = i as f64 / 60.0 * std::f64::consts::TAU;
+            handler.add_point(400.0 + 100.0 * angle.cos(), 300.0 + 100.0 * angle.sin());
+        }
+        handler.scene.selected_mut().closed = true;
+        handler.fit_curve();
+        assert!(handler.points().len() < 20);
+        assert!(handler.message().unwrap().starts_with("Fitted"));
+
+        handler.undo();
+        assert_eq!(handler.points().len(), 60);
+
+        // Curves that are already compact are left alone
+        let mut handler = InputHandler::new();
+        handler.add_point(0.0, 0.0);
+        handler.add_point(100.0, 0.0);
+        handler.add_point(100.0, 100.0);
+        handler.add_point(0.0, 100.0);
+        handler.fit_curve();
+        assert_eq!(handler.points().len(), 4);
+    }
+
+    #[test]
+    fn test_sketch_stroke_becomes_a_simplified_curve() {
+        let mut handler = InputHandler::new();
+        handler.add_point(10.0, 10.0);
+        handler.sketch_mode = true;
+        handler.begin_stroke((100.0, 100.0));
+        // Moves shorter than the spacing are not recorded
+        handler.extend_stroke((100.5, 100.0));
+        for i in 1..=100 {
+            let x = 100.0 + i as f64 * 3.0;
+            let y = if i <= 50 { 100.0 + i as f64 * 3.0 } else { 400.0 - i as f64 * 3.0 };
+            handler.extend_stroke((x, y));
+        }
+        assert_eq!(handler.sketch_stroke.as_ref().unwrap().len(), 101);
+        assert_eq!(handler.sketch_preview().unwrap(), vec![(100.0, 100.0), (250.0, 250.0), (400.0, 100.0)]);
+
+        handler.finish_stroke();
+        assert!(handler.sketch_stroke.is_none());
+        // The stroke starts a new curve rather than joining the existing points
+        assert_eq!(handler.scene.curves.len(), 2);
+        assert_eq!(handler.points(), &[(100.0, 100.0), (250.0, 250.0), (400.0, 100.0)]);
+
+        handler.undo();
+        assert_eq!(handler.scene.curves.len(), 1);
+    }
+
+    #[test]
+    fn test_sketch_click_without_moving_adds_nothing() {
+        let mut handler = InputHandler::new();
+        handler.begin_stroke((50.0, 50.0));
+        handler.finish_stroke();
+        assert!(handler.points().is_empty());
+        handler.change_sketch_tolerance(1000.0);
+        assert_eq!(handler.sketch_tolerance, 20.0);
+    }
+
+    #[test]
+    fn test_dragging_a_fine_level_point_keeps_the_control_points() {
+        let mut handler = InputHandler::new();
+        for &(x, y) in &[(100.0, 100.0), (300.0, 100.0), (300.0, 300.0)] {
+            handler.add_point(x, y);
+        }
+        handler.change_edit_level(10);
+        assert_eq!(handler.edit_level, 4);
+        handler.change_edit_level(-3);
+
+        let target = handler.scene.selected_mut().level_points(1)[2].position;
+        handler.scene.selected_mut().move_level_point(1, 2, (target.x, target.y - 30.0));
+        assert_eq!(handler.points(), &[(100.0, 100.0), (300.0, 100.0), (300.0, 300.0)]);
+        assert_eq!(handler.scene.selected_mut().level_points(1)[2].position.y, target.y - 30.0);
+
+        // Dragging a control point afterwards keeps the detail on top of it
+        handler.scene.selected_mut().points[1] = (320.0, 100.0);
+        let control = handler.scene.selected().control_points();
+        let plain = Chaikin::new(control.clone()).subdivide(&control);
+        let moved = handler.scene.selected_mut().level_points(1)[2].position;
+        assert!((moved - plain[2].position - Vector2::new(0.0, -30.0)).norm() < 1e-9);
+    }
 }
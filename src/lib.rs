@@ -1,6 +1,27 @@
 pub mod point;
 pub mod chaikin;
 pub mod input;
+pub mod grid;
+pub mod font;
+pub mod raster;
+pub mod stroke;
+pub mod scheme;
+pub mod ui;
+pub mod scene;
+pub mod selection;
+pub mod history;
+pub mod theme;
+pub mod frame;
+pub mod compare;
+pub mod control_grid;
+pub mod fit;
+pub mod analysis;
+pub mod mesh;
+pub mod mesh_io;
+pub mod preview;
+pub mod subdivider;
+pub mod sketch;
+pub mod adaptive;
 
 #[cfg(test)]
 mod chaikin_test;
@@ -11,3 +32,65 @@ mod input_test;
 #[cfg(test)]
 mod point_test;
 
+#[cfg(test)]
+mod grid_test;
+
+#[cfg(test)]
+mod raster_test;
+
+#[cfg(test)]
+mod stroke_test;
+
+#[cfg(test)]
+mod font_test;
+
+#[cfg(test)]
+mod scheme_test;
+
+#[cfg(test)]
+mod ui_test;
+
+#[cfg(test)]
+mod scene_test;
+
+#[cfg(test)]
+mod selection_test;
+
+#[cfg(test)]
+mod history_test;
+
+#[cfg(test)]
+mod theme_test;
+
+#[cfg(test)]
+mod frame_test;
+
+#[cfg(test)]
+mod compare_test;
+
+#[cfg(test)]
+mod analysis_test;
+
+#[cfg(test)]
+mod mesh_test;
+
+#[cfg(test)]
+mod mesh_io_test;
+
+#[cfg(test)]
+mod preview_test;
+
+#[cfg(test)]
+mod subdivider_test;
+
+#[cfg(test)]
+mod control_grid_test;
+
+#[cfg(test)]
+mod fit_test;
+
+#[cfg(test)]
+mod sketch_test;
+
+#[cfg(test)]
+mod adaptive_test;
@@ -1,18 +1,132 @@
+mod adaptive;
 mod canvas;
+mod analysis;
 mod chaikin;
+mod compare;
+mod control_grid;
+mod fit;
+mod font;
+mod frame;
+mod grid;
+mod history;
 mod input;
+mod mesh;
+mod mesh_io;
 mod point;
+mod preview;
+mod raster;
+mod scene;
+mod scheme;
+mod selection;
+mod sketch;
+mod stroke;
+mod subdivider;
+mod theme;
+mod ui;
 
 use canvas::Canvas;
+use control_grid::ControlGrid;
 use input::InputHandler;
+use mesh::{Mesh, SurfaceScheme};
+use preview::{Camera, Projection};
+use subdivider::Subdivider;
+use theme::Theme;
+
+// Value following a command-line option
+fn option<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter().position(|a| a == name).and_then(|pos| args.get(pos + 1)).map(String::as_str)
+}
+
+// `--mesh <file>` runs without a window: subdivide the mesh `--levels` times with the
+// `--subdivide` scheme, then save it to `--out` and a wireframe image to `--preview`
+fn run_mesh(args: &[String], theme: &Theme) -> Result<(), String> {
+    let path = option(args, "--mesh").ok_or("--mesh needs a file name")?;
+    let scheme = match option(args, "--subdivide") {
+        Some(name) => SurfaceScheme::parse(name).ok_or_else(|| format!("Unknown subdivision scheme '{}'", name))?,
+        None => SurfaceScheme::CatmullClark,
+    };
+    let levels = levels(args)?;
+    let mesh = scheme.subdivide_times(&mesh_io::load(path)?, levels);
+    println!("{} x{}: {} vertices, {} faces", scheme.name(), levels, mesh.vertices.len(), mesh.faces.len());
+    write_mesh(args, &mesh, theme)
+}
+
+// `--grid <file>` reads a height field and refines it `--levels` times with
+// tensor-product Chaikin, wrapping rows with `--periodic-u` and columns with
+// `--periodic-v`. Besides the mesh outputs, `--heightmap` saves a greyscale image.
+fn run_grid(args: &[String], theme: &Theme) -> Result<(), String> {
+    let path = option(args, "--grid").ok_or("--grid needs a file name")?;
+    let mut grid = ControlGrid::load(path)?;
+    grid.periodic_u = args.iter().any(|a| a == "--periodic-u");
+    grid.periodic_v = args.iter().any(|a| a == "--periodic-v");
+    let levels = levels(args)?;
+    for _ in 0..levels {
+        grid = grid.subdivide();
+    }
+    println!("Tensor-product Chaikin x{}: {} x {} points", levels, grid.columns, grid.rows);
+
+    if let Some(image) = option(args, "--heightmap") {
+        preview::write_pgm(image, &grid.height_map(), grid.columns, grid.rows)?;
+    }
+    write_mesh(args, &grid.to_mesh(), theme)
+}
+
+fn levels(args: &[String]) -> Result<usize, String> {
+    match option(args, "--levels") {
+        Some(levels) => levels.parse().map_err(|_| format!("Invalid level count '{}'", levels)),
+        None => Ok(1),
+    }
+}
+
+// Save `--out` and the `--preview` wireframe image for the headless modes
+fn write_mesh(args: &[String], mesh: &Mesh, theme: &Theme) -> Result<(), String> {
+    if let Some(out) = option(args, "--out") {
+        mesh_io::save(out, mesh)?;
+    }
+    if let Some(image) = option(args, "--preview") {
+        let mut camera = Camera::default();
+        if args.iter().any(|a| a == "--perspective") {
+            camera.projection = Projection::Perspective(45f64.to_radians());
+        }
+        let (width, height) = (800, 600);
+        let buffer = preview::render(mesh, &camera, width, height, theme.background, theme.point);
+        preview::write_ppm(image, &buffer, width, height)?;
+    }
+    Ok(())
+}
 
 fn main() {
     // Initialize logging
     env_logger::init();
     
-    // Create canvas and input handler
-    let mut canvas = Canvas::new(800, 600);
     let mut input = InputHandler::new();
+
+    // `--theme <file>` loads a colour theme and starts with it
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--theme") {
+        match option(&args, "--theme").map(Theme::load) {
+            Some(Ok(theme)) => input.add_theme(theme),
+            Some(Err(e)) => eprintln!("{}", e),
+            None => eprintln!("--theme needs a file name"),
+        }
+    }
+
+    let headless = if args.iter().any(|a| a == "--mesh") {
+        Some(run_mesh(&args, input.theme()))
+    } else if args.iter().any(|a| a == "--grid") {
+        Some(run_grid(&args, input.theme()))
+    } else {
+        None
+    };
+    if let Some(result) = headless {
+        if let Err(e) = result {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let mut canvas = Canvas::new(800, 600);
     
     // Main loop
     while canvas.is_open() && !input.should_close() {
@@ -0,0 +1,425 @@
+// Polygon meshes as an indexed face set, with the surface subdivision schemes that
+// generalise the curve schemes: Doo-Sabin is Chaikin's corner cutting on surfaces,
+// Catmull-Clark is the cubic B-spline, and Loop is its counterpart for triangle meshes.
+// Faces list their vertices counter-clockwise seen from outside; vertices are 3D points,
+// so colours are blended along with positions.
+use std::collections::{HashMap, HashSet};
+use super::point::Point;
+use super::subdivider::Subdivider;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SurfaceScheme {
+    DooSabin,
+    CatmullClark,
+    Loop,
+}
+
+impl SurfaceScheme {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SurfaceScheme::DooSabin => "Doo-Sabin",
+            SurfaceScheme::CatmullClark => "Catmull-Clark",
+            SurfaceScheme::Loop => "Loop",
+        }
+    }
+
+    // Scheme from a command-line name such as `doo-sabin`, `catmull-clark` or `loop`
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().replace(['_', ' '], "-").as_str() {
+            "doo-sabin" => Some(SurfaceScheme::DooSabin),
+            "catmull-clark" => Some(SurfaceScheme::CatmullClark),
+            "loop" => Some(SurfaceScheme::Loop),
+            _ => None,
+        }
+    }
+}
+
+impl Subdivider for SurfaceScheme {
+    type Shape = Mesh;
+
+    fn subdivide(&self, mesh: &Mesh) -> Mesh {
+        mesh.subdivide(*self)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Mesh {
+    pub vertices: Vec<Point<3>>,
+    pub faces: Vec<Vec<usize>>,
+    // Sharp edges, kept as creases by Catmull-Clark and Loop subdivision the way boundaries
+    // are. Crease points (`Point::crease`) are corners and never move.
+    pub creases: Vec<(usize, usize)>,
+}
+
+// How a vertex moves under Catmull-Clark and Loop subdivision
+enum VertexRule {
+    Smooth,
+    // On a boundary or crease curve, between these two neighbours along it
+    Crease(usize, usize),
+    Corner,
+}
+
+// Edge and adjacency tables of a mesh, built once per subdivision step
+struct Topology {
+    // Each undirected edge once, lower vertex index first, in order of first use
+    edges: Vec<(usize, usize)>,
+    edge_index: HashMap<(usize, usize), usize>,
+    // Faces on each side of an edge: one for boundary edges, two inside the mesh
+    edge_faces: Vec<Vec<usize>>,
+    // Boundary and crease edges
+    sharp: Vec<bool>,
+    // Face owning each directed edge `a -> b`
+    half_edges: HashMap<(usize, usize), usize>,
+    // Edges meeting at each vertex
+    vertex_edges: Vec<Vec<usize>>,
+    vertex_faces: Vec<Vec<usize>>,
+}
+
+impl Topology {
+    fn new(mesh: &Mesh) -> Self {
+        let mut topology = Topology {
+            edges: Vec::new(),
+            edge_index: HashMap::new(),
+            edge_faces: Vec::new(),
+            sharp: Vec::new(),
+            half_edges: HashMap::new(),
+            vertex_edges: vec![Vec::new(); mesh.vertices.len()],
+            vertex_faces: vec![Vec::new(); mesh.vertices.len()],
+        };
+        for (f, face) in mesh.faces.iter().enumerate() {
+            for (i, &a) in face.iter().enumerate() {
+                let b = face[(i + 1) % face.len()];
+                topology.half_edges.insert((a, b), f);
+                topology.vertex_faces[a].push(f);
+                let key = (a.min(b), a.max(b));
+                let e = match topology.edge_index.get(&key) {
+                    Some(&e) => e,
+                    None => {
+                        let e = topology.edges.len();
+                        topology.edges.push(key);
+                        topology.edge_faces.push(Vec::new());
+                        topology.edge_index.insert(key, e);
+                        topology.vertex_edges[a].push(e);
+                        topology.vertex_edges[b].push(e);
+                        e
+                    }
+                };
+                topology.edge_faces[e].push(f);
+            }
+        }
+        let creases: HashSet<(usize, usize)> = mesh.creases.iter().map(|&(a, b)| (a.min(b), a.max(b))).collect();
+        topology.sharp = (0..topology.edges.len())
+            .map(|e| topology.is_boundary_edge(e) || creases.contains(&topology.edges[e]))
+            .collect();
+        topology
+    }
+
+    fn edge(&self, a: usize, b: usize) -> usize {
+        self.edge_index[&(a.min(b), a.max(b))]
+    }
+
+    fn is_boundary_edge(&self, e: usize) -> bool {
+        self.edge_faces[e].len() < 2
+    }
+
+    fn is_boundary_vertex(&self, v: usize) -> bool {
+        self.vertex_edges[v].iter().any(|&e| self.is_boundary_edge(e))
+    }
+
+    fn other_end(&self, e: usize, v: usize) -> usize {
+        let (a, b) = self.edges[e];
+        if a == v { b } else { a }
+    }
+
+    // Smooth vertices have fewer than two sharp edges. Crease points, vertices where
+    // three or more sharp edges meet, corners of a single face and loose vertices stay
+    // where they are.
+    fn vertex_rule(&self, mesh: &Mesh, v: usize) -> VertexRule {
+        let faces = self.vertex_faces[v].len();
+        if mesh.vertices[v].crease || faces == 0 {
+            return VertexRule::Corner;
+        }
+        let sharp: Vec<usize> = self.vertex_edges[v].iter().copied().filter(|&e| self.sharp[e]).collect();
+        match sharp[..] {
+            [] | [_] => VertexRule::Smooth,
+            [e0, e1] if faces > 1 => VertexRule::Crease(self.other_end(e0, v), self.other_end(e1, v)),
+            _ => VertexRule::Corner,
+        }
+    }
+
+    // Faces around an interior vertex, counter-clockwise, starting from `first`
+    fn faces_around(&self, mesh: &Mesh, v: usize, first: usize) -> Vec<usize> {
+        let mut faces = vec![first];
+        let mut f = first;
+        loop {
+            let face = &mesh.faces[f];
+            let i = face.iter().position(|&u| u == v).unwrap();
+            let prev = face[(i + face.len() - 1) % face.len()];
+            match self.half_edges.get(&(v, prev)) {
+                Some(&next) if next != first => {
+                    faces.push(next);
+                    f = next;
+                }
+                _ => return faces,
+            }
+        }
+    }
+}
+
+impl Mesh {
+    // Each undirected edge once, lower vertex index first
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        Topology::new(self).edges
+    }
+
+    // Subdivision walks from face to face across shared edges, so every face needs three
+    // or more distinct corners, no edge may be shared by more than two faces, and faces
+    // sharing an edge must run along it in opposite directions. Vertices and faces are
+    // numbered from 1 in the messages, as in OBJ files.
+    pub fn check_manifold(&self) -> Result<(), String> {
+        let mut half_edges = HashSet::new();
+        let mut edge_uses: HashMap<(usize, usize), usize> = HashMap::new();
+        for (f, face) in self.faces.iter().enumerate() {
+            if face.len() < 3 {
+                return Err(format!("Face {} has fewer than three vertices", f + 1));
+            }
+            for (i, &a) in face.iter().enumerate() {
+                if face[..i].contains(&a) {
+                    return Err(format!("Face {} uses vertex {} twice", f + 1, a + 1));
+                }
+                let b = face[(i + 1) % face.len()];
+                let uses = edge_uses.entry((a.min(b), a.max(b))).or_insert(0);
+                *uses += 1;
+                if *uses > 2 {
+                    return Err(format!("Edge {}-{} is shared by more than two faces", a + 1, b + 1));
+                }
+                if !half_edges.insert((a, b)) {
+                    return Err(format!("Faces next to edge {}-{} are not wound consistently", a + 1, b + 1));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn subdivide(&self, scheme: SurfaceScheme) -> Mesh {
+        match scheme {
+            SurfaceScheme::DooSabin => self.doo_sabin(),
+            SurfaceScheme::CatmullClark => self.catmull_clark(),
+            SurfaceScheme::Loop => self.loop_subdivision(),
+        }
+    }
+
+    // The mesh with every face split into a fan of triangles from its first vertex
+    pub fn triangulate(&self) -> Mesh {
+        let faces = self
+            .faces
+            .iter()
+            .flat_map(|face| (1..face.len() - 1).map(move |i| vec![face[0], face[i], face[i + 1]]))
+            .collect();
+        Mesh { vertices: self.vertices.clone(), faces, creases: self.creases.clone() }
+    }
+
+    // Each crease edge split in two at its new edge point, for schemes that add a point
+    // on every edge numbered from `edge_base`
+    fn split_creases(&self, topology: &Topology, edge_base: usize) -> Vec<(usize, usize)> {
+        self.creases
+            .iter()
+            .filter_map(|&(a, b)| topology.edge_index.get(&(a.min(b), a.max(b))))
+            .flat_map(|&e| {
+                let (a, b) = topology.edges[e];
+                [(a, edge_base + e), (b, edge_base + e)]
+            })
+            .collect()
+    }
+
+    // One step of Doo-Sabin subdivision. Every face shrinks towards its centre with the
+    // Doo-Sabin weights, which for a quad are Chaikin's 3/4 : 1/4 in each direction; new
+    // faces then fill the gaps left at edges and vertices. Boundary edges and vertices get
+    // no gap face, so open meshes shrink back from their boundary. Creases are not kept.
+    pub fn doo_sabin(&self) -> Mesh {
+        let topology = Topology::new(self);
+        let mut vertices = Vec::new();
+        // Index of the new point for each corner of each face
+        let mut corners: Vec<Vec<usize>> = Vec::with_capacity(self.faces.len());
+        for face in &self.faces {
+            let n = face.len();
+            let mut face_corners = Vec::with_capacity(n);
+            for i in 0..n {
+                let terms: Vec<(f64, &Point<3>)> = (0..n)
+                    .map(|j| (doo_sabin_weight(n, (j + n - i) % n), &self.vertices[face[j]]))
+                    .collect();
+                face_corners.push(vertices.len());
+                vertices.push(Point::combine(&terms));
+            }
+            corners.push(face_corners);
+        }
+        let corner = |f: usize, v: usize| {
+            let i = self.faces[f].iter().position(|&u| u == v).unwrap();
+            corners[f][i]
+        };
+
+        let mut faces = corners.clone();
+        for (e, &(a, b)) in topology.edges.iter().enumerate() {
+            // `left` runs a -> b, `right` runs b -> a. An edge without both, as in a mesh
+            // that was never checked, is treated as a boundary.
+            let (Some(&left), Some(&right)) = (topology.half_edges.get(&(a, b)), topology.half_edges.get(&(b, a))) else {
+                continue;
+            };
+            if topology.is_boundary_edge(e) {
+                continue;
+            }
+            faces.push(vec![corner(left, b), corner(left, a), corner(right, a), corner(right, b)]);
+        }
+        for v in 0..self.vertices.len() {
+            if topology.vertex_faces[v].len() < 3 || topology.is_boundary_vertex(v) {
+                continue;
+            }
+            let around = topology.faces_around(self, v, topology.vertex_faces[v][0]);
+            faces.push(around.iter().map(|&f| corner(f, v)).collect());
+        }
+        Mesh { vertices, faces, creases: Vec::new() }
+    }
+
+    // One step of Catmull-Clark subdivision: every n-sided face becomes n quads. Boundary
+    // and crease edges follow the cubic B-spline curve rules, so they stay sharp curves.
+    pub fn catmull_clark(&self) -> Mesh {
+        let topology = Topology::new(self);
+        let face_points: Vec<Point<3>> = self
+            .faces
+            .iter()
+            .map(|face| {
+                let w = 1.0 / face.len() as f64;
+                Point::combine(&face.iter().map(|&v| (w, &self.vertices[v])).collect::<Vec<_>>())
+            })
+            .collect();
+        let midpoints: Vec<Point<3>> = topology
+            .edges
+            .iter()
+            .map(|&(a, b)| self.vertices[a].lerp(&self.vertices[b], 0.5))
+            .collect();
+        let edge_points: Vec<Point<3>> = topology
+            .edges
+            .iter()
+            .enumerate()
+            .map(|(e, &(a, b))| match topology.edge_faces[e][..] {
+                [f, g] if !topology.sharp[e] => Point::combine(&[
+                    (0.25, &self.vertices[a]),
+                    (0.25, &self.vertices[b]),
+                    (0.25, &face_points[f]),
+                    (0.25, &face_points[g]),
+                ]),
+                _ => midpoints[e].clone(),
+            })
+            .collect();
+
+        let mut vertices: Vec<Point<3>> = Vec::with_capacity(self.vertices.len() + edge_points.len() + face_points.len());
+        for (v, point) in self.vertices.iter().enumerate() {
+            let moved = match topology.vertex_rule(self, v) {
+                VertexRule::Smooth => {
+                    let edges = &topology.vertex_edges[v];
+                    let faces = &topology.vertex_faces[v];
+                    let n = faces.len() as f64;
+                    let face_average =
+                        Point::combine(&faces.iter().map(|&f| (1.0 / n, &face_points[f])).collect::<Vec<_>>());
+                    let edge_average = Point::combine(
+                        &edges.iter().map(|&e| (1.0 / edges.len() as f64, &midpoints[e])).collect::<Vec<_>>(),
+                    );
+                    Point::combine(&[(1.0 / n, &face_average), (2.0 / n, &edge_average), ((n - 3.0) / n, point)])
+                }
+                VertexRule::Crease(a, b) => {
+                    Point::combine(&[(0.125, &self.vertices[a]), (0.75, point), (0.125, &self.vertices[b])])
+                }
+                VertexRule::Corner => point.clone(),
+            };
+            vertices.push(moved);
+        }
+        let edge_base = vertices.len();
+        vertices.extend(edge_points);
+        let face_base = vertices.len();
+        vertices.extend(face_points);
+
+        let mut faces = Vec::new();
+        for (f, face) in self.faces.iter().enumerate() {
+            let n = face.len();
+            for i in 0..n {
+                let (prev, curr, next) = (face[(i + n - 1) % n], face[i], face[(i + 1) % n]);
+                faces.push(vec![
+                    curr,
+                    edge_base + topology.edge(curr, next),
+                    face_base + f,
+                    edge_base + topology.edge(prev, curr),
+                ]);
+            }
+        }
+        let creases = self.split_creases(&topology, edge_base);
+        Mesh { vertices, faces, creases }
+    }
+
+    // One step of Loop subdivision: every triangle becomes four, other faces being split
+    // into triangles first. Boundary and crease edges follow the cubic B-spline curve
+    // rules, as in `catmull_clark`.
+    pub fn loop_subdivision(&self) -> Mesh {
+        if self.faces.iter().any(|face| face.len() != 3) {
+            return self.triangulate().loop_subdivision();
+        }
+        let topology = Topology::new(self);
+        let mut vertices = Vec::with_capacity(self.vertices.len() + topology.edges.len());
+        for (v, point) in self.vertices.iter().enumerate() {
+            vertices.push(match topology.vertex_rule(self, v) {
+                VertexRule::Smooth => {
+                    let edges = &topology.vertex_edges[v];
+                    let beta = loop_beta(edges.len());
+                    let mut terms = vec![(1.0 - edges.len() as f64 * beta, point)];
+                    terms.extend(edges.iter().map(|&e| (beta, &self.vertices[topology.other_end(e, v)])));
+                    Point::combine(&terms)
+                }
+                VertexRule::Crease(a, b) => {
+                    Point::combine(&[(0.125, &self.vertices[a]), (0.75, point), (0.125, &self.vertices[b])])
+                }
+                VertexRule::Corner => point.clone(),
+            });
+        }
+        for (e, &(a, b)) in topology.edges.iter().enumerate() {
+            // The vertex of face `f` across from this edge
+            let opposite = |f: usize| self.faces[f].iter().copied().find(|&u| u != a && u != b).unwrap_or(a);
+            vertices.push(match topology.edge_faces[e][..] {
+                [f, g] if !topology.sharp[e] => Point::combine(&[
+                    (0.375, &self.vertices[a]),
+                    (0.375, &self.vertices[b]),
+                    (0.125, &self.vertices[opposite(f)]),
+                    (0.125, &self.vertices[opposite(g)]),
+                ]),
+                _ => self.vertices[a].lerp(&self.vertices[b], 0.5),
+            });
+        }
+
+        let edge_base = self.vertices.len();
+        let mid = |a: usize, b: usize| edge_base + topology.edge(a, b);
+        let mut faces = Vec::with_capacity(4 * self.faces.len());
+        for face in &self.faces {
+            let (a, b, c) = (face[0], face[1], face[2]);
+            let (ab, bc, ca) = (mid(a, b), mid(b, c), mid(c, a));
+            faces.extend([vec![a, ab, ca], vec![ab, b, bc], vec![ca, bc, c], vec![ab, bc, ca]]);
+        }
+        let creases = self.split_creases(&topology, edge_base);
+        Mesh { vertices, faces, creases }
+    }
+}
+
+// Weight of each neighbour of a smooth vertex with `n` neighbours under Loop subdivision
+fn loop_beta(n: usize) -> f64 {
+    let n = n as f64;
+    let c = 0.375 + 0.25 * (2.0 * std::f64::consts::PI / n).cos();
+    (0.625 - c * c) / n
+}
+
+// Doo-Sabin weight of the vertex `k` places after the one a new point belongs to, in an
+// n-sided face
+fn doo_sabin_weight(n: usize, k: usize) -> f64 {
+    let n = n as f64;
+    if k == 0 {
+        (n + 5.0) / (4.0 * n)
+    } else {
+        (3.0 + 2.0 * (2.0 * std::f64::consts::PI * k as f64 / n).cos()) / (4.0 * n)
+    }
+}
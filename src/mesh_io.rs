@@ -0,0 +1,421 @@
+// Reading and writing meshes as Wavefront OBJ, Stanford PLY and STL. OBJ keeps positions,
+// faces and creases, which are stored as `l` line elements; texture coordinates, normals,
+// groups and materials are skipped. PLY also keeps vertex colours, with creases in an
+// `edge` element, and is read in ASCII or binary form and written as ASCII. STL only
+// holds triangles: it is read in either form, welding shared corners back into
+// vertices, and written as binary.
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use nalgebra::Vector3;
+use super::mesh::Mesh;
+use super::point::Point;
+
+pub fn parse_obj(data: &str) -> Result<Mesh, String> {
+    let mut mesh = Mesh::default();
+    for (line_no, line) in data.lines().enumerate() {
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some("v") => {
+                let values: Vec<f64> = fields
+                    .map(|f| f.parse())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| format!("Invalid vertex on line {}", line_no + 1))?;
+                let mut point = match values[..] {
+                    [x, y, z, ..] => Point::from_vector(Vector3::new(x, y, z)),
+                    _ => return Err(format!("Invalid vertex on line {}", line_no + 1)),
+                };
+                // Some exporters add an `r g b` colour in [0, 1] after the position
+                if let [_, _, _, r, g, b] = values[..] {
+                    point.color = [r, g, b].map(|c| (c * 255.0).round().clamp(0.0, 255.0) as u8);
+                }
+                mesh.vertices.push(point);
+            }
+            Some(kind @ ("f" | "l")) => {
+                let mut indices = Vec::new();
+                for field in fields {
+                    // `v`, `v/vt`, `v//vn` or `v/vt/vn`, counting from 1 or back from the end
+                    let index: i64 = field
+                        .split('/')
+                        .next()
+                        .and_then(|v| v.parse().ok())
+                        .ok_or_else(|| format!("Invalid face on line {}", line_no + 1))?;
+                    let count = mesh.vertices.len() as i64;
+                    let index = if index < 0 { count + index } else { index - 1 };
+                    if index < 0 || index >= count {
+                        return Err(format!("Face index out of range on line {}", line_no + 1));
+                    }
+                    indices.push(index as usize);
+                }
+                if kind == "l" {
+                    mesh.creases.extend(indices.windows(2).map(|pair| (pair[0], pair[1])));
+                } else if indices.len() < 3 {
+                    return Err(format!("Invalid face on line {}", line_no + 1));
+                } else {
+                    mesh.faces.push(indices);
+                }
+            }
+            _ => {}
+        }
+    }
+    mesh.check_manifold()?;
+    Ok(mesh)
+}
+
+pub fn write_obj(mesh: &Mesh) -> String {
+    let mut out = String::new();
+    for p in &mesh.vertices {
+        let _ = writeln!(out, "v {} {} {}", p.position.x, p.position.y, p.position.z);
+    }
+    for face in &mesh.faces {
+        let indices: Vec<String> = face.iter().map(|i| (i + 1).to_string()).collect();
+        let _ = writeln!(out, "f {}", indices.join(" "));
+    }
+    for &(a, b) in &mesh.creases {
+        let _ = writeln!(out, "l {} {}", a + 1, b + 1);
+    }
+    out
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PlyFormat {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PlyType {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+impl PlyType {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "char" | "int8" => PlyType::Int8,
+            "uchar" | "uint8" => PlyType::UInt8,
+            "short" | "int16" => PlyType::Int16,
+            "ushort" | "uint16" => PlyType::UInt16,
+            "int" | "int32" => PlyType::Int32,
+            "uint" | "uint32" => PlyType::UInt32,
+            "float" | "float32" => PlyType::Float32,
+            "double" | "float64" => PlyType::Float64,
+            _ => return None,
+        })
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            PlyType::Int8 | PlyType::UInt8 => 1,
+            PlyType::Int16 | PlyType::UInt16 => 2,
+            PlyType::Int32 | PlyType::UInt32 | PlyType::Float32 => 4,
+            PlyType::Float64 => 8,
+        }
+    }
+}
+
+struct PlyProperty {
+    name: String,
+    // The type of the count for list properties
+    count: Option<PlyType>,
+    value: PlyType,
+}
+
+struct PlyElement {
+    name: String,
+    count: usize,
+    properties: Vec<PlyProperty>,
+}
+
+// Reads the values of a PLY body one at a time, whatever its format
+struct PlyValues<'a> {
+    format: PlyFormat,
+    body: &'a [u8],
+    offset: usize,
+}
+
+impl PlyValues<'_> {
+    fn next(&mut self, kind: PlyType) -> Result<f64, String> {
+        if self.format == PlyFormat::Ascii {
+            let rest = &self.body[self.offset..];
+            let start = rest.iter().position(|b| !b.is_ascii_whitespace()).ok_or("Unexpected end of PLY data")?;
+            let len = rest[start..].iter().position(|b| b.is_ascii_whitespace()).unwrap_or(rest.len() - start);
+            self.offset += start + len;
+            return std::str::from_utf8(&rest[start..start + len])
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(|| "Invalid number in PLY data".to_string());
+        }
+
+        let size = kind.size();
+        let mut bytes = [0u8; 8];
+        let src = self.body.get(self.offset..self.offset + size).ok_or("Unexpected end of PLY data")?;
+        bytes[..size].copy_from_slice(src);
+        if self.format == PlyFormat::BinaryBigEndian {
+            bytes[..size].reverse();
+        }
+        self.offset += size;
+        // Big-endian values were reversed above, so everything is little-endian here
+        let half = [bytes[0], bytes[1]];
+        let word = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        Ok(match kind {
+            PlyType::Int8 => bytes[0] as i8 as f64,
+            PlyType::UInt8 => bytes[0] as f64,
+            PlyType::Int16 => i16::from_le_bytes(half) as f64,
+            PlyType::UInt16 => u16::from_le_bytes(half) as f64,
+            PlyType::Int32 => i32::from_le_bytes(word) as f64,
+            PlyType::UInt32 => u32::from_le_bytes(word) as f64,
+            PlyType::Float32 => f32::from_le_bytes(word) as f64,
+            PlyType::Float64 => f64::from_le_bytes(bytes),
+        })
+    }
+}
+
+// Parse a PLY file. The `vertex` element needs `x`, `y` and `z` and may have `red`,
+// `green` and `blue`; the `face` element needs a `vertex_indices` (or `vertex_index`)
+// list, and an `edge` element with `vertex1` and `vertex2` gives the creases. Other
+// elements and properties are read and skipped.
+pub fn parse_ply(data: &[u8]) -> Result<Mesh, String> {
+    let header_end = data
+        .windows(10)
+        .position(|w| w == b"end_header")
+        .ok_or("Missing PLY end_header")?;
+    let header = std::str::from_utf8(&data[..header_end]).map_err(|_| "Invalid PLY header")?;
+    let body_start = data[header_end..].iter().position(|&b| b == b'\n').map_or(data.len(), |i| header_end + i + 1);
+
+    let mut lines = header.lines();
+    if lines.next().map(str::trim) != Some("ply") {
+        return Err("Not a PLY file".to_string());
+    }
+    let mut format = None;
+    let mut elements: Vec<PlyElement> = Vec::new();
+    for line in lines {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields[..] {
+            ["format", name, _] => {
+                format = Some(match name {
+                    "ascii" => PlyFormat::Ascii,
+                    "binary_little_endian" => PlyFormat::BinaryLittleEndian,
+                    "binary_big_endian" => PlyFormat::BinaryBigEndian,
+                    _ => return Err(format!("Unsupported PLY format '{}'", name)),
+                })
+            }
+            ["element", name, count] => elements.push(PlyElement {
+                name: name.to_string(),
+                count: count.parse().map_err(|_| format!("Invalid PLY element count '{}'", count))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, value, name] => {
+                let element = elements.last_mut().ok_or("PLY property before any element")?;
+                element.properties.push(PlyProperty {
+                    name: name.to_string(),
+                    count: Some(PlyType::parse(count).ok_or_else(|| format!("Unknown PLY type '{}'", count))?),
+                    value: PlyType::parse(value).ok_or_else(|| format!("Unknown PLY type '{}'", value))?,
+                });
+            }
+            ["property", value, name] => {
+                let element = elements.last_mut().ok_or("PLY property before any element")?;
+                element.properties.push(PlyProperty {
+                    name: name.to_string(),
+                    count: None,
+                    value: PlyType::parse(value).ok_or_else(|| format!("Unknown PLY type '{}'", value))?,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let mut values = PlyValues {
+        format: format.ok_or("Missing PLY format")?,
+        body: &data[body_start..],
+        offset: 0,
+    };
+    let mut mesh = Mesh::default();
+    for element in &elements {
+        for _ in 0..element.count {
+            let mut position = Vector3::zeros();
+            let mut color = [255u8; 3];
+            let mut ends = (0, 0);
+            for property in &element.properties {
+                if let Some(count) = property.count {
+                    let n = values.next(count)? as usize;
+                    let list = (0..n).map(|_| values.next(property.value)).collect::<Result<Vec<_>, _>>()?;
+                    if element.name == "face" && property.name.starts_with("vertex_ind") {
+                        if list.len() < 3 {
+                            return Err("PLY face with fewer than three vertices".to_string());
+                        }
+                        mesh.faces.push(list.iter().map(|&i| i as usize).collect());
+                    }
+                    continue;
+                }
+                let value = values.next(property.value)?;
+                match property.name.as_str() {
+                    "x" => position.x = value,
+                    "y" => position.y = value,
+                    "z" => position.z = value,
+                    "red" => color[0] = value.clamp(0.0, 255.0) as u8,
+                    "green" => color[1] = value.clamp(0.0, 255.0) as u8,
+                    "blue" => color[2] = value.clamp(0.0, 255.0) as u8,
+                    "vertex1" => ends.0 = value as usize,
+                    "vertex2" => ends.1 = value as usize,
+                    _ => {}
+                }
+            }
+            match element.name.as_str() {
+                "vertex" => mesh.vertices.push(Point { color, ..Point::from_vector(position) }),
+                "edge" => mesh.creases.push(ends),
+                _ => {}
+            }
+        }
+    }
+    let creases = mesh.creases.iter().flat_map(|&(a, b)| [a, b]);
+    if mesh.faces.iter().flatten().copied().chain(creases).any(|i| i >= mesh.vertices.len()) {
+        return Err("PLY face index out of range".to_string());
+    }
+    mesh.check_manifold()?;
+    Ok(mesh)
+}
+
+pub fn write_ply(mesh: &Mesh) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "ply\nformat ascii 1.0");
+    let _ = writeln!(out, "element vertex {}", mesh.vertices.len());
+    let _ = writeln!(out, "property double x\nproperty double y\nproperty double z");
+    let _ = writeln!(out, "property uchar red\nproperty uchar green\nproperty uchar blue");
+    let _ = writeln!(out, "element face {}", mesh.faces.len());
+    let _ = writeln!(out, "property list uchar int vertex_indices");
+    if !mesh.creases.is_empty() {
+        let _ = writeln!(out, "element edge {}", mesh.creases.len());
+        let _ = writeln!(out, "property int vertex1\nproperty int vertex2");
+    }
+    let _ = writeln!(out, "end_header");
+    for p in &mesh.vertices {
+        let [r, g, b] = p.color;
+        let _ = writeln!(out, "{} {} {} {} {} {}", p.position.x, p.position.y, p.position.z, r, g, b);
+    }
+    for face in &mesh.faces {
+        let indices: Vec<String> = face.iter().map(|i| i.to_string()).collect();
+        let _ = writeln!(out, "{} {}", face.len(), indices.join(" "));
+    }
+    for &(a, b) in &mesh.creases {
+        let _ = writeln!(out, "{} {}", a, b);
+    }
+    out
+}
+
+// Parse an ASCII or binary STL file. Binary files may also start with `solid`, so the
+// size decides: a binary file is exactly 84 bytes plus 50 per triangle.
+pub fn parse_stl(data: &[u8]) -> Result<Mesh, String> {
+    let mut triangles: Vec<[Vector3<f64>; 3]> = Vec::new();
+    let count = data.get(80..84).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize);
+    if count.is_some_and(|count| data.len() == 84 + 50 * count) {
+        for record in data[84..].chunks_exact(50) {
+            // Each record is a normal, three corners, then two attribute bytes
+            let value = |i: usize| {
+                let b = &record[12 + 4 * i..16 + 4 * i];
+                f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64
+            };
+            let corner = |c: usize| Vector3::new(value(3 * c), value(3 * c + 1), value(3 * c + 2));
+            triangles.push([corner(0), corner(1), corner(2)]);
+        }
+    } else {
+        let text = std::str::from_utf8(data).map_err(|_| "Invalid STL file")?;
+        if !text.trim_start().starts_with("solid") {
+            return Err("Not an STL file".to_string());
+        }
+        let mut corners = Vec::new();
+        for (line_no, line) in text.lines().enumerate() {
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                Some("vertex") => {
+                    let values: Vec<f64> = fields.filter_map(|f| f.parse().ok()).collect();
+                    match values[..] {
+                        [x, y, z] => corners.push(Vector3::new(x, y, z)),
+                        _ => return Err(format!("Invalid vertex on line {}", line_no + 1)),
+                    }
+                }
+                Some("endfacet") => {
+                    match corners[..] {
+                        [a, b, c] => triangles.push([a, b, c]),
+                        _ => return Err(format!("Facet without three vertices on line {}", line_no + 1)),
+                    }
+                    corners.clear();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Corners at exactly the same position are the same vertex. Triangles whose corners
+    // weld together have no area and are dropped.
+    let mut mesh = Mesh::default();
+    let mut index: HashMap<[u64; 3], usize> = HashMap::new();
+    for triangle in triangles {
+        let face: Vec<usize> = triangle
+            .iter()
+            .map(|p| {
+                *index.entry([p.x.to_bits(), p.y.to_bits(), p.z.to_bits()]).or_insert_with(|| {
+                    mesh.vertices.push(Point::from_vector(*p));
+                    mesh.vertices.len() - 1
+                })
+            })
+            .collect();
+        if face[0] != face[1] && face[1] != face[2] && face[2] != face[0] {
+            mesh.faces.push(face);
+        }
+    }
+    mesh.check_manifold()?;
+    Ok(mesh)
+}
+
+// Binary STL, with faces split into triangle fans
+pub fn write_stl(mesh: &Mesh) -> Vec<u8> {
+    let triangles = mesh.triangulate().faces;
+    let mut data = vec![0u8; 80];
+    data.extend_from_slice(&(triangles.len() as u32).to_le_bytes());
+    for face in triangles {
+        let [a, b, c] = [0, 1, 2].map(|i| mesh.vertices[face[i]].position);
+        let normal = (b - a).cross(&(c - a)).try_normalize(0.0).unwrap_or_else(Vector3::zeros);
+        for v in [normal, a, b, c] {
+            for value in v.iter() {
+                data.extend_from_slice(&(*value as f32).to_le_bytes());
+            }
+        }
+        data.extend_from_slice(&[0, 0]);
+    }
+    data
+}
+
+// Load a mesh, choosing the format from the file extension
+pub fn load(path: &str) -> Result<Mesh, String> {
+    let data = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    match extension(path).as_str() {
+        "obj" => parse_obj(&String::from_utf8_lossy(&data)),
+        "ply" => parse_ply(&data),
+        "stl" => parse_stl(&data),
+        _ => Err(format!("Unknown mesh format for {}: use .obj, .ply or .stl", path)),
+    }
+}
+
+pub fn save(path: &str, mesh: &Mesh) -> Result<(), String> {
+    let data = match extension(path).as_str() {
+        "obj" => write_obj(mesh).into_bytes(),
+        "ply" => write_ply(mesh).into_bytes(),
+        "stl" => write_stl(mesh),
+        _ => return Err(format!("Unknown mesh format for {}: use .obj, .ply or .stl", path)),
+    };
+    std::fs::write(path, data).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+fn extension(path: &str) -> String {
+    std::path::Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default()
+}
@@ -0,0 +1,155 @@
+use crate::mesh_io::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::Mesh;
+    use crate::point::Point;
+    use nalgebra::Vector3;
+
+    fn triangle() -> Mesh {
+        let mut vertices: Vec<Point<3>> = [(0.0, 0.0, 0.0), (1.5, 0.0, 0.0), (0.0, 2.0, -1.0)]
+            .iter()
+            .map(|&(x, y, z)| Point::from_vector(Vector3::new(x, y, z)))
+            .collect();
+        vertices[1].color = [255, 0, 0];
+        Mesh { vertices, faces: vec![vec![0, 1, 2]], creases: Vec::new() }
+    }
+
+    #[test]
+    fn test_obj_round_trip() {
+        let mesh = parse_obj(&write_obj(&triangle())).unwrap();
+        assert_eq!(mesh.faces, triangle().faces);
+        let positions: Vec<Vector3<f64>> = mesh.vertices.iter().map(|p| p.position).collect();
+        assert_eq!(positions[2], Vector3::new(0.0, 2.0, -1.0));
+    }
+
+    #[test]
+    fn test_obj_face_formats_and_colours() {
+        let data = "# comment\nv 0 0 0\nv 1 0 0 1 0 0\nv 1 1 0\nv 0 1 0\nvt 0 0\nvn 0 0 1\n\
+                    o quad\nf 1/1/1 2//1 3/1 -1\n";
+        let mesh = parse_obj(data).unwrap();
+        assert_eq!(mesh.faces, vec![vec![0, 1, 2, 3]]);
+        assert_eq!(mesh.vertices[1].color, [255, 0, 0]);
+        assert_eq!(mesh.vertices[0].color, [255, 255, 255]);
+    }
+
+    #[test]
+    fn test_obj_errors_name_the_line() {
+        assert_eq!(parse_obj("v 0 0\n").unwrap_err(), "Invalid vertex on line 1");
+        assert_eq!(parse_obj("v 0 0 0\nf 1 2 3\n").unwrap_err(), "Face index out of range on line 2");
+        assert_eq!(parse_obj("v 0 0 0\nf 1 x 1\n").unwrap_err(), "Invalid face on line 2");
+    }
+
+    #[test]
+    fn test_ply_round_trip_keeps_colours() {
+        let mesh = parse_ply(write_ply(&triangle()).as_bytes()).unwrap();
+        assert_eq!(mesh, triangle());
+    }
+
+    #[test]
+    fn test_binary_ply() {
+        let header = "ply\nformat binary_little_endian 1.0\ncomment made by hand\nelement vertex 3\n\
+                      property float x\nproperty float y\nproperty float z\nproperty uchar alpha\n\
+                      element face 1\nproperty list uchar uint vertex_indices\nend_header\n";
+        let mut data = header.as_bytes().to_vec();
+        for (x, y, z) in [(0.0f32, 0.0f32, 0.0f32), (1.0, 0.0, 0.0), (0.0, -2.5, 0.0)] {
+            for value in [x, y, z] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+            data.push(255);
+        }
+        data.push(3);
+        for i in [0u32, 2, 1] {
+            data.extend_from_slice(&i.to_le_bytes());
+        }
+        let mesh = parse_ply(&data).unwrap();
+        assert_eq!(mesh.vertices[2].position, Vector3::new(0.0, -2.5, 0.0));
+        assert_eq!(mesh.faces, vec![vec![0, 2, 1]]);
+
+        // The same data cut short is an error rather than a partial mesh
+        assert!(parse_ply(&data[..data.len() - 2]).is_err());
+    }
+
+    #[test]
+    fn test_ply_rejects_bad_files() {
+        assert!(parse_ply(b"solid cube\n").is_err());
+        let bad_index = "ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nproperty float y\n\
+                         property float z\nelement face 1\nproperty list uchar int vertex_indices\n\
+                         end_header\n0 0 0\n3 0 1 2\n";
+        assert_eq!(parse_ply(bad_index.as_bytes()).unwrap_err(), "PLY face index out of range");
+    }
+
+    #[test]
+    fn test_save_and_load_pick_format_from_extension() {
+        let dir = std::env::temp_dir();
+        for name in ["mesh_io_test.obj", "mesh_io_test.PLY", "mesh_io_test.stl"] {
+            let path = dir.join(name);
+            let path = path.to_str().unwrap();
+            save(path, &triangle()).unwrap();
+            assert_eq!(load(path).unwrap().faces, triangle().faces);
+            let _ = std::fs::remove_file(path);
+        }
+        assert!(save("mesh.xyz", &triangle()).is_err());
+    }
+
+    #[test]
+    fn test_creases_round_trip_as_lines_and_edges() {
+        let mut mesh = triangle();
+        mesh.creases = vec![(0, 1), (1, 2)];
+        assert_eq!(parse_obj(&write_obj(&mesh)).unwrap().creases, mesh.creases);
+        assert_eq!(parse_ply(write_ply(&mesh).as_bytes()).unwrap(), mesh);
+        // A polyline is a chain of crease edges
+        let data = "v 0 0 0\nv 1 0 0\nv 1 1 0\nf 1 2 3\nl 1 2 3\n";
+        assert_eq!(parse_obj(data).unwrap().creases, vec![(0, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn test_binary_stl_round_trip_welds_corners() {
+        let vertices = (0..4).map(|i| Point::from_vector(Vector3::new((i & 1) as f64, (i >> 1) as f64, 0.0))).collect();
+        let square = Mesh { vertices, faces: vec![vec![0, 1, 3, 2]], creases: Vec::new() };
+        let data = write_stl(&square);
+        // Header, count, then two 50-byte triangles
+        assert_eq!(data.len(), 84 + 2 * 50);
+        // The first triangle's normal faces +z
+        assert_eq!(&data[84..96], [0.0f32, 0.0, 1.0].iter().flat_map(|v| v.to_le_bytes()).collect::<Vec<_>>());
+        let mesh = parse_stl(&data).unwrap();
+        assert_eq!(mesh.vertices.len(), 4);
+        assert_eq!(mesh.faces, vec![vec![0, 1, 2], vec![0, 2, 3]]);
+    }
+
+    #[test]
+    fn test_ascii_stl() {
+        let data = "solid tri\n facet normal 0 0 1\n  outer loop\n   vertex 0 0 0\n   vertex 1 0 0\n\
+                    vertex 0 1 0\n  endloop\n endfacet\nendsolid tri\n";
+        let mesh = parse_stl(data.as_bytes()).unwrap();
+        assert_eq!(mesh.faces, vec![vec![0, 1, 2]]);
+        assert_eq!(mesh.vertices[2].position, Vector3::new(0.0, 1.0, 0.0));
+        let short = "solid tri\nfacet normal 0 0 1\nvertex 0 0 0\nendfacet\n";
+        assert_eq!(parse_stl(short.as_bytes()).unwrap_err(), "Facet without three vertices on line 4");
+        assert!(parse_stl(b"ply\n").is_err());
+    }
+
+    #[test]
+    fn test_meshes_that_cannot_be_subdivided_are_rejected() {
+        let quad = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 2 0 0\nv 0 2 0\n";
+        let inconsistent = format!("{}f 1 2 3\nf 1 4 3\n", quad);
+        assert_eq!(parse_obj(&inconsistent).unwrap_err(), "Faces next to edge 3-1 are not wound consistently");
+        let fin = format!("{}f 1 2 3\nf 2 1 5\nf 1 2 6\n", quad);
+        assert_eq!(parse_obj(&fin).unwrap_err(), "Edge 1-2 is shared by more than two faces");
+        let repeated = format!("{}f 1 2 2 3\n", quad);
+        assert_eq!(parse_obj(&repeated).unwrap_err(), "Face 1 uses vertex 2 twice");
+
+        let ply = "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\n\
+                   element face 2\nproperty list uchar int vertex_indices\nend_header\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n3 0 1 2\n";
+        assert!(parse_ply(ply.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_stl_drops_triangles_welded_flat() {
+        let data = "solid tri\nfacet normal 0 0 1\nvertex 0 0 0\nvertex 1 0 0\nvertex 0 1 0\nendfacet\n\
+                    facet normal 0 0 1\nvertex 0 0 0\nvertex 0 0 0\nvertex 1 0 0\nendfacet\nendsolid tri\n";
+        let mesh = parse_stl(data.as_bytes()).unwrap();
+        assert_eq!(mesh.faces, vec![vec![0, 1, 2]]);
+    }
+}
@@ -0,0 +1,265 @@
+use crate::mesh::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+    use crate::subdivider::Subdivider;
+    use nalgebra::Vector3;
+    use std::collections::HashSet;
+
+    fn point3(x: f64, y: f64, z: f64) -> Point<3> {
+        Point::from_vector(Vector3::new(x, y, z))
+    }
+
+    // Unit cube with outward-facing faces
+    fn cube() -> Mesh {
+        let vertices = (0..8).map(|i| point3((i & 1) as f64, ((i >> 1) & 1) as f64, ((i >> 2) & 1) as f64)).collect();
+        let faces = vec![
+            vec![0, 2, 3, 1],
+            vec![4, 5, 7, 6],
+            vec![0, 1, 5, 4],
+            vec![2, 6, 7, 3],
+            vec![0, 4, 6, 2],
+            vec![1, 3, 7, 5],
+        ];
+        Mesh { vertices, faces, creases: Vec::new() }
+    }
+
+    // Open `n` x `n` grid of unit quads in the z = 0 plane
+    fn grid(n: usize) -> Mesh {
+        let mut vertices = Vec::new();
+        for y in 0..=n {
+            for x in 0..=n {
+                vertices.push(point3(x as f64, y as f64, 0.0));
+            }
+        }
+        let mut faces = Vec::new();
+        for y in 0..n {
+            for x in 0..n {
+                let i = y * (n + 1) + x;
+                faces.push(vec![i, i + 1, i + n + 2, i + n + 1]);
+            }
+        }
+        Mesh { vertices, faces, creases: Vec::new() }
+    }
+
+    // Regular octahedron around the origin: six valence-4 vertices, the poles first
+    fn octahedron() -> Mesh {
+        let mut vertices = vec![point3(0.0, 0.0, 1.0), point3(0.0, 0.0, -1.0)];
+        vertices.extend([(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)].iter().map(|&(x, y)| point3(x, y, 0.0)));
+        let mut faces = Vec::new();
+        for i in 0..4 {
+            let (a, b) = (2 + i, 2 + (i + 1) % 4);
+            faces.push(vec![0, a, b]);
+            faces.push(vec![1, b, a]);
+        }
+        Mesh { vertices, faces, creases: Vec::new() }
+    }
+
+    // Where a smooth vertex ends up on the Loop limit surface
+    fn loop_limit(mesh: &Mesh, v: usize) -> Vector3<f64> {
+        let neighbours: Vec<usize> = mesh
+            .edges()
+            .iter()
+            .filter(|&&(a, b)| a == v || b == v)
+            .map(|&(a, b)| if a == v { b } else { a })
+            .collect();
+        let n = neighbours.len() as f64;
+        let c = 0.375 + 0.25 * (2.0 * std::f64::consts::PI / n).cos();
+        let beta = (0.625 - c * c) / n;
+        let chi = 1.0 / (3.0 / (8.0 * beta) + n);
+        let sum: Vector3<f64> = neighbours.iter().map(|&u| mesh.vertices[u].position).sum();
+        mesh.vertices[v].position * (1.0 - n * chi) + sum * chi
+    }
+
+    // Closed, consistently oriented meshes use every directed edge exactly once
+    fn is_closed_and_oriented(mesh: &Mesh) -> bool {
+        let mut half_edges = HashSet::new();
+        for face in &mesh.faces {
+            for (i, &a) in face.iter().enumerate() {
+                if !half_edges.insert((a, face[(i + 1) % face.len()])) {
+                    return false;
+                }
+            }
+        }
+        half_edges.iter().all(|&(a, b)| half_edges.contains(&(b, a)))
+    }
+
+    #[test]
+    fn test_scheme_names_parse() {
+        assert_eq!(SurfaceScheme::parse("doo-sabin"), Some(SurfaceScheme::DooSabin));
+        assert_eq!(SurfaceScheme::parse("Catmull_Clark"), Some(SurfaceScheme::CatmullClark));
+        assert_eq!(SurfaceScheme::parse("butterfly"), None);
+    }
+
+    #[test]
+    fn test_edges_are_listed_once() {
+        assert_eq!(cube().edges().len(), 12);
+        assert_eq!(grid(2).edges().len(), 12);
+        assert!(cube().edges().iter().all(|&(a, b)| a < b));
+    }
+
+    #[test]
+    fn test_catmull_clark_cube_counts_and_shape() {
+        let mesh = cube().catmull_clark();
+        // Old vertices, then one per edge, then one per face
+        assert_eq!(mesh.vertices.len(), 8 + 12 + 6);
+        assert_eq!(mesh.faces.len(), 24);
+        assert!(mesh.faces.iter().all(|f| f.len() == 4));
+        assert!(is_closed_and_oriented(&mesh));
+
+        // Valence-3 corners move in along the diagonal to 2/9
+        let corner = &mesh.vertices[0].position;
+        assert!((corner - Vector3::repeat(2.0 / 9.0)).norm() < 1e-12);
+        // Face points are the face centres
+        assert!((mesh.vertices[26 - 6].position - Vector3::new(0.5, 0.5, 0.0)).norm() < 1e-12);
+
+        let twice = mesh.catmull_clark();
+        assert_eq!(twice.faces.len(), 96);
+        assert!(is_closed_and_oriented(&twice));
+    }
+
+    #[test]
+    fn test_doo_sabin_cube_counts() {
+        let mesh = cube().doo_sabin();
+        // One new point per face corner; faces for every face, edge and vertex
+        assert_eq!(mesh.vertices.len(), 24);
+        assert_eq!(mesh.faces.len(), 6 + 12 + 8);
+        assert_eq!(mesh.faces.iter().filter(|f| f.len() == 3).count(), 8);
+        assert!(is_closed_and_oriented(&mesh));
+        assert!(is_closed_and_oriented(&mesh.doo_sabin()));
+    }
+
+    #[test]
+    fn test_doo_sabin_quads_cut_like_chaikin() {
+        let mesh = grid(1).doo_sabin();
+        // A single quad only shrinks: each corner goes a quarter of the way in
+        let positions: Vec<Vector3<f64>> = mesh.vertices.iter().map(|p| p.position).collect();
+        let expected = [(0.25, 0.25), (0.75, 0.25), (0.75, 0.75), (0.25, 0.75)];
+        for (p, (x, y)) in positions.iter().zip(expected) {
+            assert!((p - Vector3::new(x, y, 0.0)).norm() < 1e-12);
+        }
+        assert_eq!(mesh.faces, vec![vec![0, 1, 2, 3]]);
+
+        // Inner edges and vertices of a grid get gap faces, boundary ones do not
+        let mesh = grid(2).doo_sabin();
+        assert_eq!(mesh.faces.len(), 4 + 4 + 1);
+    }
+
+    #[test]
+    fn test_catmull_clark_keeps_flat_grid_flat_and_boundary_on_curve() {
+        let mesh = grid(2).catmull_clark();
+        assert_eq!(mesh.faces.len(), 16);
+        assert!(mesh.vertices.iter().all(|p| p.position.z == 0.0));
+        // The middle of a regular grid stays put
+        assert!((mesh.vertices[4].position - Vector3::new(1.0, 1.0, 0.0)).norm() < 1e-12);
+        // Corners are kept and boundary points stay on the boundary
+        assert_eq!(mesh.vertices[0].position, Vector3::zeros());
+        assert_eq!(mesh.vertices[1].position, Vector3::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_subdivision_blends_colours() {
+        let mut mesh = grid(1);
+        mesh.vertices[0].color = [255, 0, 0];
+        mesh.vertices[2].color = [0, 0, 255];
+        let refined = mesh.subdivide(SurfaceScheme::CatmullClark);
+        // The face point averages all four corners
+        assert_eq!(refined.vertices.last().unwrap().color, [191, 128, 191]);
+    }
+
+    #[test]
+    fn test_loop_counts_on_octahedron() {
+        let mesh = octahedron().loop_subdivision();
+        // Old vertices first, then one per edge
+        assert_eq!(mesh.vertices.len(), 6 + 12);
+        assert_eq!(mesh.faces.len(), 32);
+        assert_eq!(mesh.edges().len(), 48);
+        assert!(mesh.faces.iter().all(|f| f.len() == 3));
+        assert!(is_closed_and_oriented(&mesh));
+        // Edge points take 3/8 of each end and 1/8 of the two opposite vertices
+        assert_eq!(mesh.vertices[6].position, Vector3::new(0.375, 0.0, 0.375));
+    }
+
+    #[test]
+    fn test_loop_converges_to_limit_positions() {
+        let mesh = octahedron();
+        let limit = loop_limit(&mesh, 0);
+        // The pole moves straight down to 96/220 of its height
+        assert!((limit - Vector3::new(0.0, 0.0, 96.0 / 220.0)).norm() < 1e-12);
+        // Each level keeps the same limit...
+        let refined = mesh.loop_subdivision();
+        assert!((loop_limit(&refined, 0) - limit).norm() < 1e-12);
+        // ...and the vertex itself closes in on it
+        let far = SurfaceScheme::Loop.subdivide_times(&mesh, 6);
+        assert!((far.vertices[0].position - limit).norm() < 5e-3);
+        assert!((far.vertices[0].position - limit).norm() < (refined.vertices[0].position - limit).norm());
+    }
+
+    #[test]
+    fn test_loop_boundary_follows_cubic_bspline() {
+        // Hexagonal fan: the rim is a boundary with two faces at every rim vertex
+        let mut vertices = vec![point3(0.0, 0.0, 0.5)];
+        for i in 0..6 {
+            let angle = i as f64 * std::f64::consts::PI / 3.0;
+            vertices.push(point3(angle.cos(), angle.sin(), 0.0));
+        }
+        let faces = (0..6).map(|i| vec![0, 1 + i, 1 + (i + 1) % 6]).collect();
+        let mesh = Mesh { vertices, faces, creases: Vec::new() };
+        let far = SurfaceScheme::Loop.subdivide_times(&mesh, 5);
+        // The rim ignores the raised centre and converges to the B-spline limit (a + 4v + b) / 6
+        let p = |i: usize| mesh.vertices[i].position;
+        let limit = (p(6) + p(1) * 4.0 + p(2)) / 6.0;
+        assert!((far.vertices[1].position - limit).norm() < 1e-3);
+        assert!(far.vertices[1].position.z == 0.0);
+    }
+
+    #[test]
+    fn test_loop_creases_stay_sharp() {
+        let mut mesh = octahedron();
+        mesh.creases = (0..4).map(|i| (2 + i, 2 + (i + 1) % 4)).collect();
+        mesh.vertices[0].crease = true;
+        let refined = SurfaceScheme::Loop.subdivide_times(&mesh, 2);
+        // Each level splits every crease edge in two
+        assert_eq!(refined.creases.len(), 16);
+        // The equator stays in its plane while the unmarked pole below is pulled in
+        for &(a, b) in &refined.creases {
+            assert!(refined.vertices[a].position.z.abs() < 1e-12 && refined.vertices[b].position.z.abs() < 1e-12);
+        }
+        assert!(refined.vertices[1].position.z > -0.9);
+        // The crease point stays put and stays a crease
+        assert_eq!(refined.vertices[0].position, Vector3::new(0.0, 0.0, 1.0));
+        assert!(refined.vertices[0].crease);
+    }
+
+    #[test]
+    fn test_loop_triangulates_other_faces_first() {
+        let mesh = grid(1).loop_subdivision();
+        assert_eq!(mesh.faces.len(), 8);
+        assert_eq!(grid(1).triangulate().faces, vec![vec![0, 1, 3], vec![0, 3, 2]]);
+    }
+
+    #[test]
+    fn test_catmull_clark_creases_keep_cube_corners() {
+        let mut mesh = cube();
+        mesh.creases = mesh.edges();
+        let refined = mesh.catmull_clark().catmull_clark();
+        assert_eq!(refined.creases.len(), 48);
+        // Three creases meet at every corner, so the corners stay put
+        assert_eq!(refined.vertices[7].position, Vector3::new(1.0, 1.0, 1.0));
+        // Crease edges are straight, so their points stay on the cube's edges
+        assert_eq!(refined.vertices[8].position, Vector3::new(0.0, 0.5, 0.0));
+    }
+
+    #[test]
+    fn test_doo_sabin_treats_inconsistent_edges_as_boundary() {
+        // The second face runs along the shared edge the same way as the first
+        let vertices = vec![point3(0.0, 0.0, 0.0), point3(1.0, 0.0, 0.0), point3(1.0, 1.0, 0.0), point3(0.0, 1.0, 0.0)];
+        let mesh = Mesh { vertices, faces: vec![vec![0, 1, 2], vec![0, 3, 2]], creases: Vec::new() };
+        assert!(mesh.check_manifold().is_err());
+        let refined = mesh.doo_sabin();
+        assert_eq!(refined.faces.len(), 2);
+        assert!(cube().check_manifold().is_ok());
+    }
+}
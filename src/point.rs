@@ -1,24 +1,115 @@
-use nalgebra::Vector2;
+use nalgebra::{SVector, Vector2};
 
+// Weight range for `Point::weight`
+pub const MIN_WEIGHT: f64 = 0.5;
+pub const MAX_WEIGHT: f64 = 8.0;
+
+// A control or curve point in `D` dimensions. The canvas works in 2D, which is the
+// default; 3D and higher are for paths such as camera rails and robot trajectories.
 #[derive(Clone, Debug, PartialEq)]
-pub struct Point {
-    pub position: Vector2<f64>,
+pub struct Point<const D: usize = 2> {
+    pub position: SVector<f64, D>,
     pub color: [u8; 3],
+    // Crease points are kept as sharp corners by the subdivision
+    pub crease: bool,
+    // Tension: how tightly the curve hugs this point. 1 is plain Chaikin, higher values
+    // cut less of the corner, 0.5 cuts it at the edge midpoints.
+    pub weight: f64,
+}
+
+impl<const D: usize> Default for Point<D> {
+    fn default() -> Self {
+        Self::from_vector(SVector::zeros())
+    }
 }
 
 impl Point {
     pub fn new(x: f64, y: f64) -> Self {
+        Self::from_vector(Vector2::new(x, y))
+    }
+
+    pub fn with_color(x: f64, y: f64, color: [u8; 3]) -> Self {
         Self {
-            position: Vector2::new(x, y),
-            color: [255, 255, 255], 
+            color,
+            ..Self::new(x, y)
         }
     }
+}
 
-    pub fn with_color(x: f64, y: f64, color: [u8; 3]) -> Self {
+impl<const D: usize> Point<D> {
+    // A plain white point with the default weight
+    pub fn from_vector(position: SVector<f64, D>) -> Self {
         Self {
-            position: Vector2::new(x, y),
+            position,
+            color: [255, 255, 255],
+            crease: false,
+            weight: 1.0,
+        }
+    }
+
+    // Point `t` of the way from this point to `other`. Like `combine`, this blends every
+    // attribute the same way as the position; new per-point attributes belong in both.
+    pub fn lerp(&self, other: &Point<D>, t: f64) -> Point<D> {
+        let mut color = [0; 3];
+        for (c, (a, b)) in color.iter_mut().zip(self.color.iter().zip(other.color)) {
+            *c = (*a as f64 + (b as f64 - *a as f64) * t).round().clamp(0.0, 255.0) as u8;
+        }
+        Point {
+            position: self.position + (other.position - self.position) * t,
             color,
+            crease: false,
+            weight: (self.weight + (other.weight - self.weight) * t).clamp(MIN_WEIGHT, MAX_WEIGHT),
         }
     }
+
+    // Weighted sum of points, as used by subdivision masks. The weights should add up to
+    // one; colours are clamped where negative weights overshoot.
+    pub fn combine(terms: &[(f64, &Point<D>)]) -> Point<D> {
+        let mut position = SVector::zeros();
+        let mut color = [0.0; 3];
+        let mut weight = 0.0;
+        for &(w, p) in terms {
+            position += p.position * w;
+            for (c, &channel) in color.iter_mut().zip(&p.color) {
+                *c += w * channel as f64;
+            }
+            weight += w * p.weight;
+        }
+        Point {
+            position,
+            color: color.map(|c| c.round().clamp(0.0, 255.0) as u8),
+            crease: false,
+            weight: weight.clamp(MIN_WEIGHT, MAX_WEIGHT),
+        }
+    }
+
+    // Fraction of each neighbouring edge that Chaikin's corner cutting takes off at this point
+    pub fn cut_ratio(&self) -> f64 {
+        (0.25 / self.weight.max(MIN_WEIGHT)).min(0.5)
+    }
+}
+
+// Settings a control point carries besides its position
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointAttributes {
+    pub crease: bool,
+    pub weight: f64,
+    // Painted colour; unpainted points take the curve's colour
+    pub color: Option<[u8; 3]>,
 }
 
+impl Default for PointAttributes {
+    fn default() -> Self {
+        Self { crease: false, weight: 1.0, color: None }
+    }
+}
+
+impl PointAttributes {
+    pub fn increase_weight(&mut self) {
+        self.weight = (self.weight * 1.25).min(MAX_WEIGHT);
+    }
+
+    pub fn decrease_weight(&mut self) {
+        self.weight = (self.weight / 1.25).max(MIN_WEIGHT);
+    }
+}
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
-    use super::super::point::Point;
-    use nalgebra::Vector2;
+    use super::super::point::{Point, PointAttributes, MAX_WEIGHT, MIN_WEIGHT};
+    use nalgebra::{Vector2, Vector4};
 
 
     #[test]
@@ -18,4 +18,69 @@ mod tests {
         assert_eq!(point.position, Vector2::new(3.0, 4.0));
         assert_eq!(point.color, color);
     }
+
+    #[test]
+    fn test_point_defaults_to_smooth_unit_weight() {
+        let point = Point::new(1.0, 2.0);
+        assert!(!point.crease);
+        assert_eq!(point.weight, 1.0);
+        assert_eq!(point.cut_ratio(), 0.25);
+        assert_eq!(Point::default(), Point::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_cut_ratio_follows_weight() {
+        let mut point = Point::new(0.0, 0.0);
+        point.weight = 2.0;
+        assert_eq!(point.cut_ratio(), 0.125);
+        // Light points never cut past the edge midpoint
+        point.weight = 0.1;
+        assert_eq!(point.cut_ratio(), 0.5);
+    }
+
+    #[test]
+    fn test_attribute_weight_stays_in_range() {
+        let mut attributes = PointAttributes::default();
+        for _ in 0..50 {
+            attributes.increase_weight();
+        }
+        assert_eq!(attributes.weight, MAX_WEIGHT);
+        for _ in 0..50 {
+            attributes.decrease_weight();
+        }
+        assert_eq!(attributes.weight, MIN_WEIGHT);
+    }
+
+    #[test]
+    fn test_lerp_blends_position_color_and_weight() {
+        let a = Point { weight: 1.0, crease: true, ..Point::with_color(0.0, 0.0, [0, 100, 255]) };
+        let b = Point { weight: 3.0, ..Point::with_color(10.0, 20.0, [100, 0, 255]) };
+        let mid = a.lerp(&b, 0.5);
+        assert_eq!(mid.position, Vector2::new(5.0, 10.0));
+        assert_eq!(mid.color, [50, 50, 255]);
+        assert_eq!(mid.weight, 2.0);
+        // New points are never creases
+        assert!(!mid.crease);
+        assert_eq!(a.lerp(&b, 0.0).color, a.color);
+    }
+
+    #[test]
+    fn test_combine_clamps_overshooting_colors() {
+        let a = Point::with_color(0.0, 0.0, [255, 0, 10]);
+        let b = Point::with_color(4.0, 0.0, [0, 255, 10]);
+        let p = Point::combine(&[(1.5, &a), (-0.5, &b)]);
+        assert_eq!(p.position, Vector2::new(-2.0, 0.0));
+        assert_eq!(p.color, [255, 0, 10]);
+    }
+
+    #[test]
+    fn test_points_in_higher_dimensions() {
+        let a: Point<4> = Point::from_vector(Vector4::new(0.0, 2.0, 4.0, 6.0));
+        let b = Point { weight: 3.0, ..Point::from_vector(Vector4::new(4.0, 2.0, 0.0, -2.0)) };
+        assert_eq!(Point::<4>::default().position, Vector4::zeros());
+        assert_eq!(a.lerp(&b, 0.25).position, Vector4::new(1.0, 2.0, 3.0, 4.0));
+        let sum = Point::combine(&[(0.5, &a), (0.5, &b)]);
+        assert_eq!(sum.position, Vector4::new(2.0, 2.0, 2.0, 2.0));
+        assert_eq!(sum.weight, 2.0);
+    }
 }
@@ -0,0 +1,109 @@
+// Headless wireframe previews of meshes, drawn into the same 0RGB framebuffer as the
+// canvas so they can be written out as images without opening a window.
+use nalgebra::Vector3;
+use super::mesh::Mesh;
+use super::raster::{pack_color, unpack_color, Raster};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection {
+    Orthographic,
+    // Perspective with the given vertical field of view in radians
+    Perspective(f64),
+}
+
+// Where the mesh is looked at from. The mesh is always centred and scaled to fill the
+// view, so only the direction and projection need choosing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    pub projection: Projection,
+    // Turn about the vertical axis, then tilt towards the viewer, in radians
+    pub yaw: f64,
+    pub pitch: f64,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            projection: Projection::Orthographic,
+            yaw: 30f64.to_radians(),
+            pitch: 20f64.to_radians(),
+        }
+    }
+}
+
+impl Camera {
+    // Screen positions of the mesh's vertices in a `width` x `height` view, or None for
+    // vertices behind a perspective camera
+    pub fn project(&self, mesh: &Mesh, width: usize, height: usize) -> Vec<Option<(f64, f64)>> {
+        if mesh.vertices.is_empty() {
+            return Vec::new();
+        }
+        let (mut min, mut max) = (Vector3::repeat(f64::MAX), Vector3::repeat(f64::MIN));
+        for p in &mesh.vertices {
+            min = min.inf(&p.position);
+            max = max.sup(&p.position);
+        }
+        let center = (min + max) / 2.0;
+        let radius = mesh.vertices.iter().map(|p| (p.position - center).norm()).fold(0.0, f64::max).max(1e-9);
+        // Leave a margin so the mesh never touches the edges
+        let scale = 0.45 * width.min(height) as f64;
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+
+        mesh.vertices
+            .iter()
+            .map(|p| {
+                let v = (p.position - center) / radius;
+                // Yaw about y, then pitch about x; +z points at the viewer
+                let (x, z) = (v.x * cos_yaw + v.z * sin_yaw, -v.x * sin_yaw + v.z * cos_yaw);
+                let (y, z) = (v.y * cos_pitch - z * sin_pitch, v.y * sin_pitch + z * cos_pitch);
+                let (x, y) = match self.projection {
+                    Projection::Orthographic => (x, y),
+                    Projection::Perspective(fov) => {
+                        // Just far enough back for the unit sphere to fill the field of view
+                        let focal = 1.0 / (fov / 2.0).tan();
+                        let depth = 1.0 / (fov / 2.0).sin() - z;
+                        if depth <= 1e-6 {
+                            return None;
+                        }
+                        (x * focal / depth, y * focal / depth)
+                    }
+                };
+                Some((width as f64 / 2.0 + x * scale, height as f64 / 2.0 - y * scale))
+            })
+            .collect()
+    }
+}
+
+// Draw every edge of the mesh once
+pub fn draw_wireframe(raster: &mut Raster, mesh: &Mesh, camera: &Camera, color: [u8; 3]) {
+    let screen = camera.project(mesh, raster.width, raster.height);
+    for (a, b) in mesh.edges() {
+        if let (Some((x1, y1)), Some((x2, y2))) = (screen[a], screen[b]) {
+            raster.draw_line_aa(x1, y1, x2, y2, color);
+        }
+    }
+}
+
+// Render a wireframe into a fresh framebuffer
+pub fn render(mesh: &Mesh, camera: &Camera, width: usize, height: usize, background: [u8; 3], color: [u8; 3]) -> Vec<u32> {
+    let mut buffer = vec![pack_color(background); width * height];
+    draw_wireframe(&mut Raster::new(&mut buffer, width, height), mesh, camera, color);
+    buffer
+}
+
+// Save a framebuffer as a binary PPM image
+pub fn write_ppm(path: &str, buffer: &[u32], width: usize, height: usize) -> Result<(), String> {
+    let mut data = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    for &pixel in buffer.iter().take(width * height) {
+        data.extend_from_slice(&unpack_color(pixel));
+    }
+    std::fs::write(path, data).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
+
+// Save greyscale pixels as a binary PGM image
+pub fn write_pgm(path: &str, pixels: &[u8], width: usize, height: usize) -> Result<(), String> {
+    let mut data = format!("P5\n{} {}\n255\n", width, height).into_bytes();
+    data.extend(pixels.iter().take(width * height));
+    std::fs::write(path, data).map_err(|e| format!("Failed to write {}: {}", path, e))
+}
@@ -0,0 +1,73 @@
+use crate::preview::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::Mesh;
+    use crate::point::Point;
+    use crate::raster::pack_color;
+    use nalgebra::Vector3;
+
+    fn square() -> Mesh {
+        let vertices = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+            .iter()
+            .map(|&(x, y)| Point::from_vector(Vector3::new(x, y, 0.0)))
+            .collect();
+        Mesh { vertices, faces: vec![vec![0, 1, 2, 3]], creases: Vec::new() }
+    }
+
+    fn front() -> Camera {
+        Camera { yaw: 0.0, pitch: 0.0, ..Camera::default() }
+    }
+
+    #[test]
+    fn test_orthographic_projection_centres_and_fits() {
+        let screen = front().project(&square(), 200, 100);
+        // Corners lie on the bounding sphere, which fills 90% of the shorter side
+        let (x, y) = screen[2].unwrap();
+        let r = 45.0 / 2f64.sqrt();
+        assert!((x - (100.0 + r)).abs() < 1e-9 && (y - (50.0 - r)).abs() < 1e-9);
+        assert!(Camera::default().project(&Mesh::default(), 10, 10).is_empty());
+    }
+
+    #[test]
+    fn test_perspective_shrinks_far_points() {
+        let mut mesh = square();
+        mesh.vertices[0].position.z = -1.0;
+        mesh.vertices[2].position.z = 1.0;
+        let camera = Camera { projection: Projection::Perspective(60f64.to_radians()), ..front() };
+        let screen = camera.project(&mesh, 100, 100);
+        let offset = |i: usize| {
+            let (x, y) = screen[i].unwrap();
+            (x - 50.0).abs() + (y - 50.0).abs()
+        };
+        assert!(offset(2) > offset(0));
+    }
+
+    #[test]
+    fn test_render_draws_edges_on_background() {
+        let background = [10, 20, 30];
+        let buffer = render(&square(), &front(), 100, 100, background, [255, 255, 255]);
+        assert_eq!(buffer[50 * 100 + 50], pack_color(background));
+        // Four anti-aliased edges of about 64 px each
+        assert!(buffer.iter().filter(|&&p| p != pack_color(background)).count() > 4 * 60);
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let path = std::env::temp_dir().join("preview_test.ppm");
+        let path = path.to_str().unwrap();
+        write_ppm(path, &[pack_color([1, 2, 3]), pack_color([4, 5, 6])], 2, 1).unwrap();
+        assert_eq!(std::fs::read(path).unwrap(), b"P6\n2 1\n255\n\x01\x02\x03\x04\x05\x06");
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_write_pgm() {
+        let path = std::env::temp_dir().join("preview_test.pgm");
+        let path = path.to_str().unwrap();
+        write_pgm(path, &[0, 128, 255], 3, 1).unwrap();
+        assert_eq!(std::fs::read(path).unwrap(), b"P5\n3 1\n255\n\x00\x80\xff");
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,342 @@
+// Software rasteriser working directly on a 0RGB `u32` framebuffer
+
+pub fn pack_color(color: [u8; 3]) -> u32 {
+    ((color[0] as u32) << 16) | ((color[1] as u32) << 8) | (color[2] as u32)
+}
+
+pub fn unpack_color(pixel: u32) -> [u8; 3] {
+    [(pixel >> 16) as u8, (pixel >> 8) as u8, pixel as u8]
+}
+
+// Blend `src` over `dst` with the given opacity in [0, 1]
+pub fn blend_color(dst: u32, src: [u8; 3], alpha: f64) -> u32 {
+    let alpha = alpha.clamp(0.0, 1.0);
+    let dst = unpack_color(dst);
+    let mut out = [0u8; 3];
+    for i in 0..3 {
+        let value = src[i] as f64 * alpha + dst[i] as f64 * (1.0 - alpha);
+        out[i] = value.round() as u8;
+    }
+    pack_color(out)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FillRule {
+    NonZero,
+    EvenOdd,
+}
+
+impl FillRule {
+    fn is_inside(&self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Paint {
+    Solid([u8; 3]),
+    // Colour ramp from `from` at `start` to `to` at `end`, clamped beyond both ends
+    LinearGradient {
+        start: (f64, f64),
+        end: (f64, f64),
+        from: [u8; 3],
+        to: [u8; 3],
+    },
+}
+
+impl Paint {
+    pub fn color_at(&self, x: f64, y: f64) -> [u8; 3] {
+        match *self {
+            Paint::Solid(color) => color,
+            Paint::LinearGradient { start, end, from, to } => {
+                let axis = (end.0 - start.0, end.1 - start.1);
+                let length_sq = axis.0 * axis.0 + axis.1 * axis.1;
+                let t = if length_sq > 0.0 {
+                    (((x - start.0) * axis.0 + (y - start.1) * axis.1) / length_sq).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let mut color = [0u8; 3];
+                for i in 0..3 {
+                    color[i] = (from[i] as f64 + t * (to[i] as f64 - from[i] as f64)).round() as u8;
+                }
+                color
+            }
+        }
+    }
+}
+
+fn fpart(value: f64) -> f64 {
+    value - value.floor()
+}
+
+pub struct Raster<'a> {
+    pub buffer: &'a mut [u32],
+    pub width: usize,
+    pub height: usize,
+}
+
+impl<'a> Raster<'a> {
+    pub fn new(buffer: &'a mut [u32], width: usize, height: usize) -> Self {
+        Self { buffer, width, height }
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return None;
+        }
+        let idx = y as usize * self.width + x as usize;
+        if idx < self.buffer.len() {
+            Some(idx)
+        } else {
+            None
+        }
+    }
+
+    pub fn set_pixel(&mut self, x: i32, y: i32, color: [u8; 3]) {
+        if let Some(idx) = self.index(x, y) {
+            self.buffer[idx] = pack_color(color);
+        }
+    }
+
+    pub fn blend_pixel(&mut self, x: i32, y: i32, color: [u8; 3], alpha: f64) {
+        if alpha <= 0.0 {
+            return;
+        }
+        if let Some(idx) = self.index(x, y) {
+            self.buffer[idx] = blend_color(self.buffer[idx], color, alpha);
+        }
+    }
+
+    // Blend a solid rectangle, clipped to the buffer
+    pub fn fill_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: [u8; 3], alpha: f64) {
+        for py in y.max(0)..(y + height).min(self.height as i32) {
+            for px in x.max(0)..(x + width).min(self.width as i32) {
+                self.blend_pixel(px, py, color, alpha);
+            }
+        }
+    }
+
+    // Bresenham's line algorithm on integer-truncated endpoints
+    pub fn draw_line(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, color: [u8; 3]) {
+        let x1 = x1 as i32;
+        let y1 = y1 as i32;
+        let x2 = x2 as i32;
+        let y2 = y2 as i32;
+
+        let dx = (x2 - x1).abs();
+        let dy = (y2 - y1).abs();
+        let sx = if x1 < x2 { 1 } else { -1 };
+        let sy = if y1 < y2 { 1 } else { -1 };
+        let mut err = dx - dy;
+        let mut x = x1;
+        let mut y = y1;
+
+        while x != x2 || y != y2 {
+            self.set_pixel(x, y, color);
+
+            let e2 = 2 * err;
+            if e2 > -dy {
+                err -= dy;
+                x += sx;
+            }
+            if e2 < dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    // Xiaolin Wu's anti-aliased line with sub-pixel endpoints. Pixel centres sit at
+    // integer + 0.5, matching `draw_disc_aa`.
+    pub fn draw_line_aa(&mut self, x1: f64, y1: f64, x2: f64, y2: f64, color: [u8; 3]) {
+        let (mut x1, mut y1, mut x2, mut y2) = (x1 - 0.5, y1 - 0.5, x2 - 0.5, y2 - 0.5);
+        let steep = (y2 - y1).abs() > (x2 - x1).abs();
+        if steep {
+            std::mem::swap(&mut x1, &mut y1);
+            std::mem::swap(&mut x2, &mut y2);
+        }
+        if x1 > x2 {
+            std::mem::swap(&mut x1, &mut x2);
+            std::mem::swap(&mut y1, &mut y2);
+        }
+
+        let dx = x2 - x1;
+        let dy = y2 - y1;
+        let gradient = if dx == 0.0 { 1.0 } else { dy / dx };
+
+        // Plot with the major axis along x; swap back for steep lines. Flooring keeps
+        // pixels left of or above the canvas where they belong instead of rounding to 0.
+        let plot = |raster: &mut Self, x: f64, y: f64, alpha: f64| {
+            let (x, y) = (x.floor() as i32, y.floor() as i32);
+            if steep {
+                raster.blend_pixel(y, x, color, alpha);
+            } else {
+                raster.blend_pixel(x, y, color, alpha);
+            }
+        };
+
+        // First endpoint
+        let x_end = x1.round();
+        let y_end = y1 + gradient * (x_end - x1);
+        let x_gap = 1.0 - fpart(x1 + 0.5);
+        let xpxl1 = x_end;
+        let ypxl1 = y_end.floor();
+        plot(self, xpxl1, ypxl1, (1.0 - fpart(y_end)) * x_gap);
+        plot(self, xpxl1, ypxl1 + 1.0, fpart(y_end) * x_gap);
+        let mut intery = y_end + gradient;
+
+        // Second endpoint
+        let x_end = x2.round();
+        let y_end = y2 + gradient * (x_end - x2);
+        let x_gap = fpart(x2 + 0.5);
+        let xpxl2 = x_end;
+        let ypxl2 = y_end.floor();
+        plot(self, xpxl2, ypxl2, (1.0 - fpart(y_end)) * x_gap);
+        plot(self, xpxl2, ypxl2 + 1.0, fpart(y_end) * x_gap);
+
+        // Main loop
+        let mut x = xpxl1 + 1.0;
+        while x < xpxl2 {
+            let base = intery.floor();
+            let frac = intery - base;
+            plot(self, x, base, 1.0 - frac);
+            plot(self, x, base + 1.0, frac);
+            intery += gradient;
+            x += 1.0;
+        }
+    }
+
+    // Filled disc on an integer-aligned centre, no anti-aliasing
+    pub fn draw_disc(&mut self, x: f64, y: f64, radius: f64, color: [u8; 3]) {
+        let x = x as i32;
+        let y = y as i32;
+
+        for dx in -radius as i32..=radius as i32 {
+            for dy in -radius as i32..=radius as i32 {
+                if dx * dx + dy * dy <= (radius * radius) as i32 {
+                    self.set_pixel(x + dx, y + dy, color);
+                }
+            }
+        }
+    }
+
+    // Filled disc with sub-pixel centre. Coverage is approximated by the signed distance
+    // from each pixel centre to the circle edge, which is accurate to within a few percent
+    // for radii above one pixel.
+    pub fn draw_disc_aa(&mut self, x: f64, y: f64, radius: f64, color: [u8; 3]) {
+        let min_x = (x - radius - 1.0).floor() as i32;
+        let max_x = (x + radius + 1.0).ceil() as i32;
+        let min_y = (y - radius - 1.0).floor() as i32;
+        let max_y = (y + radius + 1.0).ceil() as i32;
+
+        for py in min_y..=max_y {
+            for px in min_x..=max_x {
+                let cx = px as f64 + 0.5 - x;
+                let cy = py as f64 + 0.5 - y;
+                let dist = (cx * cx + cy * cy).sqrt();
+                let coverage = (radius + 0.5 - dist).clamp(0.0, 1.0);
+                self.blend_pixel(px, py, color, coverage);
+            }
+        }
+    }
+
+    // Fill the union of several polygons using the non-zero winding rule
+    pub fn fill_polygons(&mut self, polygons: &[Vec<(f64, f64)>], color: [u8; 3], antialias: bool) {
+        self.fill_path(polygons, FillRule::NonZero, &Paint::Solid(color), antialias);
+    }
+
+    // Scanline polygon filler. All polygons form one path, so holes and overlaps are
+    // resolved by `rule`. With `antialias` each row is sampled on four sub-scanlines and
+    // span ends get fractional horizontal coverage; otherwise pixels are lit when their
+    // centre is inside.
+    pub fn fill_path(&mut self, polygons: &[Vec<(f64, f64)>], rule: FillRule, paint: &Paint, antialias: bool) {
+        let mut min_y = f64::MAX;
+        let mut max_y = f64::MIN;
+        let mut min_x = f64::MAX;
+        let mut max_x = f64::MIN;
+        for &(x, y) in polygons.iter().flatten() {
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+            min_y = min_y.min(y);
+            max_y = max_y.max(y);
+        }
+        if min_y > max_y {
+            return;
+        }
+
+        let x0 = (min_x.floor() as i32).max(0);
+        let x1 = (max_x.ceil() as i32).min(self.width as i32 - 1);
+        let y0 = (min_y.floor() as i32).max(0);
+        let y1 = (max_y.ceil() as i32).min(self.height as i32 - 1);
+        if x0 > x1 || y0 > y1 {
+            return;
+        }
+
+        let samples = if antialias { 4 } else { 1 };
+        let mut coverage = vec![0.0; (x1 - x0 + 1) as usize];
+        let mut crossings: Vec<(f64, i32)> = Vec::new();
+
+        for py in y0..=y1 {
+            coverage.iter_mut().for_each(|c| *c = 0.0);
+
+            for s in 0..samples {
+                let sy = py as f64 + (s as f64 + 0.5) / samples as f64;
+
+                crossings.clear();
+                for polygon in polygons {
+                    for i in 0..polygon.len() {
+                        let a = polygon[i];
+                        let b = polygon[(i + 1) % polygon.len()];
+                        if (a.1 <= sy && b.1 > sy) || (b.1 <= sy && a.1 > sy) {
+                            let x = a.0 + (sy - a.1) * (b.0 - a.0) / (b.1 - a.1);
+                            crossings.push((x, if b.1 > a.1 { 1 } else { -1 }));
+                        }
+                    }
+                }
+                crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+                let mut winding = 0;
+                let mut span_start = 0.0;
+                for &(x, dir) in &crossings {
+                    let was_inside = rule.is_inside(winding);
+                    winding += dir;
+                    let inside = rule.is_inside(winding);
+                    if !was_inside && inside {
+                        span_start = x;
+                    } else if was_inside && !inside {
+                        accumulate_span(&mut coverage, x0, span_start, x, samples, antialias);
+                    }
+                }
+            }
+
+            for (i, &c) in coverage.iter().enumerate() {
+                if c > 0.0 {
+                    let px = x0 + i as i32;
+                    let color = paint.color_at(px as f64 + 0.5, py as f64 + 0.5);
+                    self.blend_pixel(px, py, color, c.min(1.0));
+                }
+            }
+        }
+    }
+}
+
+// Add the part of [start, end) covered in each pixel of a row to `coverage`
+fn accumulate_span(coverage: &mut [f64], x0: i32, start: f64, end: f64, samples: usize, antialias: bool) {
+    let weight = 1.0 / samples as f64;
+    let first = (start.floor() as i32).max(x0);
+    let last = (end.ceil() as i32 - 1).min(x0 + coverage.len() as i32 - 1);
+
+    for px in first..=last {
+        let amount = if antialias {
+            (end.min(px as f64 + 1.0) - start.max(px as f64)).max(0.0)
+        } else {
+            let centre = px as f64 + 0.5;
+            if centre >= start && centre < end { 1.0 } else { 0.0 }
+        };
+        coverage[(px - x0) as usize] += amount * weight;
+    }
+}
@@ -0,0 +1,204 @@
+use crate::raster::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: [u8; 3] = [255, 255, 255];
+
+    fn intensity(buffer: &[u32], width: usize, x: usize, y: usize) -> u8 {
+        unpack_color(buffer[y * width + x])[0]
+    }
+
+    #[test]
+    fn test_pack_and_unpack_roundtrip() {
+        let color = [12, 34, 56];
+        assert_eq!(pack_color(color), 0x0C2238);
+        assert_eq!(unpack_color(pack_color(color)), color);
+    }
+
+    #[test]
+    fn test_blend_color_mixes_channels() {
+        assert_eq!(blend_color(0x000000, WHITE, 0.5), 0x808080);
+        assert_eq!(blend_color(0x000000, WHITE, 1.0), 0xFFFFFF);
+        assert_eq!(blend_color(0x123456, WHITE, 0.0), 0x123456);
+        // Opacity is clamped
+        assert_eq!(blend_color(0x000000, WHITE, 2.0), 0xFFFFFF);
+    }
+
+    #[test]
+    fn test_pixels_outside_buffer_are_ignored() {
+        let mut buffer = vec![0; 4 * 4];
+        let mut raster = Raster::new(&mut buffer, 4, 4);
+        raster.set_pixel(-1, 0, WHITE);
+        raster.set_pixel(4, 0, WHITE);
+        raster.blend_pixel(0, 4, WHITE, 1.0);
+        raster.draw_line_aa(-10.0, -10.0, 20.0, 20.0, WHITE);
+        raster.draw_disc_aa(-3.0, 2.0, 5.0, WHITE);
+        assert_eq!(buffer.len(), 16);
+    }
+
+    #[test]
+    fn test_aliased_line_sets_full_intensity_pixels() {
+        let mut buffer = vec![0; 10 * 10];
+        Raster::new(&mut buffer, 10, 10).draw_line(1.0, 1.0, 8.0, 1.0, WHITE);
+        for x in 1..8 {
+            assert_eq!(intensity(&buffer, 10, x, 1), 255);
+        }
+        assert_eq!(intensity(&buffer, 10, 1, 2), 0);
+    }
+
+    #[test]
+    fn test_aa_line_on_pixel_centres_is_solid() {
+        let mut buffer = vec![0; 10 * 10];
+        Raster::new(&mut buffer, 10, 10).draw_line_aa(1.5, 4.5, 8.5, 4.5, WHITE);
+        for x in 2..8 {
+            assert_eq!(intensity(&buffer, 10, x, 4), 255);
+            assert_eq!(intensity(&buffer, 10, x, 5), 0);
+        }
+    }
+
+    #[test]
+    fn test_aa_line_between_rows_splits_coverage() {
+        let mut buffer = vec![0; 10 * 10];
+        Raster::new(&mut buffer, 10, 10).draw_line_aa(1.5, 5.0, 8.5, 5.0, WHITE);
+        for x in 2..8 {
+            let top = intensity(&buffer, 10, x, 4) as i32;
+            let bottom = intensity(&buffer, 10, x, 5) as i32;
+            assert!((top - 128).abs() <= 1);
+            assert!((bottom - 128).abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn test_aa_steep_line_is_drawn() {
+        let mut buffer = vec![0; 10 * 10];
+        Raster::new(&mut buffer, 10, 10).draw_line_aa(3.5, 1.5, 3.5, 8.5, WHITE);
+        for y in 2..8 {
+            assert_eq!(intensity(&buffer, 10, 3, y), 255);
+        }
+    }
+
+    #[test]
+    fn test_aa_line_with_negative_and_fractional_ends_stays_in_place() {
+        let mut buffer = vec![0; 10 * 10];
+        let mut raster = Raster::new(&mut buffer, 10, 10);
+        // Starts left of the buffer and ends half way across pixel 3
+        raster.draw_line_aa(-4.25, 2.5, 3.5, 2.5, WHITE);
+        // Half way between row 0 and the row above the buffer
+        raster.draw_line_aa(2.5, 0.0, 6.5, 0.0, WHITE);
+        for x in 0..3 {
+            assert_eq!(intensity(&buffer, 10, x, 2), 255);
+        }
+        assert!((intensity(&buffer, 10, 3, 2) as i32 - 128).abs() <= 1);
+        for x in 3..6 {
+            assert!((intensity(&buffer, 10, x, 0) as i32 - 128).abs() <= 1);
+        }
+        // Its ends cover half of pixels 2 and 6
+        assert!((intensity(&buffer, 10, 2, 0) as i32 - 64).abs() <= 1);
+        assert!((intensity(&buffer, 10, 6, 0) as i32 - 64).abs() <= 1);
+        // Nothing spills onto other pixels, such as the ends of the rows above
+        let lit = buffer.iter().filter(|&&c| c != 0).count();
+        assert_eq!(lit, 4 + 5);
+    }
+
+    #[test]
+    fn test_aa_line_outside_buffer_draws_nothing() {
+        let mut buffer = vec![0; 10 * 10];
+        let mut raster = Raster::new(&mut buffer, 10, 10);
+        raster.draw_line_aa(-8.5, -3.25, -1.75, -6.5, WHITE);
+        raster.draw_line_aa(-0.6, 2.0, -0.6, 8.0, WHITE);
+        raster.draw_line_aa(12.3, 4.5, 20.0, 5.25, WHITE);
+        raster.draw_line_aa(2.5, -1.6, 7.5, -1.6, WHITE);
+        assert!(buffer.iter().all(|&c| c == 0));
+    }
+
+    #[test]
+    fn test_aa_disc_coverage_fades_at_edge() {
+        let mut buffer = vec![0; 20 * 20];
+        Raster::new(&mut buffer, 20, 20).draw_disc_aa(10.0, 10.0, 4.3, WHITE);
+        // Fully inside
+        assert_eq!(intensity(&buffer, 20, 10, 10), 255);
+        // Far outside
+        assert_eq!(intensity(&buffer, 20, 0, 0), 0);
+        // A pixel straddling the edge is partially covered
+        let edge = intensity(&buffer, 20, 14, 10);
+        assert!(edge > 0 && edge < 255);
+    }
+
+    #[test]
+    fn test_aliased_disc_matches_integer_circle() {
+        let mut buffer = vec![0; 10 * 10];
+        Raster::new(&mut buffer, 10, 10).draw_disc(5.7, 5.2, 1.0, WHITE);
+        assert_eq!(intensity(&buffer, 10, 5, 5), 255);
+        assert_eq!(intensity(&buffer, 10, 6, 5), 255);
+        assert_eq!(intensity(&buffer, 10, 6, 6), 0);
+    }
+
+    // Outer square with an inner square wound the same way
+    fn nested_squares() -> Vec<Vec<(f64, f64)>> {
+        vec![
+            vec![(2.0, 2.0), (18.0, 2.0), (18.0, 18.0), (2.0, 18.0)],
+            vec![(6.0, 6.0), (14.0, 6.0), (14.0, 14.0), (6.0, 14.0)],
+        ]
+    }
+
+    #[test]
+    fn test_fill_path_non_zero_fills_nested_polygon() {
+        let mut buffer = vec![0; 20 * 20];
+        Raster::new(&mut buffer, 20, 20).fill_path(&nested_squares(), FillRule::NonZero, &Paint::Solid(WHITE), false);
+        assert_eq!(intensity(&buffer, 20, 10, 10), 255);
+        assert_eq!(intensity(&buffer, 20, 3, 3), 255);
+        assert_eq!(intensity(&buffer, 20, 1, 1), 0);
+    }
+
+    #[test]
+    fn test_fill_path_even_odd_leaves_hole() {
+        let mut buffer = vec![0; 20 * 20];
+        Raster::new(&mut buffer, 20, 20).fill_path(&nested_squares(), FillRule::EvenOdd, &Paint::Solid(WHITE), false);
+        assert_eq!(intensity(&buffer, 20, 10, 10), 0);
+        assert_eq!(intensity(&buffer, 20, 3, 3), 255);
+    }
+
+    #[test]
+    fn test_fill_path_self_intersecting_star() {
+        // Pentagram: the centre has winding number 2
+        let star: Vec<Vec<(f64, f64)>> = vec![(0..5)
+            .map(|i| {
+                let angle = -std::f64::consts::FRAC_PI_2 + i as f64 * 4.0 * std::f64::consts::PI / 5.0;
+                (20.0 + 18.0 * angle.cos(), 20.0 + 18.0 * angle.sin())
+            })
+            .collect()];
+
+        let mut non_zero = vec![0; 40 * 40];
+        let mut even_odd = vec![0; 40 * 40];
+        Raster::new(&mut non_zero, 40, 40).fill_path(&star, FillRule::NonZero, &Paint::Solid(WHITE), false);
+        Raster::new(&mut even_odd, 40, 40).fill_path(&star, FillRule::EvenOdd, &Paint::Solid(WHITE), false);
+        assert_eq!(intensity(&non_zero, 40, 20, 20), 255);
+        assert_eq!(intensity(&even_odd, 40, 20, 20), 0);
+    }
+
+    #[test]
+    fn test_fill_path_antialiased_edge_is_partial() {
+        let mut buffer = vec![0; 10 * 10];
+        let half_pixel = vec![vec![(2.0, 2.0), (5.5, 2.0), (5.5, 8.0), (2.0, 8.0)]];
+        Raster::new(&mut buffer, 10, 10).fill_path(&half_pixel, FillRule::NonZero, &Paint::Solid(WHITE), true);
+        assert_eq!(intensity(&buffer, 10, 3, 4), 255);
+        assert!((intensity(&buffer, 10, 5, 4) as i32 - 128).abs() <= 1);
+        assert_eq!(intensity(&buffer, 10, 6, 4), 0);
+    }
+
+    #[test]
+    fn test_linear_gradient_interpolates_and_clamps() {
+        let paint = Paint::LinearGradient {
+            start: (0.0, 0.0),
+            end: (0.0, 10.0),
+            from: [0, 0, 0],
+            to: [200, 100, 0],
+        };
+        assert_eq!(paint.color_at(3.0, -5.0), [0, 0, 0]);
+        assert_eq!(paint.color_at(3.0, 5.0), [100, 50, 0]);
+        assert_eq!(paint.color_at(3.0, 50.0), [200, 100, 0]);
+        assert_eq!(Paint::Solid([1, 2, 3]).color_at(9.0, 9.0), [1, 2, 3]);
+    }
+}
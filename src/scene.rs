@@ -0,0 +1,306 @@
+// A scene of independent curves, each with its own control points, settings and animation
+use nalgebra::Vector2;
+use super::analysis::distance_to_curve;
+use super::chaikin::Chaikin;
+use super::frame::{Frame, Overlays};
+use super::point::{Point, PointAttributes};
+use super::scheme::Scheme;
+
+// Default palette for new curves: the dark theme's curve colours
+pub const CURVE_COLORS: &[([u8; 3], &str)] = &[
+    ([0, 255, 255], "Cyan"),
+    ([255, 220, 0], "Yellow"),
+    ([255, 0, 255], "Magenta"),
+    ([255, 255, 255], "White"),
+];
+
+// Colours offered for painting control points
+pub const PAINT_COLORS: &[[u8; 3]] = &[
+    [255, 60, 60],
+    [255, 160, 0],
+    [255, 230, 0],
+    [60, 220, 60],
+    [0, 200, 255],
+    [80, 100, 255],
+    [200, 80, 255],
+    [255, 255, 255],
+];
+
+pub struct Curve {
+    pub points: Vec<(f64, f64)>,
+    // Crease and weight of each control point. May be shorter than `points`; missing
+    // entries are the defaults.
+    pub attributes: Vec<PointAttributes>,
+    pub closed: bool,
+    pub scheme: Scheme,
+    pub color: [u8; 3],
+    pub animating: bool,
+    pub chaikin: Chaikin,
+}
+
+impl Curve {
+    pub fn new(color: [u8; 3]) -> Self {
+        Self {
+            points: Vec::new(),
+            attributes: Vec::new(),
+            closed: false,
+            scheme: Scheme::Chaikin,
+            color,
+            animating: false,
+            chaikin: Chaikin::new(Vec::new()),
+        }
+    }
+
+    pub fn control_points(&self) -> Vec<Point> {
+        self.points
+            .iter()
+            .enumerate()
+            .map(|(i, &(x, y))| {
+                let attributes = self.attributes(i);
+                let color = attributes.color.unwrap_or(self.color);
+                Point { crease: attributes.crease, weight: attributes.weight, ..Point::with_color(x, y, color) }
+            })
+            .collect()
+    }
+
+    pub fn attributes(&self, index: usize) -> PointAttributes {
+        self.attributes.get(index).copied().unwrap_or_default()
+    }
+
+    pub fn attributes_mut(&mut self, index: usize) -> &mut PointAttributes {
+        if self.attributes.len() < self.points.len() {
+            self.attributes.resize(self.points.len(), PointAttributes::default());
+        }
+        &mut self.attributes[index]
+    }
+
+    // Bring the subdivision up to date with the control points and settings
+    pub fn sync(&mut self) {
+        let points = self.control_points();
+        self.chaikin.set_points(points);
+        self.chaikin.set_closed(self.closed);
+        self.chaikin.set_scheme(self.scheme);
+    }
+
+    // Points of subdivision level `level`, fine-level edits included
+    pub fn level_points(&mut self, level: usize) -> Vec<Point> {
+        self.sync();
+        self.chaikin.level(level)
+    }
+
+    // Drag a point of a finer level. The offset is kept as detail on top of the coarser
+    // levels, so it follows along when the control points move.
+    pub fn move_level_point(&mut self, level: usize, index: usize, (x, y): (f64, f64)) {
+        self.sync();
+        self.chaikin.move_level_point(level, index, Vector2::new(x, y));
+    }
+
+    // Advance this curve's animation with the shared iteration count, speed and overlays
+    pub fn step(&mut self, max_steps: usize, speed: f64, overlays: Overlays) -> Frame<'_> {
+        self.sync();
+        self.chaikin.set_max_steps(max_steps);
+        self.chaikin.set_animation_speed(speed);
+        self.chaikin.overlays = overlays;
+        self.chaikin.step()
+    }
+
+    // Distance from (x, y) to the control polygon, or f64::MAX for an empty curve
+    pub fn distance_to(&self, x: f64, y: f64) -> f64 {
+        distance_to_curve(&self.control_points(), self.closed, &Vector2::new(x, y))
+    }
+}
+
+// Editable state of a curve, without its animation
+#[derive(Clone, Debug, PartialEq)]
+pub struct CurveSnapshot {
+    pub points: Vec<(f64, f64)>,
+    pub attributes: Vec<PointAttributes>,
+    pub closed: bool,
+    pub scheme: Scheme,
+    pub color: [u8; 3],
+    // Fine-level offsets, as in `Chaikin::details`
+    pub details: Vec<Vec<Vector2<f64>>>,
+}
+
+// Editable state of a whole scene, as kept by the undo history
+#[derive(Clone, Debug, PartialEq)]
+pub struct SceneSnapshot {
+    pub curves: Vec<CurveSnapshot>,
+    pub selected: usize,
+}
+
+pub struct Scene {
+    pub curves: Vec<Curve>,
+    // Index of the curve that receives new points and edits; always valid
+    pub selected: usize,
+    // Colours handed out to new curves in turn; never empty
+    pub palette: Vec<[u8; 3]>,
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scene {
+    // A scene starts with one empty curve ready for points
+    pub fn new() -> Self {
+        Self {
+            curves: vec![Curve::new(CURVE_COLORS[0].0)],
+            selected: 0,
+            palette: CURVE_COLORS.iter().map(|&(color, _)| color).collect(),
+        }
+    }
+
+    pub fn selected(&self) -> &Curve {
+        &self.curves[self.selected]
+    }
+
+    pub fn selected_mut(&mut self) -> &mut Curve {
+        &mut self.curves[self.selected]
+    }
+
+    // Start a new curve with the selected curve's scheme and open/closed mode and the
+    // next colour, and select it. An empty selected curve is reused instead.
+    pub fn new_curve(&mut self) -> usize {
+        if self.selected().points.is_empty() {
+            return self.selected;
+        }
+
+        let color = self.palette[self.curves.len() % self.palette.len()];
+        let mut curve = Curve::new(color);
+        curve.closed = self.selected().closed;
+        curve.scheme = self.selected().scheme;
+        self.curves.push(curve);
+        self.selected = self.curves.len() - 1;
+        self.selected
+    }
+
+    // Switch to another palette, e.g. for a new theme. Curves coloured from the old
+    // palette take the colour at the same position in the new one.
+    pub fn set_palette(&mut self, palette: Vec<[u8; 3]>) {
+        if palette.is_empty() {
+            return;
+        }
+        for curve in &mut self.curves {
+            if let Some(i) = self.palette.iter().position(|&c| c == curve.color) {
+                curve.color = palette[i % palette.len()];
+            }
+        }
+        self.palette = palette;
+    }
+
+    // Select a curve. An empty curve that loses the selection is dropped.
+    pub fn select(&mut self, index: usize) {
+        if index >= self.curves.len() || index == self.selected {
+            return;
+        }
+        let previous = self.selected;
+        self.selected = index;
+        if self.curves[previous].points.is_empty() {
+            self.curves.remove(previous);
+            if index > previous {
+                self.selected -= 1;
+            }
+        }
+    }
+
+    // Topmost curve whose control polygon passes within `tolerance` of (x, y)
+    pub fn curve_at(&self, x: f64, y: f64, tolerance: f64) -> Option<usize> {
+        self.curves
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(i, curve)| (i, curve.distance_to(x, y)))
+            .filter(|&(_, dist)| dist <= tolerance)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    pub fn has_points(&self) -> bool {
+        self.curves.iter().any(|c| !c.points.is_empty())
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.curves.iter().any(|c| c.animating)
+    }
+
+    // Animate every curve that has points. Returns false when there is nothing to animate.
+    pub fn animate_all(&mut self) -> bool {
+        for curve in &mut self.curves {
+            curve.animating = !curve.points.is_empty();
+        }
+        self.has_points()
+    }
+
+    // Start or stop the selected curve only. Returns false when it has no points.
+    pub fn toggle_selected_animation(&mut self) -> bool {
+        let curve = self.selected_mut();
+        if curve.points.is_empty() {
+            return false;
+        }
+        curve.animating = !curve.animating;
+        true
+    }
+
+    // Remove every curve, leaving a single empty one
+    pub fn clear(&mut self) {
+        let mut curve = Curve::new(self.palette[0]);
+        curve.closed = self.selected().closed;
+        curve.scheme = self.selected().scheme;
+        self.curves = vec![curve];
+        self.selected = 0;
+    }
+
+    pub fn snapshot(&self) -> SceneSnapshot {
+        let curves = self
+            .curves
+            .iter()
+            .map(|c| CurveSnapshot {
+                points: c.points.clone(),
+                attributes: c.attributes.clone(),
+                closed: c.closed,
+                scheme: c.scheme,
+                color: c.color,
+                details: c.chaikin.details.clone(),
+            })
+            .collect();
+        SceneSnapshot { curves, selected: self.selected }
+    }
+
+    // Bring back a snapshot. Curves that still exist keep their animation state.
+    pub fn restore(&mut self, snapshot: SceneSnapshot) {
+        self.curves.truncate(snapshot.curves.len());
+        for (i, saved) in snapshot.curves.into_iter().enumerate() {
+            if i == self.curves.len() {
+                self.curves.push(Curve::new(saved.color));
+            }
+            let curve = &mut self.curves[i];
+            curve.points = saved.points;
+            curve.attributes = saved.attributes;
+            curve.closed = saved.closed;
+            curve.scheme = saved.scheme;
+            curve.color = saved.color;
+            // Line the subdivision up with the restored points first, so the detail
+            // isn't dropped as belonging to a different set of points
+            curve.sync();
+            curve.chaikin.set_details(saved.details);
+        }
+        if self.curves.is_empty() {
+            self.curves.push(Curve::new(self.palette[0]));
+        }
+        self.selected = snapshot.selected.min(self.curves.len() - 1);
+    }
+
+    // All control points, the selected curve's first so indices into it stay valid
+    pub fn all_points(&self) -> Vec<(f64, f64)> {
+        let mut points = self.selected().points.clone();
+        for (i, curve) in self.curves.iter().enumerate() {
+            if i != self.selected {
+                points.extend_from_slice(&curve.points);
+            }
+        }
+        points
+    }
+}
@@ -0,0 +1,173 @@
+use crate::scene::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::Overlays;
+    use crate::scheme::Scheme;
+
+    fn scene_with_two_curves() -> Scene {
+        let mut scene = Scene::new();
+        scene.selected_mut().points = vec![(0.0, 0.0), (100.0, 0.0)];
+        scene.new_curve();
+        scene.selected_mut().points = vec![(0.0, 50.0), (100.0, 50.0), (100.0, 100.0)];
+        scene
+    }
+
+    #[test]
+    fn test_new_scene_has_one_empty_selected_curve() {
+        let scene = Scene::new();
+        assert_eq!(scene.curves.len(), 1);
+        assert_eq!(scene.selected, 0);
+        assert!(!scene.has_points());
+        assert!(!scene.is_animating());
+    }
+
+    #[test]
+    fn test_new_curve_inherits_settings_and_takes_next_colour() {
+        let mut scene = Scene::new();
+        scene.selected_mut().closed = true;
+        scene.selected_mut().scheme = Scheme::FourPoint;
+
+        // An empty selected curve is reused
+        assert_eq!(scene.new_curve(), 0);
+        assert_eq!(scene.curves.len(), 1);
+
+        scene.selected_mut().points.push((1.0, 1.0));
+        assert_eq!(scene.new_curve(), 1);
+        let curve = scene.selected();
+        assert!(curve.closed && curve.points.is_empty());
+        assert_eq!(curve.scheme, Scheme::FourPoint);
+        assert_eq!(curve.color, CURVE_COLORS[1].0);
+    }
+
+    #[test]
+    fn test_select_drops_empty_curve() {
+        let mut scene = scene_with_two_curves();
+        scene.new_curve();
+        assert_eq!(scene.curves.len(), 3);
+
+        scene.select(0);
+        assert_eq!(scene.curves.len(), 2);
+        assert_eq!(scene.selected, 0);
+
+        // Out of range selections are ignored
+        scene.select(5);
+        assert_eq!(scene.selected, 0);
+    }
+
+    #[test]
+    fn test_curve_at_picks_nearest_control_polygon() {
+        let scene = scene_with_two_curves();
+        assert_eq!(scene.curve_at(50.0, 3.0, 6.0), Some(0));
+        assert_eq!(scene.curve_at(50.0, 48.0, 6.0), Some(1));
+        assert_eq!(scene.curve_at(98.0, 75.0, 6.0), Some(1));
+        assert_eq!(scene.curve_at(50.0, 25.0, 6.0), None);
+    }
+
+    #[test]
+    fn test_closed_curve_includes_closing_edge() {
+        let mut scene = scene_with_two_curves();
+        // The closing edge runs from (100, 100) back to (0, 50)
+        assert_eq!(scene.curve_at(50.0, 75.0, 2.0), None);
+        scene.selected_mut().closed = true;
+        assert_eq!(scene.curve_at(50.0, 75.0, 2.0), Some(1));
+    }
+
+    #[test]
+    fn test_animate_all_and_individually() {
+        let mut scene = scene_with_two_curves();
+        scene.new_curve();
+        assert!(scene.animate_all());
+        assert!(scene.curves[0].animating && scene.curves[1].animating);
+        // Curves without points stay still
+        assert!(!scene.curves[2].animating);
+        assert!(!scene.toggle_selected_animation());
+
+        scene.select(1);
+        assert!(scene.toggle_selected_animation());
+        assert!(scene.curves[0].animating && !scene.curves[1].animating);
+    }
+
+    #[test]
+    fn test_curves_step_independently() {
+        let mut scene = scene_with_two_curves();
+        scene.curves[1].closed = true;
+        let (first, second) = scene.curves.split_at_mut(1);
+        let open = first[0].step(3, 1.0, Overlays::default());
+        let closed = second[0].step(5, 2.0, Overlays::default());
+        assert!(!open.curve.is_empty() && !closed.curve.is_empty());
+        assert!(closed.info.closed && !open.info.closed);
+        assert_eq!(scene.curves[0].chaikin.max_steps, 3);
+        assert_eq!(scene.curves[1].chaikin.max_steps, 5);
+        assert!(scene.curves[1].chaikin.closed && !scene.curves[0].chaikin.closed);
+    }
+
+    #[test]
+    fn test_restore_brings_back_curves() {
+        let mut scene = scene_with_two_curves();
+        scene.curves[0].animating = true;
+        let snapshot = scene.snapshot();
+
+        scene.clear();
+        scene.restore(snapshot.clone());
+        assert_eq!(scene.snapshot(), snapshot);
+        assert_eq!(scene.selected, 1);
+
+        // A curve that survived keeps animating
+        scene.curves.truncate(1);
+        scene.curves[0].animating = true;
+        scene.restore(snapshot);
+        assert!(scene.curves[0].animating);
+        assert_eq!(scene.curves.len(), 2);
+    }
+
+    #[test]
+    fn test_clear_and_all_points() {
+        let mut scene = scene_with_two_curves();
+        let points = scene.all_points();
+        // The selected curve's points come first
+        assert_eq!(&points[..3], &scene.selected().points[..]);
+        assert_eq!(points.len(), 5);
+
+        scene.clear();
+        assert_eq!(scene.curves.len(), 1);
+        assert!(!scene.has_points());
+    }
+
+    #[test]
+    fn test_level_detail_is_kept_in_snapshots() {
+        let mut scene = scene_with_two_curves();
+        let before = scene.snapshot();
+        let level = scene.selected_mut().level_points(1);
+        let (x, y) = (level[1].position.x, level[1].position.y + 15.0);
+        scene.selected_mut().move_level_point(1, 1, (x, y));
+        let after = scene.snapshot();
+        assert_ne!(after, before);
+
+        scene.restore(before);
+        assert!(scene.selected().chaikin.details.is_empty());
+        scene.restore(after);
+        assert_eq!(scene.selected_mut().level_points(1)[1].position.y, y);
+    }
+
+    #[test]
+    fn test_undoing_level_edit_restarts_animation() {
+        let mut scene = scene_with_two_curves();
+        scene.selected_mut().sync();
+        let before = scene.snapshot();
+        let level = scene.selected_mut().level_points(1);
+        scene.selected_mut().move_level_point(1, 1, (level[1].position.x, level[1].position.y + 15.0));
+        // Part way through the animation, showing the edited level
+        let edited = scene.selected().chaikin.level(1);
+        let chaikin = &mut scene.selected_mut().chaikin;
+        chaikin.current_points = edited.clone();
+        chaikin.current_step = 1;
+
+        scene.restore(before);
+        let chaikin = &scene.selected().chaikin;
+        assert_eq!(chaikin.current_step, 0);
+        assert_eq!(chaikin.current_points, chaikin.original_points);
+        assert_ne!(chaikin.level(1), edited);
+    }
+}
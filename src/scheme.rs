@@ -0,0 +1,94 @@
+use super::point::Point;
+
+// Curve subdivision schemes that can stand in for Chaikin's corner cutting
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scheme {
+    // Chaikin's corner cutting (quadratic B-spline)
+    Chaikin,
+    // Lane-Riesenfeld cubic B-spline subdivision
+    CubicBSpline,
+    // Dyn-Levin-Gregory interpolating four-point scheme
+    FourPoint,
+}
+
+impl Scheme {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scheme::Chaikin => "Chaikin",
+            Scheme::CubicBSpline => "Cubic B-spline",
+            Scheme::FourPoint => "Four-point",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Scheme::Chaikin => Scheme::CubicBSpline,
+            Scheme::CubicBSpline => Scheme::FourPoint,
+            Scheme::FourPoint => Scheme::Chaikin,
+        }
+    }
+}
+
+// One step of cubic B-spline subdivision. Open curves keep their endpoints.
+pub fn cubic_bspline<const D: usize>(points: &[Point<D>], closed: bool) -> Vec<Point<D>> {
+    let n = points.len();
+    if n < 3 {
+        return points.to_vec();
+    }
+
+    let mut result = Vec::with_capacity(2 * n);
+    if closed {
+        for i in 0..n {
+            let prev = &points[(i + n - 1) % n];
+            let curr = &points[i];
+            let next = &points[(i + 1) % n];
+            result.push(Point::combine(&[(0.125, prev), (0.75, curr), (0.125, next)]));
+            result.push(Point::combine(&[(0.5, curr), (0.5, next)]));
+        }
+    } else {
+        result.push(points[0].clone());
+        for i in 0..n - 1 {
+            if i > 0 {
+                result.push(Point::combine(&[(0.125, &points[i - 1]), (0.75, &points[i]), (0.125, &points[i + 1])]));
+            }
+            result.push(Point::combine(&[(0.5, &points[i]), (0.5, &points[i + 1])]));
+        }
+        result.push(points[n - 1].clone());
+    }
+    result
+}
+
+// One step of the four-point scheme: existing points are kept and a new point is inserted
+// on every edge. Open curves mirror the end points to get the missing neighbours.
+pub fn four_point<const D: usize>(points: &[Point<D>], closed: bool) -> Vec<Point<D>> {
+    let n = points.len();
+    if n < 3 {
+        return points.to_vec();
+    }
+
+    let get = |i: isize| -> Point<D> {
+        if closed {
+            return points[i.rem_euclid(n as isize) as usize].clone();
+        }
+        if i < 0 {
+            Point::combine(&[(2.0, &points[0]), (-1.0, &points[1])])
+        } else if i as usize >= n {
+            Point::combine(&[(2.0, &points[n - 1]), (-1.0, &points[n - 2])])
+        } else {
+            points[i as usize].clone()
+        }
+    };
+
+    let edges = if closed { n } else { n - 1 };
+    let mut result = Vec::with_capacity(2 * n);
+    for i in 0..edges {
+        let i = i as isize;
+        result.push(points[i as usize].clone());
+        let (a, b, c, d) = (get(i - 1), get(i), get(i + 1), get(i + 2));
+        result.push(Point::combine(&[(-1.0 / 16.0, &a), (9.0 / 16.0, &b), (9.0 / 16.0, &c), (-1.0 / 16.0, &d)]));
+    }
+    if !closed {
+        result.push(points[n - 1].clone());
+    }
+    result
+}
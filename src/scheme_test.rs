@@ -0,0 +1,114 @@
+use crate::scheme::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point::Point;
+    use nalgebra::{Vector2, Vector3};
+
+    fn point(x: f64, y: f64) -> Point {
+        Point::new(x, y)
+    }
+
+    fn square() -> Vec<Point> {
+        vec![point(0.0, 0.0), point(8.0, 0.0), point(8.0, 8.0), point(0.0, 8.0)]
+    }
+
+    #[test]
+    fn test_scheme_cycle_and_names() {
+        let mut scheme = Scheme::Chaikin;
+        let mut names = Vec::new();
+        for _ in 0..3 {
+            names.push(scheme.name());
+            scheme = scheme.next();
+        }
+        assert_eq!(scheme, Scheme::Chaikin);
+        assert_eq!(names, vec!["Chaikin", "Cubic B-spline", "Four-point"]);
+    }
+
+    #[test]
+    fn test_cubic_bspline_open_keeps_endpoints() {
+        let input = vec![point(0.0, 0.0), point(8.0, 8.0), point(16.0, 0.0)];
+        let output = cubic_bspline(&input, false);
+        assert_eq!(output.len(), 5);
+        assert_eq!(output[0], input[0]);
+        assert_eq!(output[4], input[2]);
+        // Edge midpoint and smoothed vertex
+        assert_eq!(output[1].position, Vector2::new(4.0, 4.0));
+        assert_eq!(output[2].position, Vector2::new(8.0, 6.0));
+    }
+
+    #[test]
+    fn test_cubic_bspline_closed_doubles_points() {
+        let output = cubic_bspline(&square(), true);
+        assert_eq!(output.len(), 8);
+        assert_eq!(output[0].position, Vector2::new(1.0, 1.0));
+        assert_eq!(output[1].position, Vector2::new(4.0, 0.0));
+    }
+
+    #[test]
+    fn test_four_point_interpolates_original_points() {
+        let input = square();
+        let output = four_point(&input, true);
+        assert_eq!(output.len(), 8);
+        for (i, p) in input.iter().enumerate() {
+            assert_eq!(&output[2 * i], p);
+        }
+        // Inserted points bulge outwards: y = (-8 + 9 * 0 + 9 * 0 - 8) / 16 on the top edge
+        assert_eq!(output[1].position, Vector2::new(4.0, -1.0));
+    }
+
+    #[test]
+    fn test_four_point_open_keeps_endpoints() {
+        let input = vec![point(0.0, 0.0), point(4.0, 0.0), point(8.0, 0.0)];
+        let output = four_point(&input, false);
+        assert_eq!(output.len(), 5);
+        assert_eq!(output[0], input[0]);
+        assert_eq!(output[4], input[2]);
+        // A straight line stays straight with mirrored end neighbours
+        for p in &output {
+            assert_eq!(p.position.y, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_short_inputs_are_returned_unchanged() {
+        let input = vec![point(1.0, 2.0), point(3.0, 4.0)];
+        assert_eq!(cubic_bspline(&input, false), input);
+        assert_eq!(four_point(&input, true), input);
+    }
+
+    #[test]
+    fn test_schemes_carry_colors() {
+        let points = vec![
+            Point::with_color(0.0, 0.0, [0, 0, 0]),
+            Point::with_color(8.0, 0.0, [80, 80, 80]),
+            Point::with_color(16.0, 0.0, [160, 160, 160]),
+        ];
+        let bspline = cubic_bspline(&points, false);
+        assert_eq!(bspline[1].color, [40, 40, 40]);
+        assert_eq!(bspline[2].color, [80, 80, 80]);
+
+        // The mirrored point before the start can't go below black, so the first new
+        // point comes out slightly darker than the edge midpoint
+        let four = four_point(&points, false);
+        assert_eq!(four[1].color, [35, 35, 35]);
+        assert_eq!(four[3].color, [120, 120, 120]);
+        assert_eq!(four[2].color, points[1].color);
+    }
+
+    #[test]
+    fn test_schemes_work_in_three_dimensions() {
+        let points: Vec<Point<3>> = [[0.0, 0.0, 0.0], [8.0, 0.0, 8.0], [16.0, 0.0, 0.0], [24.0, 0.0, 8.0]]
+            .iter()
+            .map(|&[x, y, z]| Point::from_vector(Vector3::new(x, y, z)))
+            .collect();
+        let bspline = cubic_bspline(&points, true);
+        assert_eq!(bspline.len(), 8);
+        assert_eq!(bspline[1].position, Vector3::new(4.0, 0.0, 4.0));
+        // The interpolating scheme keeps the original points
+        let four = four_point(&points, false);
+        assert_eq!(four[2], points[1]);
+        assert!(four.iter().all(|p| p.position.y == 0.0));
+    }
+}
@@ -0,0 +1,211 @@
+// Selected control points of the selected curve and the group transforms applied to them
+use std::collections::BTreeSet;
+
+// Gap between the selection's bounding box and its handles
+const HANDLE_MARGIN: f64 = 8.0;
+// Distance of the rotate handle above the bounding box
+const ROTATE_HANDLE_OFFSET: f64 = 24.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Axis {
+    // Flip left to right
+    Horizontal,
+    // Flip top to bottom
+    Vertical,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Handle {
+    Scale,
+    Rotate,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Selection {
+    indices: BTreeSet<usize>,
+}
+
+impl Selection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.indices.contains(&index)
+    }
+
+    // Selected indices in ascending order
+    pub fn indices(&self) -> Vec<usize> {
+        self.indices.iter().copied().collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.indices.clear();
+    }
+
+    pub fn select_only(&mut self, index: usize) {
+        self.indices.clear();
+        self.indices.insert(index);
+    }
+
+    pub fn toggle(&mut self, index: usize) {
+        if !self.indices.remove(&index) {
+            self.indices.insert(index);
+        }
+    }
+
+    pub fn select_all(&mut self, count: usize) {
+        self.indices = (0..count).collect();
+    }
+
+    // Select the points inside the rectangle spanned by two corners. `additive` keeps
+    // the current selection, as with Shift held.
+    pub fn select_in_rect(&mut self, points: &[(f64, f64)], a: (f64, f64), b: (f64, f64), additive: bool) {
+        if !additive {
+            self.indices.clear();
+        }
+        let (min_x, max_x) = (a.0.min(b.0), a.0.max(b.0));
+        let (min_y, max_y) = (a.1.min(b.1), a.1.max(b.1));
+        for (i, &(x, y)) in points.iter().enumerate() {
+            if (min_x..=max_x).contains(&x) && (min_y..=max_y).contains(&y) {
+                self.indices.insert(i);
+            }
+        }
+    }
+
+    // Drop indices that no longer refer to a point, e.g. after an undo
+    pub fn retain_valid(&mut self, count: usize) {
+        self.indices.retain(|&i| i < count);
+    }
+
+    // Bounding box of the selected points as (min_x, min_y, max_x, max_y)
+    pub fn bounds(&self, points: &[(f64, f64)]) -> Option<(f64, f64, f64, f64)> {
+        let mut selected = self.indices.iter().filter_map(|&i| points.get(i));
+        let &(x, y) = selected.next()?;
+        Some(selected.fold((x, y, x, y), |(min_x, min_y, max_x, max_y), &(x, y)| {
+            (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+        }))
+    }
+
+    // Centre of the bounding box, used as the pivot for rotating, scaling and mirroring
+    pub fn pivot(&self, points: &[(f64, f64)]) -> Option<(f64, f64)> {
+        self.bounds(points)
+            .map(|(min_x, min_y, max_x, max_y)| ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0))
+    }
+
+    // Apply `f` to every selected point
+    fn apply<F: Fn((f64, f64)) -> (f64, f64)>(&self, points: &mut [(f64, f64)], f: F) {
+        for &i in &self.indices {
+            if let Some(point) = points.get_mut(i) {
+                *point = f(*point);
+            }
+        }
+    }
+
+    pub fn translate(&self, points: &mut [(f64, f64)], dx: f64, dy: f64) {
+        self.apply(points, |(x, y)| (x + dx, y + dy));
+    }
+
+    // Rotate by `angle` radians, clockwise on screen since y points down
+    pub fn rotate(&self, points: &mut [(f64, f64)], angle: f64, pivot: (f64, f64)) {
+        let (sin, cos) = angle.sin_cos();
+        self.apply(points, |(x, y)| {
+            let (dx, dy) = (x - pivot.0, y - pivot.1);
+            (pivot.0 + dx * cos - dy * sin, pivot.1 + dx * sin + dy * cos)
+        });
+    }
+
+    pub fn scale(&self, points: &mut [(f64, f64)], factor: f64, pivot: (f64, f64)) {
+        self.apply(points, |(x, y)| (pivot.0 + (x - pivot.0) * factor, pivot.1 + (y - pivot.1) * factor));
+    }
+
+    pub fn mirror(&self, points: &mut [(f64, f64)], axis: Axis, pivot: (f64, f64)) {
+        self.apply(points, |(x, y)| match axis {
+            Axis::Horizontal => (2.0 * pivot.0 - x, y),
+            Axis::Vertical => (x, 2.0 * pivot.1 - y),
+        });
+    }
+
+    // Remove the entries at selected indices from a list that runs parallel to the points
+    pub fn remove_selected<T>(&self, items: &mut Vec<T>) {
+        let mut index = 0;
+        items.retain(|_| {
+            let keep = !self.indices.contains(&index);
+            index += 1;
+            keep
+        });
+    }
+
+    // Remove the selected points and clear the selection
+    pub fn delete(&mut self, points: &mut Vec<(f64, f64)>) {
+        self.remove_selected(points);
+        self.indices.clear();
+    }
+}
+
+// Handle positions around a selection's bounding box: a scale handle on every corner
+// and a rotate handle above the top edge
+pub fn handles(bounds: (f64, f64, f64, f64)) -> Vec<(Handle, (f64, f64))> {
+    let (min_x, min_y, max_x, max_y) = bounds;
+    let (left, top) = (min_x - HANDLE_MARGIN, min_y - HANDLE_MARGIN);
+    let (right, bottom) = (max_x + HANDLE_MARGIN, max_y + HANDLE_MARGIN);
+    vec![
+        (Handle::Scale, (left, top)),
+        (Handle::Scale, (right, top)),
+        (Handle::Scale, (right, bottom)),
+        (Handle::Scale, (left, bottom)),
+        (Handle::Rotate, ((min_x + max_x) / 2.0, top - ROTATE_HANDLE_OFFSET)),
+    ]
+}
+
+pub fn handle_at(bounds: (f64, f64, f64, f64), pos: (f64, f64), radius: f64) -> Option<Handle> {
+    handles(bounds)
+        .into_iter()
+        .find(|(_, (x, y))| (x - pos.0).powi(2) + (y - pos.1).powi(2) <= radius * radius)
+        .map(|(handle, _)| handle)
+}
+
+// A handle being dragged. The transform is always recomputed from the points as they
+// were when the drag started, so it doesn't accumulate rounding errors.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransformDrag {
+    pub handle: Handle,
+    pub pivot: (f64, f64),
+    pub start: (f64, f64),
+    pub original: Vec<(f64, f64)>,
+}
+
+impl TransformDrag {
+    pub fn new(handle: Handle, pivot: (f64, f64), start: (f64, f64), points: &[(f64, f64)]) -> Self {
+        Self { handle, pivot, start, original: points.to_vec() }
+    }
+
+    // Points of the curve with the selection transformed for the cursor at `pos`
+    pub fn apply(&self, selection: &Selection, pos: (f64, f64)) -> Vec<(f64, f64)> {
+        let mut points = self.original.clone();
+        let (sx, sy) = (self.start.0 - self.pivot.0, self.start.1 - self.pivot.1);
+        let (px, py) = (pos.0 - self.pivot.0, pos.1 - self.pivot.1);
+        match self.handle {
+            Handle::Scale => {
+                let start_dist = (sx * sx + sy * sy).sqrt();
+                if start_dist > 0.0 {
+                    let factor = (px * px + py * py).sqrt() / start_dist;
+                    selection.scale(&mut points, factor, self.pivot);
+                }
+            }
+            Handle::Rotate => {
+                let angle = py.atan2(px) - sy.atan2(sx);
+                selection.rotate(&mut points, angle, self.pivot);
+            }
+        }
+        points
+    }
+}
@@ -0,0 +1,127 @@
+use crate::selection::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> Vec<(f64, f64)> {
+        vec![(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]
+    }
+
+    fn assert_close(actual: &[(f64, f64)], expected: &[(f64, f64)]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a.0 - e.0).abs() < 1e-9 && (a.1 - e.1).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_click_and_toggle() {
+        let mut selection = Selection::new();
+        selection.select_only(2);
+        selection.toggle(0);
+        assert_eq!(selection.indices(), vec![0, 2]);
+        selection.toggle(2);
+        assert_eq!(selection.indices(), vec![0]);
+        selection.select_only(3);
+        assert_eq!(selection.indices(), vec![3]);
+    }
+
+    #[test]
+    fn test_select_in_rect_replaces_or_adds() {
+        let points = square();
+        let mut selection = Selection::new();
+        // Corners may be given in any order
+        selection.select_in_rect(&points, (12.0, -1.0), (5.0, 11.0), false);
+        assert_eq!(selection.indices(), vec![1, 2]);
+        selection.select_in_rect(&points, (-1.0, -1.0), (1.0, 1.0), true);
+        assert_eq!(selection.indices(), vec![0, 1, 2]);
+        selection.select_in_rect(&points, (-1.0, -1.0), (1.0, 1.0), false);
+        assert_eq!(selection.indices(), vec![0]);
+    }
+
+    #[test]
+    fn test_bounds_and_pivot() {
+        let points = square();
+        let mut selection = Selection::new();
+        assert_eq!(selection.bounds(&points), None);
+        selection.select_all(points.len());
+        assert_eq!(selection.bounds(&points), Some((0.0, 0.0, 10.0, 10.0)));
+        assert_eq!(selection.pivot(&points), Some((5.0, 5.0)));
+    }
+
+    #[test]
+    fn test_transforms_only_touch_selected_points() {
+        let mut points = square();
+        let mut selection = Selection::new();
+        selection.select_only(1);
+        selection.toggle(2);
+
+        selection.translate(&mut points, 5.0, 0.0);
+        assert_close(&points, &[(0.0, 0.0), (15.0, 0.0), (15.0, 10.0), (0.0, 10.0)]);
+
+        selection.mirror(&mut points, Axis::Horizontal, (0.0, 0.0));
+        assert_close(&points, &[(0.0, 0.0), (-15.0, 0.0), (-15.0, 10.0), (0.0, 10.0)]);
+
+        selection.mirror(&mut points, Axis::Vertical, (0.0, 5.0));
+        assert_close(&points, &[(0.0, 0.0), (-15.0, 10.0), (-15.0, 0.0), (0.0, 10.0)]);
+    }
+
+    #[test]
+    fn test_rotate_and_scale_about_pivot() {
+        let mut points = square();
+        let mut selection = Selection::new();
+        selection.select_all(points.len());
+
+        selection.rotate(&mut points, std::f64::consts::FRAC_PI_2, (5.0, 5.0));
+        assert_close(&points, &[(10.0, 0.0), (10.0, 10.0), (0.0, 10.0), (0.0, 0.0)]);
+
+        selection.scale(&mut points, 2.0, (5.0, 5.0));
+        assert_close(&points, &[(15.0, -5.0), (15.0, 15.0), (-5.0, 15.0), (-5.0, -5.0)]);
+    }
+
+    #[test]
+    fn test_delete_and_retain_valid() {
+        let mut points = square();
+        let mut selection = Selection::new();
+        selection.select_only(1);
+        selection.toggle(3);
+        selection.delete(&mut points);
+        assert_eq!(points, vec![(0.0, 0.0), (10.0, 10.0)]);
+        assert!(selection.is_empty());
+
+        selection.select_all(4);
+        selection.retain_valid(2);
+        assert_eq!(selection.indices(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_handles_surround_bounds() {
+        let bounds = (0.0, 0.0, 10.0, 10.0);
+        let list = handles(bounds);
+        assert_eq!(list.iter().filter(|(h, _)| *h == Handle::Scale).count(), 4);
+
+        let (_, rotate) = list.iter().find(|(h, _)| *h == Handle::Rotate).copied().unwrap();
+        assert_eq!(rotate.0, 5.0);
+        assert!(rotate.1 < 0.0);
+        assert_eq!(handle_at(bounds, rotate, 3.0), Some(Handle::Rotate));
+        assert_eq!(handle_at(bounds, (5.0, 5.0), 3.0), None);
+    }
+
+    #[test]
+    fn test_transform_drag_scales_and_rotates_from_original() {
+        let points = square();
+        let mut selection = Selection::new();
+        selection.select_all(points.len());
+
+        let drag = TransformDrag::new(Handle::Scale, (5.0, 5.0), (15.0, 5.0), &points);
+        let scaled = drag.apply(&selection, (25.0, 5.0));
+        assert_close(&scaled, &[(-5.0, -5.0), (15.0, -5.0), (15.0, 15.0), (-5.0, 15.0)]);
+        // Returning to the start gives back the original points
+        assert_close(&drag.apply(&selection, (15.0, 5.0)), &points);
+
+        let drag = TransformDrag::new(Handle::Rotate, (5.0, 5.0), (5.0, -5.0), &points);
+        let rotated = drag.apply(&selection, (15.0, 5.0));
+        assert_close(&rotated, &[(10.0, 0.0), (10.0, 10.0), (0.0, 10.0), (0.0, 0.0)]);
+    }
+}
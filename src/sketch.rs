@@ -0,0 +1,93 @@
+// Freehand strokes turned into control polygons. A raw stroke has a point for every few
+// pixels the mouse moved; simplifying it keeps only the points that carry its shape.
+
+use nalgebra::Vector2;
+use super::analysis::distance_to_segment;
+
+// Limits of the simplification tolerance, in pixels
+pub const MIN_TOLERANCE: f64 = 0.5;
+pub const MAX_TOLERANCE: f64 = 20.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Simplifier {
+    // Ramer-Douglas-Peucker: keep the point farthest from the chord, then recurse
+    DouglasPeucker,
+    // Visvalingam-Whyatt: drop the point spanning the smallest triangle, repeatedly
+    Visvalingam,
+}
+
+impl Simplifier {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Simplifier::DouglasPeucker => "Douglas-Peucker",
+            Simplifier::Visvalingam => "Visvalingam",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Simplifier::DouglasPeucker => Simplifier::Visvalingam,
+            Simplifier::Visvalingam => Simplifier::DouglasPeucker,
+        }
+    }
+
+    // The stroke with the points it can spare removed. The ends are always kept.
+    pub fn simplify(self, points: &[(f64, f64)], tolerance: f64) -> Vec<(f64, f64)> {
+        match self {
+            Simplifier::DouglasPeucker => douglas_peucker(points, tolerance),
+            Simplifier::Visvalingam => visvalingam(points, tolerance),
+        }
+    }
+}
+
+// Keep every point that lies more than `tolerance` from the chord of the stretch it is in
+pub fn douglas_peucker(points: &[(f64, f64)], tolerance: f64) -> Vec<(f64, f64)> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    // Stretches still to check, as (first, last) index pairs
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = stack.pop() {
+        let farthest = (first + 1..last)
+            .map(|i| (i, distance_to_segment(&vector(points[first]), &vector(points[last]), &vector(points[i]))))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((i, distance)) = farthest {
+            if distance > tolerance {
+                keep[i] = true;
+                stack.push((first, i));
+                stack.push((i, last));
+            }
+        }
+    }
+    points.iter().zip(keep).filter(|(_, k)| *k).map(|(&p, _)| p).collect()
+}
+
+// Drop points one at a time, always the one whose triangle with its neighbours is
+// smallest, until every triangle left is at least `tolerance` squared in area
+pub fn visvalingam(points: &[(f64, f64)], tolerance: f64) -> Vec<(f64, f64)> {
+    let min_area = tolerance * tolerance;
+    let mut kept = points.to_vec();
+    while kept.len() > 2 {
+        let smallest = (1..kept.len() - 1)
+            .map(|i| (i, triangle_area(kept[i - 1], kept[i], kept[i + 1])))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        match smallest {
+            Some((i, area)) if area < min_area => {
+                kept.remove(i);
+            }
+            _ => break,
+        }
+    }
+    kept
+}
+
+fn vector((x, y): (f64, f64)) -> Vector2<f64> {
+    Vector2::new(x, y)
+}
+
+fn triangle_area(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    ((b.0 - a.0) * (c.1 - a.1) - (c.0 - a.0) * (b.1 - a.1)).abs() / 2.0
+}
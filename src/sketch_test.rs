@@ -0,0 +1,67 @@
+use crate::sketch::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A wobbly horizontal line with a sharp bump in the middle
+    fn stroke() -> Vec<(f64, f64)> {
+        let mut points: Vec<(f64, f64)> = (0..=50).map(|i| (i as f64 * 2.0, if i % 2 == 0 { 0.4 } else { -0.4 })).collect();
+        points[25].1 = 30.0;
+        points
+    }
+
+    #[test]
+    fn test_douglas_peucker_keeps_ends_and_corners() {
+        let simplified = douglas_peucker(&stroke(), 1.0);
+        assert_eq!(simplified, vec![(0.0, 0.4), (48.0, 0.4), (50.0, 30.0), (52.0, 0.4), (100.0, 0.4)]);
+        // A tolerance bigger than the bump leaves only the ends
+        assert_eq!(douglas_peucker(&stroke(), 40.0), vec![(0.0, 0.4), (100.0, 0.4)]);
+    }
+
+    #[test]
+    fn test_douglas_peucker_stays_within_tolerance() {
+        let points: Vec<(f64, f64)> = (0..200).map(|i| i as f64 / 20.0).map(|t| (t * 30.0, 50.0 * t.sin())).collect();
+        for tolerance in [0.5, 2.0, 8.0] {
+            let simplified = douglas_peucker(&points, tolerance);
+            assert!(simplified.len() < points.len());
+            // Every dropped point is close to the kept polyline
+            for &p in &points {
+                let distance = simplified
+                    .windows(2)
+                    .map(|w| {
+                        let (a, b) = (w[0], w[1]);
+                        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+                        let t = (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / (dx * dx + dy * dy)).clamp(0.0, 1.0);
+                        (a.0 + dx * t - p.0).hypot(a.1 + dy * t - p.1)
+                    })
+                    .fold(f64::MAX, f64::min);
+                assert!(distance <= tolerance + 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_visvalingam_drops_small_triangles_first() {
+        let simplified = visvalingam(&stroke(), 2.0);
+        assert_eq!(simplified.first(), Some(&(0.0, 0.4)));
+        assert_eq!(simplified.last(), Some(&(100.0, 0.4)));
+        assert!(simplified.contains(&(50.0, 30.0)));
+        assert!(simplified.len() < 15, "{:?}", simplified);
+        // A zero tolerance drops nothing but collinear points
+        let line = vec![(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (2.0, 1.0)];
+        assert_eq!(visvalingam(&line, 0.0), line);
+        assert_eq!(visvalingam(&line, 0.1), vec![(0.0, 0.0), (2.0, 0.0), (2.0, 1.0)]);
+    }
+
+    #[test]
+    fn test_short_strokes_are_kept() {
+        let two = vec![(0.0, 0.0), (5.0, 5.0)];
+        for simplifier in [Simplifier::DouglasPeucker, Simplifier::Visvalingam] {
+            assert_eq!(simplifier.simplify(&two, 10.0), two);
+            assert!(simplifier.simplify(&[], 1.0).is_empty());
+        }
+        assert_eq!(Simplifier::DouglasPeucker.next(), Simplifier::Visvalingam);
+        assert_eq!(Simplifier::Visvalingam.next().name(), "Douglas-Peucker");
+    }
+}
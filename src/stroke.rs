@@ -0,0 +1,277 @@
+// Polygon stroker: turns a polyline into filled outlines with joins and caps.
+// Every outline is emitted with a positive signed area so the pieces can be filled
+// together with the non-zero rule to get their union.
+
+use std::f64::consts::PI;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StrokeStyle {
+    pub width: f64,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    pub miter_limit: f64,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl StrokeStyle {
+    pub fn new(width: f64) -> Self {
+        Self {
+            width,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+        }
+    }
+
+    // A one pixel butt-capped stroke is drawn as a plain line
+    pub fn is_hairline(&self) -> bool {
+        self.width <= 1.0
+    }
+
+    pub fn increase_width(&mut self) {
+        self.width = (self.width + 1.0).min(32.0);
+    }
+
+    pub fn decrease_width(&mut self) {
+        self.width = (self.width - 1.0).max(1.0);
+    }
+
+    pub fn cycle_join(&mut self) {
+        self.join = match self.join {
+            LineJoin::Miter => LineJoin::Round,
+            LineJoin::Round => LineJoin::Bevel,
+            LineJoin::Bevel => LineJoin::Miter,
+        };
+    }
+
+    pub fn cycle_cap(&mut self) {
+        self.cap = match self.cap {
+            LineCap::Butt => LineCap::Round,
+            LineCap::Round => LineCap::Square,
+            LineCap::Square => LineCap::Butt,
+        };
+    }
+}
+
+type Vec2 = (f64, f64);
+
+fn sub(a: Vec2, b: Vec2) -> Vec2 {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn add(a: Vec2, b: Vec2) -> Vec2 {
+    (a.0 + b.0, a.1 + b.1)
+}
+
+fn scale(a: Vec2, s: f64) -> Vec2 {
+    (a.0 * s, a.1 * s)
+}
+
+fn cross(a: Vec2, b: Vec2) -> f64 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+fn normalize(a: Vec2) -> Vec2 {
+    let len = (a.0 * a.0 + a.1 * a.1).sqrt();
+    (a.0 / len, a.1 / len)
+}
+
+// Left-hand normal of a unit direction
+fn normal(dir: Vec2) -> Vec2 {
+    (-dir.1, dir.0)
+}
+
+pub fn signed_area(polygon: &[Vec2]) -> f64 {
+    let mut area = 0.0;
+    for i in 0..polygon.len() {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % polygon.len()];
+        area += cross(a, b);
+    }
+    area * 0.5
+}
+
+fn oriented(mut polygon: Vec<Vec2>) -> Vec<Vec2> {
+    if signed_area(&polygon) < 0.0 {
+        polygon.reverse();
+    }
+    polygon
+}
+
+// Number of segments used to approximate an arc of `angle` radians
+fn arc_segments(radius: f64, angle: f64) -> usize {
+    let per_radian = (radius / 2.0).sqrt().max(2.0);
+    ((angle.abs() * per_radian).ceil() as usize).max(2)
+}
+
+// Pie slice around `center` sweeping from `from` to `to` (both offsets of length `radius`)
+fn arc_fan(center: Vec2, from: Vec2, to: Vec2, radius: f64) -> Vec<Vec2> {
+    let start = from.1.atan2(from.0);
+    let mut sweep = to.1.atan2(to.0) - start;
+    while sweep > PI {
+        sweep -= 2.0 * PI;
+    }
+    while sweep < -PI {
+        sweep += 2.0 * PI;
+    }
+
+    let steps = arc_segments(radius, sweep);
+    let mut fan = vec![center];
+    for i in 0..=steps {
+        let angle = start + sweep * i as f64 / steps as f64;
+        fan.push((center.0 + radius * angle.cos(), center.1 + radius * angle.sin()));
+    }
+    fan
+}
+
+fn circle(center: Vec2, radius: f64) -> Vec<Vec2> {
+    let steps = arc_segments(radius, 2.0 * PI);
+    (0..steps)
+        .map(|i| {
+            let angle = 2.0 * PI * i as f64 / steps as f64;
+            (center.0 + radius * angle.cos(), center.1 + radius * angle.sin())
+        })
+        .collect()
+}
+
+fn join(vertex: Vec2, dir_in: Vec2, dir_out: Vec2, half_width: f64, style: &StrokeStyle) -> Option<Vec<Vec2>> {
+    let turn = cross(dir_in, dir_out);
+    if turn.abs() < 1e-12 && dir_in.0 * dir_out.0 + dir_in.1 * dir_out.1 > 0.0 {
+        // Straight continuation, the segment bodies already meet
+        return None;
+    }
+
+    // The gap opens on the side opposite to the turn
+    let side = if turn > 0.0 { -1.0 } else { 1.0 };
+    let n_in = scale(normal(dir_in), half_width * side);
+    let n_out = scale(normal(dir_out), half_width * side);
+    let a = add(vertex, n_in);
+    let b = add(vertex, n_out);
+
+    let piece = match style.join {
+        LineJoin::Bevel => vec![vertex, a, b],
+        LineJoin::Round => arc_fan(vertex, n_in, n_out, half_width),
+        LineJoin::Miter => {
+            let bisector = add(n_in, n_out);
+            let bisector_len = (bisector.0 * bisector.0 + bisector.1 * bisector.1).sqrt();
+            // Miter length over stroke width is 1 / sin(phi / 2) for the interior angle
+            // phi, and sin(phi / 2) is the cosine of half the angle between the normals
+            let cos_half = bisector_len / (2.0 * half_width);
+            if cos_half < 1e-6 || 1.0 / cos_half > style.miter_limit {
+                vec![vertex, a, b]
+            } else {
+                let tip = add(vertex, scale(normalize(bisector), half_width / cos_half));
+                vec![vertex, a, tip, b]
+            }
+        }
+    };
+    Some(oriented(piece))
+}
+
+fn cap(end: Vec2, dir: Vec2, half_width: f64, cap: LineCap) -> Option<Vec<Vec2>> {
+    let n = scale(normal(dir), half_width);
+    match cap {
+        LineCap::Butt => None,
+        LineCap::Round => Some(oriented(circle(end, half_width))),
+        LineCap::Square => {
+            let ext = scale(dir, half_width);
+            Some(oriented(vec![
+                add(end, n),
+                add(add(end, n), ext),
+                add(sub(end, n), ext),
+                sub(end, n),
+            ]))
+        }
+    }
+}
+
+// Outline a polyline with the given style. `closed` joins the last point back to the
+// first instead of capping the ends. Returns convex pieces to be filled with the non-zero
+// winding rule.
+pub fn stroke_polyline(points: &[Vec2], style: &StrokeStyle, closed: bool) -> Vec<Vec<Vec2>> {
+    let mut pts: Vec<Vec2> = Vec::with_capacity(points.len());
+    for &p in points {
+        if pts.last().is_none_or(|&last: &Vec2| (last.0 - p.0).abs() > 1e-9 || (last.1 - p.1).abs() > 1e-9) {
+            pts.push(p);
+        }
+    }
+    if closed && pts.len() > 2 && pts.first() == pts.last() {
+        pts.pop();
+    }
+
+    let half_width = style.width / 2.0;
+    let mut pieces = Vec::new();
+
+    if pts.len() < 2 {
+        // A lone point only shows up with round or square caps
+        if let Some(&p) = pts.first() {
+            match style.cap {
+                LineCap::Butt => {}
+                LineCap::Round => pieces.push(oriented(circle(p, half_width))),
+                LineCap::Square => pieces.push(oriented(vec![
+                    (p.0 - half_width, p.1 - half_width),
+                    (p.0 + half_width, p.1 - half_width),
+                    (p.0 + half_width, p.1 + half_width),
+                    (p.0 - half_width, p.1 + half_width),
+                ])),
+            }
+        }
+        return pieces;
+    }
+
+    let closed = closed && pts.len() > 2;
+    let segment_count = if closed { pts.len() } else { pts.len() - 1 };
+    let dirs: Vec<Vec2> = (0..segment_count)
+        .map(|i| normalize(sub(pts[(i + 1) % pts.len()], pts[i])))
+        .collect();
+
+    // Segment bodies
+    for (i, &dir) in dirs.iter().enumerate() {
+        let p0 = pts[i];
+        let p1 = pts[(i + 1) % pts.len()];
+        let n = scale(normal(dir), half_width);
+        pieces.push(oriented(vec![add(p0, n), add(p1, n), sub(p1, n), sub(p0, n)]));
+    }
+
+    // Joins between consecutive segments
+    let join_range = if closed { 0..segment_count } else { 1..segment_count };
+    for i in join_range {
+        let dir_in = dirs[(i + segment_count - 1) % segment_count];
+        let dir_out = dirs[i];
+        if let Some(piece) = join(pts[i], dir_in, dir_out, half_width, style) {
+            pieces.push(piece);
+        }
+    }
+
+    // Caps
+    if !closed {
+        let start_dir = scale(dirs[0], -1.0);
+        if let Some(piece) = cap(pts[0], start_dir, half_width, style.cap) {
+            pieces.push(piece);
+        }
+        if let Some(piece) = cap(pts[pts.len() - 1], dirs[segment_count - 1], half_width, style.cap) {
+            pieces.push(piece);
+        }
+    }
+
+    pieces
+}
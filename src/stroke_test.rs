@@ -0,0 +1,140 @@
+use crate::stroke::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raster::Raster;
+
+    fn bounds(pieces: &[Vec<(f64, f64)>]) -> (f64, f64, f64, f64) {
+        let mut b = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+        for &(x, y) in pieces.iter().flatten() {
+            b = (b.0.min(x), b.1.min(y), b.2.max(x), b.3.max(y));
+        }
+        b
+    }
+
+    fn style(width: f64, join: LineJoin, cap: LineCap) -> StrokeStyle {
+        StrokeStyle { width, join, cap, miter_limit: 4.0 }
+    }
+
+    #[test]
+    fn test_butt_segment_is_single_rectangle() {
+        let pieces = stroke_polyline(&[(0.0, 0.0), (10.0, 0.0)], &style(4.0, LineJoin::Miter, LineCap::Butt), false);
+        assert_eq!(pieces.len(), 1);
+        assert_eq!(bounds(&pieces), (0.0, -2.0, 10.0, 2.0));
+        assert!((signed_area(&pieces[0]) - 40.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_square_cap_extends_by_half_width() {
+        let pieces = stroke_polyline(&[(0.0, 0.0), (10.0, 0.0)], &style(4.0, LineJoin::Miter, LineCap::Square), false);
+        let (min_x, _, max_x, _) = bounds(&pieces);
+        assert!((min_x + 2.0).abs() < 1e-9);
+        assert!((max_x - 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_round_cap_adds_circles() {
+        let pieces = stroke_polyline(&[(0.0, 0.0), (10.0, 0.0)], &style(4.0, LineJoin::Miter, LineCap::Round), false);
+        assert_eq!(pieces.len(), 3);
+        // Circles are polygonal, so the extent is only approximately the radius
+        let (min_x, _, max_x, _) = bounds(&pieces);
+        assert!((min_x + 2.0).abs() < 0.1);
+        assert!((max_x - 12.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_all_pieces_have_positive_area() {
+        let points = [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 3.0)];
+        for join in [LineJoin::Miter, LineJoin::Round, LineJoin::Bevel] {
+            for cap in [LineCap::Butt, LineCap::Round, LineCap::Square] {
+                for piece in stroke_polyline(&points, &style(6.0, join, cap), false) {
+                    assert!(signed_area(&piece) >= 0.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_right_angle_miter_reaches_outer_corner() {
+        let points = [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)];
+        let pieces = stroke_polyline(&points, &style(4.0, LineJoin::Miter, LineCap::Butt), false);
+        // Two bodies and one join
+        assert_eq!(pieces.len(), 3);
+        let (_, min_y, max_x, _) = bounds(&pieces);
+        assert!((max_x - 12.0).abs() < 1e-9);
+        assert!((min_y + 2.0).abs() < 1e-9);
+        assert!(pieces[2].iter().any(|&(x, y)| (x - 12.0).abs() < 1e-9 && (y + 2.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_sharp_miter_falls_back_to_bevel() {
+        // Nearly reversing direction exceeds the miter limit
+        let points = [(0.0, 0.0), (10.0, 0.0), (0.0, 1.0)];
+        let pieces = stroke_polyline(&points, &style(4.0, LineJoin::Miter, LineCap::Butt), false);
+        assert_eq!(pieces[2].len(), 3);
+    }
+
+    #[test]
+    fn test_closed_stroke_has_no_caps() {
+        let square = [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)];
+        let pieces = stroke_polyline(&square, &style(2.0, LineJoin::Bevel, LineCap::Round), true);
+        // Four bodies and four joins
+        assert_eq!(pieces.len(), 8);
+    }
+
+    #[test]
+    fn test_duplicate_points_are_ignored() {
+        let points = [(0.0, 0.0), (0.0, 0.0), (10.0, 0.0), (10.0, 0.0)];
+        let pieces = stroke_polyline(&points, &style(2.0, LineJoin::Miter, LineCap::Butt), false);
+        assert_eq!(pieces.len(), 1);
+    }
+
+    #[test]
+    fn test_style_adjustments_stay_in_range() {
+        let mut stroke = StrokeStyle::default();
+        assert!(stroke.is_hairline());
+        stroke.decrease_width();
+        assert_eq!(stroke.width, 1.0);
+        stroke.increase_width();
+        assert!(!stroke.is_hairline());
+
+        stroke.cycle_join();
+        assert_eq!(stroke.join, LineJoin::Round);
+        stroke.cycle_cap();
+        stroke.cycle_cap();
+        assert_eq!(stroke.cap, LineCap::Square);
+    }
+
+    #[test]
+    fn test_filled_stroke_covers_expected_pixels() {
+        let mut buffer = vec![0; 20 * 20];
+        let points = [(2.0, 10.0), (18.0, 10.0)];
+        let pieces = stroke_polyline(&points, &style(4.0, LineJoin::Miter, LineCap::Butt), false);
+        Raster::new(&mut buffer, 20, 20).fill_polygons(&pieces, [255, 255, 255], false);
+
+        for y in 8..12 {
+            assert_eq!(buffer[y * 20 + 10], 0xFFFFFF);
+        }
+        assert_eq!(buffer[7 * 20 + 10], 0);
+        assert_eq!(buffer[12 * 20 + 10], 0);
+        assert_eq!(buffer[10 * 20 + 1], 0);
+    }
+
+    #[test]
+    fn test_overlapping_pieces_are_not_blended_twice() {
+        let style = style(5.0, LineJoin::Round, LineCap::Round);
+        let single = stroke_polyline(&[(2.0, 10.3), (18.0, 10.3)], &style, false);
+        let split = stroke_polyline(&[(2.0, 10.3), (9.7, 10.3), (18.0, 10.3)], &style, false);
+
+        let mut expected = vec![0; 20 * 20];
+        let mut actual = vec![0; 20 * 20];
+        Raster::new(&mut expected, 20, 20).fill_polygons(&single, [200, 200, 200], true);
+        Raster::new(&mut actual, 20, 20).fill_polygons(&split, [200, 200, 200], true);
+
+        // The seam and the caps overlapping the bodies must not show up as brighter pixels
+        for (a, e) in actual.iter().zip(expected.iter()) {
+            assert!(((a >> 16) as i32 - (e >> 16) as i32).abs() <= 1);
+        }
+    }
+}
@@ -0,0 +1,23 @@
+// Refinement one level at a time, shared by curves (`Chaikin`) and meshes
+// (`SurfaceScheme`), so callers can ask either for a number of levels the same way
+pub trait Subdivider {
+    type Shape: Clone;
+
+    // One level of refinement
+    fn subdivide(&self, shape: &Self::Shape) -> Self::Shape;
+
+    // `shape` followed by `count` levels of refinement
+    fn levels(&self, shape: &Self::Shape, count: usize) -> Vec<Self::Shape> {
+        let mut levels = vec![shape.clone()];
+        for _ in 0..count {
+            let next = self.subdivide(&levels[levels.len() - 1]);
+            levels.push(next);
+        }
+        levels
+    }
+
+    // `shape` after `count` levels of refinement
+    fn subdivide_times(&self, shape: &Self::Shape, count: usize) -> Self::Shape {
+        (0..count).fold(shape.clone(), |shape, _| self.subdivide(&shape))
+    }
+}
@@ -0,0 +1,35 @@
+use crate::subdivider::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chaikin::Chaikin;
+    use crate::mesh::{Mesh, SurfaceScheme};
+    use crate::point::Point;
+    use nalgebra::Vector3;
+
+    #[test]
+    fn test_curves_refine_through_the_shared_interface() {
+        let points = vec![Point::new(0.0, 0.0), Point::new(100.0, 0.0), Point::new(100.0, 100.0)];
+        let chaikin = Chaikin::new(points.clone());
+        let levels = chaikin.levels(&points, 3);
+        assert_eq!(levels.len(), 4);
+        assert_eq!(levels[0], points);
+        assert_eq!(levels[1], chaikin.apply_chaikin(&points));
+        assert_eq!(chaikin.subdivide_times(&points, 3), levels[3]);
+        assert_eq!(chaikin.subdivide_times(&points, 0), points);
+    }
+
+    #[test]
+    fn test_meshes_refine_through_the_shared_interface() {
+        let vertices = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]
+            .iter()
+            .map(|&(x, y)| Point::from_vector(Vector3::new(x, y, 0.0)))
+            .collect();
+        let mesh = Mesh { vertices, faces: vec![vec![0, 1, 2]], creases: Vec::new() };
+        let levels = SurfaceScheme::Loop.levels(&mesh, 2);
+        assert_eq!(levels.iter().map(|m| m.faces.len()).collect::<Vec<_>>(), vec![1, 4, 16]);
+        assert_eq!(levels[1], mesh.loop_subdivision());
+        assert_eq!(SurfaceScheme::CatmullClark.subdivide(&mesh), mesh.catmull_clark());
+    }
+}
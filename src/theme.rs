@@ -0,0 +1,308 @@
+// Colour themes for the canvas, HUD and settings panel. Themes only choose colours:
+// what a point is (control point, curve point, selected, sharp) is always shown by
+// its marker's shape and size as well, so no theme can make two kinds look alike.
+use super::scene::CURVE_COLORS;
+use super::ui::UiColors;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub background: [u8; 3],
+    pub grid: [u8; 3],
+    // Every fourth grid line
+    pub grid_axis: [u8; 3],
+    // Points of curves that are being edited; `_inactive` is for unselected curves
+    pub point: [u8; 3],
+    pub point_inactive: [u8; 3],
+    // Control points and polygon of animating curves
+    pub control_point: [u8; 3],
+    pub control_point_inactive: [u8; 3],
+    pub polygon: [u8; 3],
+    pub polygon_inactive: [u8; 3],
+    // The point being dragged
+    pub drag_point: [u8; 3],
+    // Colours handed out to new curves in turn, with their names for the HUD
+    pub curves: Vec<([u8; 3], String)>,
+    // Ghost trail colours, one per subdivision level and repeating after the last
+    pub levels: Vec<[u8; 3]>,
+    // Cut points and the edges they are taken from
+    pub construction: [u8; 3],
+    pub fill: [u8; 3],
+    pub gradient_top: [u8; 3],
+    pub gradient_bottom: [u8; 3],
+    pub selection: [u8; 3],
+    pub selection_box: [u8; 3],
+    pub rubber_band: [u8; 3],
+    pub crease: [u8; 3],
+    pub text: [u8; 3],
+    pub message_background: [u8; 3],
+    pub hud_background: [u8; 3],
+    pub hud_text: [u8; 3],
+    pub help_key: [u8; 3],
+    pub ui: UiColors,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            name: "Dark".to_string(),
+            background: [0, 0, 0],
+            grid: [48, 48, 48],
+            grid_axis: [80, 80, 80],
+            point: [255, 165, 0],
+            point_inactive: [140, 95, 20],
+            control_point: [255, 0, 0],
+            control_point_inactive: [150, 0, 0],
+            polygon: [128, 0, 0],
+            polygon_inactive: [70, 0, 0],
+            drag_point: [255, 0, 0],
+            curves: CURVE_COLORS.iter().map(|&(color, name)| (color, name.to_string())).collect(),
+            levels: vec![[90, 110, 255], [0, 200, 120], [230, 200, 0], [255, 120, 0], [220, 60, 160], [0, 190, 230]],
+            construction: [200, 200, 200],
+            fill: [0, 90, 110],
+            gradient_top: [0, 120, 160],
+            gradient_bottom: [60, 20, 90],
+            selection: [255, 255, 255],
+            selection_box: [120, 120, 120],
+            rubber_band: [80, 140, 255],
+            crease: [255, 200, 0],
+            text: [255, 255, 255],
+            message_background: [32, 32, 32],
+            hud_background: [0, 0, 0],
+            hud_text: [192, 192, 192],
+            help_key: [255, 208, 128],
+            ui: UiColors::default(),
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            name: "Light".to_string(),
+            background: [245, 245, 240],
+            grid: [222, 222, 215],
+            grid_axis: [195, 195, 188],
+            point: [200, 100, 0],
+            point_inactive: [215, 175, 130],
+            control_point: [190, 20, 20],
+            control_point_inactive: [215, 140, 140],
+            polygon: [220, 120, 120],
+            polygon_inactive: [235, 200, 200],
+            drag_point: [190, 20, 20],
+            curves: vec![
+                ([0, 110, 160], "Blue".to_string()),
+                ([180, 110, 0], "Ochre".to_string()),
+                ([160, 0, 140], "Purple".to_string()),
+                ([20, 20, 20], "Black".to_string()),
+            ],
+            levels: vec![[40, 60, 200], [0, 140, 80], [170, 140, 0], [210, 90, 0], [170, 30, 120], [0, 130, 170]],
+            construction: [90, 90, 90],
+            fill: [180, 215, 225],
+            gradient_top: [150, 200, 230],
+            gradient_bottom: [215, 190, 230],
+            selection: [20, 20, 20],
+            selection_box: [140, 140, 140],
+            rubber_band: [40, 90, 220],
+            crease: [200, 120, 0],
+            text: [20, 20, 20],
+            message_background: [230, 230, 225],
+            hud_background: [255, 255, 255],
+            hud_text: [60, 60, 60],
+            help_key: [150, 80, 0],
+            ui: UiColors {
+                background: [235, 235, 230],
+                text: [30, 30, 30],
+                label: [100, 100, 100],
+                widget: [200, 200, 195],
+                hot: [180, 180, 175],
+                accent: [0, 120, 170],
+                highlight: [0, 0, 0],
+                shadow: [255, 255, 255],
+            },
+        }
+    }
+
+    // Black background with the Okabe-Ito palette, whose colours stay distinct for the
+    // common kinds of colour blindness
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "High contrast".to_string(),
+            background: [0, 0, 0],
+            grid: [60, 60, 60],
+            grid_axis: [110, 110, 110],
+            point: [230, 159, 0],
+            point_inactive: [150, 105, 0],
+            control_point: [213, 94, 0],
+            control_point_inactive: [140, 62, 0],
+            polygon: [170, 170, 170],
+            polygon_inactive: [90, 90, 90],
+            drag_point: [255, 255, 255],
+            curves: vec![
+                ([86, 180, 233], "Sky blue".to_string()),
+                ([240, 228, 66], "Yellow".to_string()),
+                ([0, 158, 115], "Green".to_string()),
+                ([204, 121, 167], "Pink".to_string()),
+            ],
+            levels: vec![[0, 114, 178], [0, 158, 115], [240, 228, 66], [230, 159, 0], [204, 121, 167], [86, 180, 233]],
+            construction: [255, 255, 255],
+            fill: [0, 70, 120],
+            gradient_top: [0, 114, 178],
+            gradient_bottom: [0, 40, 70],
+            selection: [255, 255, 255],
+            selection_box: [200, 200, 200],
+            rubber_band: [86, 180, 233],
+            crease: [240, 228, 66],
+            text: [255, 255, 255],
+            message_background: [0, 0, 0],
+            hud_background: [0, 0, 0],
+            hud_text: [255, 255, 255],
+            help_key: [240, 228, 66],
+            ui: UiColors {
+                background: [0, 0, 0],
+                text: [255, 255, 255],
+                label: [200, 200, 200],
+                widget: [90, 90, 90],
+                hot: [140, 140, 140],
+                accent: [240, 228, 66],
+                highlight: [255, 255, 255],
+                shadow: [0, 0, 0],
+            },
+        }
+    }
+
+    pub fn builtin() -> Vec<Self> {
+        vec![Self::dark(), Self::light(), Self::high_contrast()]
+    }
+
+    // Name of a curve colour, for colours from this theme
+    pub fn curve_name(&self, color: [u8; 3]) -> Option<&str> {
+        self.curves.iter().find(|(c, _)| *c == color).map(|(_, name)| name.as_str())
+    }
+
+    pub fn level_color(&self, level: usize) -> [u8; 3] {
+        self.levels.get(level % self.levels.len().max(1)).copied().unwrap_or(self.construction)
+    }
+
+    // Curve colours without their names
+    pub fn curve_colors(&self) -> Vec<[u8; 3]> {
+        self.curves.iter().map(|&(color, _)| color).collect()
+    }
+
+    // Parse a theme file: one `key = colour` per line, where a colour is `#RRGGBB` or
+    // `r, g, b`. Keys left out keep the dark theme's colour. Each `curve = colour name`
+    // line adds a curve colour, replacing the dark theme's list; `level` lines do the
+    // same for the ghost trail colours. `#` starts a comment at the beginning of a line.
+    pub fn parse(data: &str) -> Result<Self, String> {
+        let mut theme = Self::dark();
+        theme.name = "Custom".to_string();
+        let mut curves = Vec::new();
+        let mut levels = Vec::new();
+        for (line_no, line) in data.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("Expected `key = value` on line {}", line_no + 1));
+            };
+            let (key, value) = (key.trim(), value.trim());
+            match key {
+                "name" => theme.name = value.to_string(),
+                "curve" => {
+                    let (color, name) = parse_color_and_rest(value)
+                        .ok_or_else(|| format!("Invalid colour on line {}", line_no + 1))?;
+                    let name = if name.is_empty() { format!("Colour {}", curves.len() + 1) } else { name.to_string() };
+                    curves.push((color, name));
+                }
+                "level" => match parse_color_and_rest(value) {
+                    Some((color, "")) => levels.push(color),
+                    _ => return Err(format!("Invalid colour on line {}", line_no + 1)),
+                },
+                _ => {
+                    let slot = theme
+                        .color_mut(key)
+                        .ok_or_else(|| format!("Unknown theme key '{}' on line {}", key, line_no + 1))?;
+                    *slot = match parse_color_and_rest(value) {
+                        Some((color, "")) => color,
+                        _ => return Err(format!("Invalid colour on line {}", line_no + 1)),
+                    };
+                }
+            }
+        }
+        if !curves.is_empty() {
+            theme.curves = curves;
+        }
+        if !levels.is_empty() {
+            theme.levels = levels;
+        }
+        Ok(theme)
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let data = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        Self::parse(&data)
+    }
+
+    fn color_mut(&mut self, key: &str) -> Option<&mut [u8; 3]> {
+        Some(match key {
+            "background" => &mut self.background,
+            "grid" => &mut self.grid,
+            "grid_axis" => &mut self.grid_axis,
+            "point" => &mut self.point,
+            "point_inactive" => &mut self.point_inactive,
+            "control_point" => &mut self.control_point,
+            "control_point_inactive" => &mut self.control_point_inactive,
+            "polygon" => &mut self.polygon,
+            "polygon_inactive" => &mut self.polygon_inactive,
+            "drag_point" => &mut self.drag_point,
+            "fill" => &mut self.fill,
+            "gradient_top" => &mut self.gradient_top,
+            "gradient_bottom" => &mut self.gradient_bottom,
+            "selection" => &mut self.selection,
+            "selection_box" => &mut self.selection_box,
+            "rubber_band" => &mut self.rubber_band,
+            "crease" => &mut self.crease,
+            "construction" => &mut self.construction,
+            "text" => &mut self.text,
+            "message_background" => &mut self.message_background,
+            "hud_background" => &mut self.hud_background,
+            "hud_text" => &mut self.hud_text,
+            "help_key" => &mut self.help_key,
+            "panel_background" => &mut self.ui.background,
+            "panel_text" => &mut self.ui.text,
+            "panel_label" => &mut self.ui.label,
+            "panel_widget" => &mut self.ui.widget,
+            "panel_hot" => &mut self.ui.hot,
+            "panel_accent" => &mut self.ui.accent,
+            "panel_highlight" => &mut self.ui.highlight,
+            "panel_shadow" => &mut self.ui.shadow,
+            _ => return None,
+        })
+    }
+}
+
+// A colour at the start of `value`, followed by whatever text is left
+fn parse_color_and_rest(value: &str) -> Option<([u8; 3], &str)> {
+    if let Some(hex) = value.strip_prefix('#') {
+        let digits = hex.get(..6).filter(|d| d.is_ascii())?;
+        let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).ok();
+        let rest = &hex[6..];
+        if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        return Some(([channel(0)?, channel(2)?, channel(4)?], rest.trim()));
+    }
+
+    let mut parts = value.splitn(3, ',');
+    let r = parts.next()?.trim().parse().ok()?;
+    let g = parts.next()?.trim().parse().ok()?;
+    let last = parts.next()?.trim();
+    let end = last.find(char::is_whitespace).unwrap_or(last.len());
+    let b = last[..end].parse().ok()?;
+    Some(([r, g, b], last[end..].trim()))
+}
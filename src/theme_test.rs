@@ -0,0 +1,86 @@
+use crate::theme::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::InputHandler;
+    use crate::scene::CURVE_COLORS;
+
+    #[test]
+    fn test_builtin_themes_are_distinct_and_named() {
+        let themes = Theme::builtin();
+        assert_eq!(themes.len(), 3);
+        assert_eq!(themes[0], Theme::default());
+        for (i, a) in themes.iter().enumerate() {
+            assert!(!a.curves.is_empty());
+            for b in &themes[i + 1..] {
+                assert_ne!(a.name, b.name);
+                assert_ne!(a.curves, b.curves);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_overrides_keys_and_keeps_the_rest() {
+        let data = "# A custom theme\nname = Paper\nbackground = #F0E8D8\n\npanel_accent = 10, 20, 30\n";
+        let theme = Theme::parse(data).unwrap();
+        assert_eq!(theme.name, "Paper");
+        assert_eq!(theme.background, [0xF0, 0xE8, 0xD8]);
+        assert_eq!(theme.ui.accent, [10, 20, 30]);
+        assert_eq!(theme.point, Theme::dark().point);
+        assert_eq!(theme.curves, Theme::dark().curves);
+    }
+
+    #[test]
+    fn test_parse_curve_lines_replace_palette() {
+        let theme = Theme::parse("curve = #112233 Deep blue\ncurve = 1,2,3\nlevel = #000001").unwrap();
+        assert_eq!(theme.curve_name([1, 2, 3]), Some("Colour 2"));
+        assert_eq!(theme.curve_name([9, 9, 9]), None);
+        assert_eq!(theme.level_color(0), [0, 0, 1]);
+        assert_eq!(theme.level_color(5), [0, 0, 1]);
+        assert_eq!(
+            theme.curves,
+            vec![([0x11, 0x22, 0x33], "Deep blue".to_string()), ([1, 2, 3], "Colour 2".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_bundled_theme_file_loads() {
+        let theme = Theme::load("assets/themes/solarized.theme").unwrap();
+        assert_eq!(theme.name, "Solarized");
+        assert_eq!(theme.curves.len(), 4);
+        assert!(Theme::load("assets/themes/missing.theme").is_err());
+    }
+
+    #[test]
+    fn test_parse_reports_errors_with_line_numbers() {
+        assert_eq!(Theme::parse("\nbackground").unwrap_err(), "Expected `key = value` on line 2");
+        assert_eq!(Theme::parse("sky = #000000").unwrap_err(), "Unknown theme key 'sky' on line 1");
+        assert!(Theme::parse("grid = #12345").is_err());
+        assert!(Theme::parse("grid = 1, 2, 300").is_err());
+        assert!(Theme::parse("grid = #123456 extra").is_err());
+    }
+
+    #[test]
+    fn test_switching_theme_recolours_curves() {
+        let mut handler = InputHandler::new();
+        handler.add_point(0.0, 0.0);
+        handler.scene.new_curve();
+        handler.add_point(10.0, 10.0);
+        handler.scene.selected_mut().color = [1, 2, 3];
+        assert_eq!(handler.scene.curves[0].color, CURVE_COLORS[0].0);
+
+        handler.cycle_theme();
+        let light = Theme::light();
+        assert_eq!(handler.theme(), &light);
+        assert_eq!(handler.scene.curves[0].color, light.curves[0].0);
+        // Colours picked outside the palette are left alone
+        assert_eq!(handler.scene.curves[1].color, [1, 2, 3]);
+        handler.scene.new_curve();
+        assert_eq!(handler.scene.selected().color, light.curves[2].0);
+
+        handler.add_theme(Theme::parse("curve = #010101").unwrap());
+        assert_eq!(handler.theme, 3);
+        assert_eq!(handler.scene.curves[0].color, [1, 1, 1]);
+    }
+}
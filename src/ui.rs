@@ -0,0 +1,309 @@
+// Immediate-mode widgets drawn straight into the software framebuffer. Widgets are
+// declared every frame; the only state kept between frames is in `UiState`.
+
+use super::font::BitmapFont;
+use super::raster::Raster;
+
+const ROW_HEIGHT: i32 = 20;
+const PADDING: i32 = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UiColors {
+    pub background: [u8; 3],
+    pub text: [u8; 3],
+    pub label: [u8; 3],
+    pub widget: [u8; 3],
+    // Widgets under the mouse
+    pub hot: [u8; 3],
+    // Checked boxes, chosen options and filled slider tracks
+    pub accent: [u8; 3],
+    // Outlines around the chosen swatch and a grabbed slider knob
+    pub highlight: [u8; 3],
+    pub shadow: [u8; 3],
+}
+
+impl Default for UiColors {
+    fn default() -> Self {
+        Self {
+            background: [20, 20, 20],
+            text: [220, 220, 220],
+            label: [150, 150, 150],
+            widget: [70, 70, 70],
+            hot: [100, 100, 100],
+            accent: [0, 180, 200],
+            highlight: [255, 255, 255],
+            shadow: [0, 0, 0],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct UiInput {
+    pub mouse_pos: (f64, f64),
+    pub mouse_down: bool,
+    // True only on the frame the button went down
+    pub mouse_pressed: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UiState {
+    // Widget holding the mouse, e.g. a slider being dragged
+    pub active: Option<u64>,
+    pub panel_open: bool,
+    // Screen area covered by the UI last frame, so clicks there don't reach the canvas
+    pub rect: Option<(i32, i32, i32, i32)>,
+}
+
+fn widget_id(label: &str) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    label.hash(&mut hasher);
+    hasher.finish()
+}
+
+pub struct Ui<'a> {
+    raster: Raster<'a>,
+    font: &'a BitmapFont,
+    input: UiInput,
+    state: &'a mut UiState,
+    colors: UiColors,
+    x: i32,
+    y: i32,
+    width: i32,
+}
+
+impl<'a> Ui<'a> {
+    pub fn new(raster: Raster<'a>, font: &'a BitmapFont, input: UiInput, state: &'a mut UiState) -> Self {
+        if !input.mouse_down {
+            state.active = None;
+        }
+        state.rect = None;
+        Self { raster, font, input, state, colors: UiColors::default(), x: 0, y: 0, width: 0 }
+    }
+
+    pub fn set_colors(&mut self, colors: UiColors) {
+        self.colors = colors;
+    }
+
+    fn hovered(&self, x: i32, y: i32, w: i32, h: i32) -> bool {
+        let (mx, my) = self.input.mouse_pos;
+        mx >= x as f64 && mx < (x + w) as f64 && my >= y as f64 && my < (y + h) as f64
+    }
+
+    // Click on a rectangle, reported on the frame the button goes down
+    fn clicked(&mut self, id: u64, x: i32, y: i32, w: i32, h: i32) -> bool {
+        if self.input.mouse_pressed && self.state.active.is_none() && self.hovered(x, y, w, h) {
+            self.state.active = Some(id);
+            return true;
+        }
+        false
+    }
+
+    fn text(&mut self, x: i32, y: i32, text: &str, color: [u8; 3]) {
+        let raster = &mut self.raster;
+        self.font.render(x, y, text, 1, |px, py| raster.set_pixel(px, py, color));
+    }
+
+    fn outline(&mut self, x: i32, y: i32, w: i32, h: i32, color: [u8; 3]) {
+        for px in x..x + w {
+            self.raster.set_pixel(px, y, color);
+            self.raster.set_pixel(px, y + h - 1, color);
+        }
+        for py in y..y + h {
+            self.raster.set_pixel(x, py, color);
+            self.raster.set_pixel(x + w - 1, py, color);
+        }
+    }
+
+    // Text baseline offset that centres a line of text in a row
+    fn text_offset(&self, height: i32) -> i32 {
+        (height - self.font.line_height(1) as i32) / 2
+    }
+
+    // Collapsible panel on the right edge of the screen. `contents` is only run while the
+    // panel is open. Returns whether the panel is open.
+    pub fn side_panel<F: FnOnce(&mut Self)>(&mut self, title: &str, width: i32, contents: F) -> bool {
+        let screen_w = self.raster.width as i32;
+        let screen_h = self.raster.height as i32;
+        let header_h = ROW_HEIGHT + 4;
+        let collapsed_w = self.font.text_width(title, 1) as i32 + 2 * PADDING + 16;
+
+        // The header acts as the collapse toggle
+        let header_w = if self.state.panel_open { width } else { collapsed_w };
+        if self.clicked(widget_id(title), screen_w - header_w, 0, header_w, header_h) {
+            self.state.panel_open = !self.state.panel_open;
+        }
+
+        let header_w = if self.state.panel_open { width } else { collapsed_w };
+        let header_x = screen_w - header_w;
+        if self.state.panel_open {
+            self.raster.fill_rect(header_x, 0, width, screen_h, self.colors.background, 0.85);
+            self.state.rect = Some((header_x, 0, width, screen_h));
+        } else {
+            self.state.rect = Some((header_x, 0, header_w, header_h));
+        }
+
+        let fill = if self.hovered(header_x, 0, header_w, header_h) { self.colors.hot } else { self.colors.widget };
+        self.raster.fill_rect(header_x, 0, header_w, header_h, fill, 1.0);
+        let marker = if self.state.panel_open { "-" } else { "+" };
+        let offset = self.text_offset(header_h);
+        self.text(header_x + PADDING, offset, marker, self.colors.accent);
+        self.text(header_x + PADDING + 16, offset, title, self.colors.text);
+
+        if self.state.panel_open {
+            self.x = header_x + PADDING;
+            self.y = header_h + PADDING;
+            self.width = width - 2 * PADDING;
+            contents(self);
+        }
+        self.state.panel_open
+    }
+
+    pub fn label(&mut self, text: &str) {
+        let offset = self.text_offset(ROW_HEIGHT);
+        let (x, y) = (self.x, self.y);
+        self.text(x, y + offset, text, self.colors.label);
+        self.y += ROW_HEIGHT;
+    }
+
+    pub fn separator(&mut self) {
+        let (x, y, w) = (self.x, self.y + PADDING / 2, self.width);
+        self.raster.fill_rect(x, y, w, 1, self.colors.widget, 1.0);
+        self.y += PADDING;
+    }
+
+    pub fn button(&mut self, label: &str) -> bool {
+        let (x, y, w, h) = (self.x, self.y, self.width, ROW_HEIGHT);
+        let clicked = self.clicked(widget_id(label), x, y, w, h);
+
+        let fill = if self.hovered(x, y, w, h) { self.colors.hot } else { self.colors.widget };
+        self.raster.fill_rect(x, y, w, h, fill, 1.0);
+        let text_w = self.font.text_width(label, 1) as i32;
+        let offset = self.text_offset(h);
+        self.text(x + (w - text_w) / 2, y + offset, label, self.colors.text);
+
+        self.y += h + 4;
+        clicked
+    }
+
+    // Returns true when the value was toggled this frame
+    pub fn checkbox(&mut self, label: &str, value: &mut bool) -> bool {
+        let (x, y, h) = (self.x, self.y, ROW_HEIGHT);
+        let changed = self.clicked(widget_id(label), x, y, self.width, h);
+        if changed {
+            *value = !*value;
+        }
+
+        let box_size = 12;
+        let box_y = y + (h - box_size) / 2;
+        self.raster.fill_rect(x, box_y, box_size, box_size, self.colors.widget, 1.0);
+        if *value {
+            self.raster.fill_rect(x + 3, box_y + 3, box_size - 6, box_size - 6, self.colors.accent, 1.0);
+        }
+        let offset = self.text_offset(h);
+        self.text(x + box_size + 6, y + offset, label, self.colors.text);
+
+        self.y += h;
+        changed
+    }
+
+    // One option per row; returns true when the selection changed this frame
+    pub fn radio_group<T: Copy + PartialEq>(&mut self, label: &str, options: &[(T, &str)], value: &mut T) -> bool {
+        self.label(label);
+        let mut changed = false;
+        for (option, name) in options {
+            let (x, y, h) = (self.x, self.y, ROW_HEIGHT);
+            let id = widget_id(&format!("{}/{}", label, name));
+            if self.clicked(id, x, y, self.width, h) && *value != *option {
+                *value = *option;
+                changed = true;
+            }
+
+            let dot = 10;
+            let dot_y = y + (h - dot) / 2;
+            let centre = (x as f64 + dot as f64 / 2.0, dot_y as f64 + dot as f64 / 2.0);
+            self.raster.draw_disc_aa(centre.0, centre.1, dot as f64 / 2.0, self.colors.widget);
+            if *value == *option {
+                self.raster.draw_disc_aa(centre.0, centre.1, dot as f64 / 2.0 - 2.5, self.colors.accent);
+            }
+            let offset = self.text_offset(h);
+            self.text(x + dot + 6, y + offset, name, self.colors.text);
+            self.y += h;
+        }
+        changed
+    }
+
+    // A row of colour swatches; returns true when a different colour was picked
+    pub fn swatches(&mut self, label: &str, colors: &[[u8; 3]], value: &mut [u8; 3]) -> bool {
+        self.label(label);
+        let (x, y, size, gap) = (self.x, self.y, 16, 4);
+        let mut changed = false;
+        for (i, &color) in colors.iter().enumerate() {
+            let sx = x + i as i32 * (size + gap);
+            let id = widget_id(&format!("{}/{}", label, i));
+            if self.clicked(id, sx, y, size, size) && *value != color {
+                *value = color;
+                changed = true;
+            }
+
+            self.raster.fill_rect(sx, y, size, size, color, 1.0);
+            // The current colour gets a double outline so it doesn't rely on the colour itself
+            if *value == color {
+                self.outline(sx - 2, y - 2, size + 4, size + 4, self.colors.highlight);
+                self.outline(sx - 1, y - 1, size + 2, size + 2, self.colors.shadow);
+            } else {
+                self.outline(sx, y, size, size, self.colors.widget);
+            }
+        }
+        self.y += size + PADDING;
+        changed
+    }
+
+    // Horizontal slider; returns true when the value changed this frame
+    pub fn slider(&mut self, label: &str, value: &mut f64, min: f64, max: f64, decimals: usize) -> bool {
+        let caption = format!("{}: {:.*}", label, decimals, *value);
+        self.label(&caption);
+
+        let (x, y, w, h) = (self.x, self.y, self.width, 12);
+        let id = widget_id(label);
+        self.clicked(id, x, y, w, h);
+
+        let mut changed = false;
+        if self.state.active == Some(id) && self.input.mouse_down && max > min {
+            let t = ((self.input.mouse_pos.0 - x as f64) / w as f64).clamp(0.0, 1.0);
+            let new_value = min + t * (max - min);
+            let new_value = if decimals == 0 { new_value.round() } else { new_value };
+            if new_value != *value {
+                *value = new_value;
+                changed = true;
+            }
+        }
+
+        let t = if max > min { ((*value - min) / (max - min)).clamp(0.0, 1.0) } else { 0.0 };
+        self.raster.fill_rect(x, y + h / 2 - 2, w, 4, self.colors.widget, 1.0);
+        self.raster.fill_rect(x, y + h / 2 - 2, (t * w as f64) as i32, 4, self.colors.accent, 1.0);
+        let knob_x = x + (t * (w - 8) as f64) as i32;
+        let knob_color = if self.state.active == Some(id) || self.hovered(x, y, w, h) { self.colors.highlight } else { self.colors.text };
+        self.raster.fill_rect(knob_x, y, 8, h, knob_color, 1.0);
+        self.outline(knob_x, y, 8, h, self.colors.widget);
+
+        self.y += h + PADDING;
+        changed
+    }
+
+    // Integer variant of `slider`
+    pub fn slider_usize(&mut self, label: &str, value: &mut usize, min: usize, max: usize) -> bool {
+        let mut v = *value as f64;
+        let changed = self.slider(label, &mut v, min as f64, max as f64, 0);
+        *value = v as usize;
+        changed
+    }
+}
+
+// Whether a point falls inside the area the UI covered last frame
+pub fn covers(state: &UiState, pos: (f64, f64)) -> bool {
+    match state.rect {
+        Some((x, y, w, h)) => pos.0 >= x as f64 && pos.0 < (x + w) as f64 && pos.1 >= y as f64 && pos.1 < (y + h) as f64,
+        None => false,
+    }
+}
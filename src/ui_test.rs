@@ -0,0 +1,162 @@
+use crate::ui::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::font::BitmapFont;
+    use crate::raster::Raster;
+
+    const WIDTH: usize = 400;
+    const HEIGHT: usize = 300;
+    const PANEL_WIDTH: i32 = 200;
+
+    fn press(x: f64, y: f64) -> UiInput {
+        UiInput { mouse_pos: (x, y), mouse_down: true, mouse_pressed: true }
+    }
+
+    fn hold(x: f64, y: f64) -> UiInput {
+        UiInput { mouse_pos: (x, y), mouse_down: true, mouse_pressed: false }
+    }
+
+    fn idle() -> UiInput {
+        UiInput::default()
+    }
+
+    // Run one frame of an open panel, letting `contents` declare the widgets
+    fn frame<F: FnOnce(&mut Ui)>(state: &mut UiState, input: UiInput, contents: F) {
+        let font = BitmapFont::builtin();
+        let mut buffer = vec![0; WIDTH * HEIGHT];
+        let mut ui = Ui::new(Raster::new(&mut buffer, WIDTH, HEIGHT), &font, input, state);
+        ui.side_panel("Settings", PANEL_WIDTH, contents);
+    }
+
+    fn open_state() -> UiState {
+        UiState { panel_open: true, ..UiState::default() }
+    }
+
+    // Widgets start below the 24 px header plus 8 px padding, 8 px in from the panel edge
+    const LEFT: f64 = (WIDTH as i32 - PANEL_WIDTH + 8) as f64;
+    const TOP: f64 = 32.0;
+
+    #[test]
+    fn test_panel_header_toggles_and_reports_rect() {
+        let mut state = UiState::default();
+        frame(&mut state, idle(), |_| {});
+        let (x, y, w, h) = state.rect.unwrap();
+        // Collapsed, only the header is covered
+        assert!(w < PANEL_WIDTH && h < HEIGHT as i32 && y == 0 && x + w == WIDTH as i32);
+        assert!(!state.panel_open);
+
+        frame(&mut state, press(WIDTH as f64 - 5.0, 5.0), |_| {});
+        assert!(state.panel_open);
+        assert_eq!(state.rect, Some((WIDTH as i32 - PANEL_WIDTH, 0, PANEL_WIDTH, HEIGHT as i32)));
+    }
+
+    #[test]
+    fn test_contents_only_run_while_open() {
+        let mut state = UiState::default();
+        let mut ran = false;
+        frame(&mut state, idle(), |_| ran = true);
+        assert!(!ran);
+
+        let mut state = open_state();
+        frame(&mut state, idle(), |_| ran = true);
+        assert!(ran);
+    }
+
+    #[test]
+    fn test_button_clicks_only_on_press() {
+        let mut state = open_state();
+        let mut clicked = false;
+        frame(&mut state, press(LEFT + 10.0, TOP + 5.0), |ui| clicked = ui.button("Go"));
+        assert!(clicked);
+
+        // Holding the button does not click again
+        frame(&mut state, hold(LEFT + 10.0, TOP + 5.0), |ui| clicked = ui.button("Go"));
+        assert!(!clicked);
+
+        // Pressing outside does nothing
+        let mut state = open_state();
+        frame(&mut state, press(LEFT + 10.0, TOP + 100.0), |ui| clicked = ui.button("Go"));
+        assert!(!clicked);
+    }
+
+    #[test]
+    fn test_checkbox_toggles_value() {
+        let mut state = open_state();
+        let mut value = false;
+        frame(&mut state, press(LEFT + 5.0, TOP + 5.0), |ui| {
+            assert!(ui.checkbox("Grid", &mut value));
+        });
+        assert!(value);
+    }
+
+    #[test]
+    fn test_radio_group_selects_clicked_option() {
+        let mut state = open_state();
+        let options = [(1, "One"), (2, "Two"), (3, "Three")];
+        let mut value = 1;
+        // The group label takes the first row, options follow one per row
+        frame(&mut state, press(LEFT + 5.0, TOP + 20.0 * 3.0 + 5.0), |ui| {
+            assert!(ui.radio_group("Number", &options, &mut value));
+        });
+        assert_eq!(value, 3);
+    }
+
+    #[test]
+    fn test_slider_follows_drag_and_clamps() {
+        let mut state = open_state();
+        let mut value = 0.0;
+        let track_width = (PANEL_WIDTH - 16) as f64;
+        // The track sits under the caption row
+        let track_y = TOP + 20.0 + 5.0;
+
+        frame(&mut state, press(LEFT + track_width / 2.0, track_y), |ui| {
+            ui.slider("Speed", &mut value, 0.0, 10.0, 1);
+        });
+        assert!((value - 5.0).abs() < 1e-9);
+
+        // Dragging keeps control even when the cursor leaves the track
+        frame(&mut state, hold(LEFT + track_width * 4.0, track_y + 200.0), |ui| {
+            ui.slider("Speed", &mut value, 0.0, 10.0, 1);
+        });
+        assert_eq!(value, 10.0);
+
+        // Releasing ends the drag
+        frame(&mut state, idle(), |ui| {
+            ui.slider("Speed", &mut value, 0.0, 10.0, 1);
+        });
+        assert_eq!(state.active, None);
+    }
+
+    #[test]
+    fn test_integer_slider_rounds() {
+        let mut state = open_state();
+        let mut steps = 1;
+        let track_width = (PANEL_WIDTH - 16) as f64;
+        frame(&mut state, press(LEFT + track_width * 0.52, TOP + 25.0), |ui| {
+            ui.slider_usize("Iterations", &mut steps, 1, 12);
+        });
+        assert_eq!(steps, 7);
+    }
+
+    #[test]
+    fn test_swatches_pick_clicked_colour() {
+        let mut state = open_state();
+        let colors = [[255, 0, 0], [0, 255, 0], [0, 0, 255]];
+        let mut value = colors[0];
+        // Swatches are 16 px wide with a 4 px gap, in the row under the caption
+        frame(&mut state, press(LEFT + 2.0 * 20.0 + 8.0, TOP + 20.0 + 8.0), |ui| {
+            assert!(ui.swatches("Paint", &colors, &mut value));
+        });
+        assert_eq!(value, colors[2]);
+    }
+
+    #[test]
+    fn test_covers_uses_last_rect() {
+        let state = UiState { rect: Some((10, 10, 20, 20)), ..UiState::default() };
+        assert!(covers(&state, (15.0, 15.0)));
+        assert!(!covers(&state, (35.0, 15.0)));
+        assert!(!covers(&UiState::default(), (15.0, 15.0)));
+    }
+}